@@ -0,0 +1,142 @@
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::Context;
+use clap::Args;
+use radius_log_core::{detect_attacks, filter_and_sort, DetectionSettings, Finding, RadiusRequest, ReasonCategories, SortSpec};
+use serde_json::Value;
+
+use crate::follow::{human_line, json_line};
+use crate::input::{self, TimeArgs};
+use crate::query::FilterArgs;
+
+#[derive(Debug, Args)]
+pub struct DetectArgs {
+    /// Log file, or folder of .log files (repeatable)
+    #[arg(short, long = "file", value_name = "PATH", required = true)]
+    pub files: Vec<PathBuf>,
+    #[command(flatten)]
+    pub filter: FilterArgs,
+    #[command(flatten)]
+    pub time: TimeArgs,
+    /// Window the failures are counted in, in seconds
+    #[arg(long, value_name = "SECS", default_value_t = DetectionSettings::default().window_secs)]
+    pub window: u32,
+    /// Distinct users one MAC, access point or client IP must fail against to be a password spray
+    #[arg(long, value_name = "N", default_value_t = DetectionSettings::default().spray_users)]
+    pub spray_users: usize,
+    /// Failures of one user to be a brute force
+    #[arg(long, value_name = "N", default_value_t = DetectionSettings::default().brute_force_failures)]
+    pub brute_force: usize,
+    /// One JSON array of findings, each with its requests
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `radius-log detect`.
+///
+/// # Errors
+/// Fails on an invalid query, an unreadable input or an output error.
+pub fn run(args: &DetectArgs) -> anyhow::Result<ExitCode> {
+    let query = args.filter.query()?;
+    let (items, report) = input::load(&args.files, &args.time.settings())?;
+    let ids = filter_and_sort(&items, &query, args.filter.errors().as_ref(), &SortSpec::default());
+
+    let settings = DetectionSettings {
+        window_secs: args.window,
+        spray_users: args.spray_users,
+        brute_force_failures: args.brute_force,
+        ..Default::default()
+    };
+    let categories = ReasonCategories::default();
+    let findings = detect_attacks(&items, &ids, &categories, &settings);
+
+    let text = if args.json {
+        let findings: Vec<Value> = findings.iter().map(|finding| finding_json(finding, &items, &categories)).collect();
+        serde_json::to_string_pretty(&findings)? + "\n"
+    } else {
+        findings.iter().map(|finding| finding_text(finding, &items)).collect::<Vec<_>>().join("\n")
+    };
+    match io::stdout().lock().write_all(text.as_bytes()) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        other => other.context("cannot write the output")?,
+    }
+    Ok(input::exit_status(&report))
+}
+
+// Summary line, the search query showing it in the GUI, then its requests
+fn finding_text(finding: &Finding, items: &[RadiusRequest]) -> String {
+    let mut out = format!(
+        "{} {}={}: {} failures, {} users, {} - {}\n  search: {}\n",
+        finding.kind.name(),
+        finding.source.field(),
+        finding.value,
+        finding.failures,
+        finding.users,
+        finding.first.to_rfc3339(),
+        finding.last.to_rfc3339(),
+        finding.query()
+    );
+    for req in finding.rows.iter().filter_map(|&i| items.get(i)) {
+        out.push_str("  ");
+        out.push_str(&human_line(req));
+        out.push('\n');
+    }
+    out
+}
+
+// The finding's fields, its search query, and its requests in place of the row numbers
+fn finding_json(finding: &Finding, items: &[RadiusRequest], categories: &ReasonCategories) -> Value {
+    let mut value = serde_json::to_value(finding).unwrap_or_default();
+    if let Value::Object(object) = &mut value {
+        object.remove("rows");
+        object.insert("query".to_string(), finding.query().into());
+        let requests = finding.rows.iter().filter_map(|&i| items.get(i)).map(|req| json_line(req, categories)).collect();
+        object.insert("requests".to_string(), Value::Array(requests));
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cli, Command};
+    use clap::Parser;
+    use radius_log_core::{parse_nps_timestamp, AttackKind, Source, Zone};
+
+    #[test]
+    fn parses_arguments() {
+        let cli = Cli::try_parse_from(["radius-log", "detect", "-f", "logs", "--window", "300", "--spray-users", "5", "--json"]).expect("valid arguments");
+        let Command::Detect(args) = cli.command else { panic!("detect command") };
+        assert_eq!((args.window, args.spray_users, args.brute_force, args.json), (300, 5, 10, true));
+    }
+
+    #[test]
+    fn formats_findings_with_their_requests() {
+        let items = vec![
+            RadiusRequest { user: "alice".to_string(), ..Default::default() },
+            RadiusRequest { user: "CORP\\jdoe".to_string(), resp_type: "Access-Reject".to_string(), ..Default::default() },
+        ];
+        let time = parse_nps_timestamp("01/15/2024 08:00:00").and_then(|t| Zone::Utc.resolve(t)).expect("timestamp");
+        let finding = Finding {
+            kind: AttackKind::BruteForce,
+            source: Source::User,
+            value: "CORP\\jdoe".to_string(),
+            first: time,
+            last: time,
+            failures: 1,
+            users: 1,
+            rows: vec![1],
+            codes: vec![0],
+        };
+
+        let text = finding_text(&finding, &items);
+        assert!(text.starts_with("brute_force user=CORP\\jdoe: 1 failures, 1 users, 2024-01-15T08:00:00+00:00 - "));
+        assert_eq!(text.lines().count(), 3);
+        let json = finding_json(&finding, &items, &ReasonCategories::default());
+        assert_eq!((json["kind"].as_str(), json["query"].as_str()), (Some("brute_force"), Some("user=\"CORP\\\\jdoe\" (response=Access-Reject NOT code>0)")));
+        assert_eq!(json["requests"][0]["user"], "CORP\\jdoe");
+        assert!(json.get("rows").is_none());
+    }
+}
@@ -0,0 +1,125 @@
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::{Args, ValueEnum};
+use radius_log_core::{LogColumn, LogFollower, RadiusRequest, ReasonCategories, ReasonCategory};
+use serde_json::{Map, Value};
+
+use crate::input::TimeArgs;
+use crate::query::FilterArgs;
+
+#[derive(Debug, Args)]
+pub struct FollowArgs {
+    /// Log file, or folder of .log files (follows the newest one across the daily rotation)
+    #[arg(short, long = "file", value_name = "PATH")]
+    pub file: PathBuf,
+    #[command(flatten)]
+    pub filter: FilterArgs,
+    #[command(flatten)]
+    pub time: TimeArgs,
+    #[arg(long, value_enum, default_value_t = LineFormat::Human)]
+    pub format: LineFormat,
+    /// Print the requests already in the file first
+    #[arg(long)]
+    pub from_start: bool,
+    /// Milliseconds between two reads of the file
+    #[arg(long, value_name = "MS", default_value_t = 1000)]
+    pub interval: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LineFormat {
+    /// One aligned line per request
+    Human,
+    /// One JSON object per line
+    Json,
+}
+
+/// `radius-log follow`: runs until interrupted, or until the output is closed.
+///
+/// # Errors
+/// Fails on an invalid query, or if the file or folder cannot be read at start.
+pub fn run(args: &FollowArgs) -> anyhow::Result<ExitCode> {
+    let query = args.filter.query()?;
+    let errors = args.filter.errors();
+    let categories = ReasonCategories::default();
+
+    let mut follower = LogFollower::open(&args.file, args.time.settings(), args.from_start)?;
+    if let Some(file) = follower.current_file() {
+        eprintln!("radius-log: following {}", file.display());
+    }
+
+    let mut out = io::stdout().lock();
+    loop {
+        // A file being rotated or briefly locked is read again on the next round
+        match follower.poll() {
+            Ok(update) => {
+                if let Some(file) = &update.switched_to {
+                    eprintln!("radius-log: following {}", file.display());
+                }
+                for skipped in &update.report.skipped {
+                    eprintln!("radius-log: {} @ {}: {}", skipped.file, skipped.offset, skipped.error);
+                }
+                // Errors-only keeps the failed requests themselves: their sessions are still being logged
+                let written = update
+                    .completed
+                    .iter()
+                    .filter(|req| query.matches(req) && errors.as_ref().is_none_or(|errors| errors.targets(req)))
+                    .try_for_each(|req| match args.format {
+                        LineFormat::Human => writeln!(out, "{}", human_line(req)),
+                        LineFormat::Json => writeln!(out, "{}", json_line(req, &categories)),
+                    })
+                    .and_then(|()| out.flush());
+                // `radius-log follow ... | head` ends the follow
+                if let Err(e) = written {
+                    return if e.kind() == io::ErrorKind::BrokenPipe { Ok(ExitCode::SUCCESS) } else { Err(e.into()) };
+                }
+            }
+            Err(e) => eprintln!("radius-log: {e:#}"),
+        }
+        std::thread::sleep(Duration::from_millis(args.interval));
+    }
+}
+
+pub fn human_line(req: &RadiusRequest) -> String {
+    let outcome = req.resp_type.strip_prefix("Access-").unwrap_or(&req.resp_type);
+    format!(
+        "{}  {:<19}  {}  mac={}  ap={} ({})  server={}  {}",
+        req.timestamp, outcome, req.user, req.mac, req.ap_name, req.ap_ip, req.server, req.reason
+    )
+}
+
+// Every column under its short name, the numeric reason code and its category
+pub fn json_line(req: &RadiusRequest, categories: &ReasonCategories) -> Value {
+    let mut object: Map<String, Value> =
+        LogColumn::all().into_iter().map(|col| (col.name().to_string(), req.column_text(col).into())).collect();
+    object.insert("reason_code".to_string(), req.reason_code.into());
+    object.insert("category".to_string(), categories.classify(req).map(ReasonCategory::name).into());
+    Value::Object(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_lines() {
+        let req = RadiusRequest {
+            timestamp: "01/15/2024 08:00:00.456".to_string(),
+            user: "CORP\\jdoe".to_string(),
+            resp_type: "Access-Reject".to_string(),
+            reason: "Authentication failed (16)".to_string(),
+            reason_code: Some(16),
+            ..Default::default()
+        };
+
+        assert!(human_line(&req).starts_with("01/15/2024 08:00:00.456  Reject               CORP\\jdoe  mac="));
+        let json = json_line(&req, &ReasonCategories::default());
+        assert_eq!(json["user"], "CORP\\jdoe");
+        assert_eq!(json["response"], "Access-Reject");
+        assert_eq!((json["reason_code"].as_u64(), json["category"].as_str()), (Some(16), Some("credentials")));
+        assert!(!json.to_string().contains('\n'));
+    }
+}
@@ -0,0 +1,77 @@
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::Context;
+use clap::Args;
+use radius_log_core::{filter_and_sort, BucketSize, SortSpec, TimeSeries, MAX_BUCKETS};
+
+use crate::input::{self, TimeArgs};
+use crate::query::FilterArgs;
+
+#[derive(Debug, Args)]
+pub struct HistogramArgs {
+    /// Log file, or folder of .log files (repeatable)
+    #[arg(short, long = "file", value_name = "PATH", required = true)]
+    pub files: Vec<PathBuf>,
+    #[command(flatten)]
+    pub filter: FilterArgs,
+    #[command(flatten)]
+    pub time: TimeArgs,
+    /// Interval: `minute`, `5min` or `hour` (default: chosen from the period covered)
+    #[arg(long, value_name = "SIZE", value_parser = parse_bucket)]
+    pub bucket: Option<BucketSize>,
+    /// Output file (default: standard output)
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+fn parse_bucket(name: &str) -> Result<BucketSize, String> {
+    BucketSize::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = BucketSize::ALL.into_iter().map(BucketSize::name).collect();
+        format!("unknown interval '{name}' (expected one of: {})", names.join(", "))
+    })
+}
+
+/// `radius-log histogram`: the time series as CSV.
+///
+/// # Errors
+/// Fails on an invalid query, an unreadable input, when no request has a timestamp, or on an output error.
+pub fn run(args: &HistogramArgs) -> anyhow::Result<ExitCode> {
+    let query = args.filter.query()?;
+    let (items, report) = input::load(&args.files, &args.time.settings())?;
+    let ids = filter_and_sort(&items, &query, args.filter.errors().as_ref(), &SortSpec::default());
+    let series = TimeSeries::compute(&items, &ids, args.bucket).context("no request with a timestamp")?;
+    if series.outside > 0 {
+        eprintln!("radius-log: {} requests left out, too far from the others for {MAX_BUCKETS} intervals", series.outside);
+    }
+
+    let written = match &args.output {
+        Some(path) => {
+            let file = File::create(path).with_context(|| format!("cannot create {}", path.display()))?;
+            series.write_csv(BufWriter::new(file), args.time.shown_zone())
+        }
+        None => series.write_csv(BufWriter::new(io::stdout().lock()), args.time.shown_zone()),
+    };
+    match written {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        other => other.context("cannot write the output")?,
+    }
+    Ok(input::exit_status(&report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cli, Command};
+    use clap::Parser;
+
+    #[test]
+    fn parses_arguments() {
+        let cli = Cli::try_parse_from(["radius-log", "histogram", "-f", "logs", "--bucket", "5min", "-o", "h.csv"]).expect("valid arguments");
+        let Command::Histogram(args) = cli.command else { panic!("histogram command") };
+        assert_eq!((args.bucket, args.output), (Some(BucketSize::FiveMinutes), Some(PathBuf::from("h.csv"))));
+        assert!(Cli::try_parse_from(["radius-log", "histogram", "-f", "logs", "--bucket", "day"]).is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::Context;
+use clap::Args;
+use radius_log_core::{list_log_files, parse_full_logic, ParseReport, RadiusRequest, TimeSettings, Zone};
+
+use crate::EXIT_SKIPPED_RECORDS;
+
+/// Time zones shared by the commands (the `time` section of the GUI config).
+#[derive(Debug, Args)]
+pub struct TimeArgs {
+    /// Zone the timestamps are written in: `local`, `utc` or an IANA name such as `Europe/Paris` (default: as logged)
+    #[arg(long, value_name = "ZONE", value_parser = parse_zone)]
+    pub zone: Option<Zone>,
+    /// Zone the NPS servers log in
+    #[arg(long, value_name = "ZONE", value_parser = parse_zone, default_value = "local")]
+    pub server_zone: Zone,
+}
+
+impl TimeArgs {
+    pub fn settings(&self) -> TimeSettings {
+        TimeSettings { display_zone: self.zone, default_server_zone: self.server_zone, ..Default::default() }
+    }
+
+    /// Zone of the wall-clock times written: the display zone, or the servers' one when shown as logged.
+    pub fn shown_zone(&self) -> Zone {
+        self.zone.unwrap_or(self.server_zone)
+    }
+}
+
+fn parse_zone(name: &str) -> Result<Zone, String> {
+    Zone::from_name(name).ok_or_else(|| format!("unknown time zone '{name}' (expected local, utc or an IANA name such as Europe/Paris)"))
+}
+
+/// Log files named by `paths`: files as given, folders expanded to their `.log` files (oldest first).
+///
+/// # Errors
+/// Fails if a folder cannot be read or holds no `.log` file.
+pub fn log_files(paths: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            let found = list_log_files(path).with_context(|| format!("cannot read folder {}", path.display()))?;
+            anyhow::ensure!(!found.is_empty(), "no .log file in {}", path.display());
+            files.extend(found);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    Ok(files)
+}
+
+/// Requests of every file of `paths` (see [`log_files`]), in file order, with the parse report.
+///
+/// # Errors
+/// Fails on the first file that cannot be opened or read.
+pub fn load(paths: &[PathBuf], time: &TimeSettings) -> anyhow::Result<(Vec<RadiusRequest>, ParseReport)> {
+    let mut items = Vec::new();
+    let mut report = ParseReport::default();
+    for file in log_files(paths)? {
+        let path = file.to_str().with_context(|| format!("{}: not a UTF-8 path", file.display()))?;
+        let (parsed, file_report) = parse_full_logic(path, None).with_context(|| format!("cannot read {}", file.display()))?;
+        items.extend(parsed);
+        report.merge(file_report);
+    }
+    time.apply_all(&mut items);
+    Ok((items, report))
+}
+
+/// Exit status of a run that read `report`: skipped records are listed on stderr.
+pub fn exit_status(report: &ParseReport) -> ExitCode {
+    if report.lost() == 0 {
+        return ExitCode::SUCCESS;
+    }
+    eprint!("radius-log: {}", report.to_text());
+    ExitCode::from(EXIT_SKIPPED_RECORDS)
+}
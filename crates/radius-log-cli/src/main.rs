@@ -0,0 +1,56 @@
+//! `radius-log`: the NPS log engine without the window, for scripts,
+//! scheduled tasks and Linux log collectors.
+//!
+//! Exit status: 0 on success, 1 on error (unreadable input, invalid query,
+//! output failure), 2 on a usage error, 3 when some records could not be
+//! parsed (the output is still complete for the others).
+
+mod detect;
+mod follow;
+mod histogram;
+mod input;
+mod query;
+mod stats;
+
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+
+/// Some records were malformed or truncated and skipped.
+const EXIT_SKIPPED_RECORDS: u8 = 3;
+
+#[derive(Debug, Parser)]
+#[command(name = "radius-log", version, about = "Parse, filter and export Microsoft NPS/IAS RADIUS logs")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Reads log files or folders, filters and sorts the requests, and writes them out
+    Query(query::QueryArgs),
+    /// Follows a log file or folder and prints each request as it completes (`tail -f` for NPS)
+    Follow(follow::FollowArgs),
+    /// Summarizes log files or folders: outcomes, success rate, top users, MACs, access points, servers and reason codes
+    Stats(stats::StatsArgs),
+    /// Counts the requests per minute, 5 minutes or hour (accepts, rejects, reason codes) as CSV
+    Histogram(histogram::HistogramArgs),
+    /// Flags password sprays (one MAC, access point or client IP failing against many users) and brute forces (one user failing many times)
+    Detect(detect::DetectArgs),
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match &cli.command {
+        Command::Query(args) => query::run(args),
+        Command::Follow(args) => follow::run(args),
+        Command::Stats(args) => stats::run(args),
+        Command::Histogram(args) => histogram::run(args),
+        Command::Detect(args) => detect::run(args),
+    };
+    result.unwrap_or_else(|e| {
+        eprintln!("radius-log: {e:#}");
+        ExitCode::FAILURE
+    })
+}
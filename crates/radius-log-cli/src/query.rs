@@ -0,0 +1,202 @@
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::Context;
+use clap::{Args, ValueEnum};
+use radius_log_core::{
+    attribute_names, export_xlsx, filter_and_sort, write_csv, ErrorFilter, ExportColumn, LogColumn, Query, ReasonCategory, SortKey, SortSpec,
+};
+
+use crate::input::{self, TimeArgs};
+
+/// Row selection shared by the commands.
+#[derive(Debug, Args)]
+pub struct FilterArgs {
+    /// Search, in the query language of the search box (e.g. `user:jdoe AND code=16`)
+    #[arg(short, long, default_value = "")]
+    pub query: String,
+    /// The query is one regular expression
+    #[arg(long)]
+    pub regex: bool,
+    /// Failed sessions only
+    #[arg(long)]
+    pub errors_only: bool,
+    /// Failure category of --errors-only: `credentials`, `account_state`, `certificate`, `policy`, `infrastructure` or `other`
+    #[arg(long, value_name = "CATEGORY", requires = "errors_only", value_parser = parse_category)]
+    pub category: Option<ReasonCategory>,
+}
+
+impl FilterArgs {
+    /// # Errors
+    /// Fails on an invalid query or regular expression.
+    pub fn query(&self) -> anyhow::Result<Query> {
+        if self.regex { Query::regex(&self.query) } else { Query::parse(&self.query) }.context("invalid query")
+    }
+
+    pub fn errors(&self) -> Option<ErrorFilter> {
+        self.errors_only.then(|| ErrorFilter { category: self.category, ..Default::default() })
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct QueryArgs {
+    /// Log file, or folder of .log files (repeatable)
+    #[arg(short, long = "file", value_name = "PATH", required = true)]
+    pub files: Vec<PathBuf>,
+    #[command(flatten)]
+    pub filter: FilterArgs,
+    #[command(flatten)]
+    pub time: TimeArgs,
+    /// Sort key, most significant first, `:desc` to reverse it (repeatable; default: timestamp)
+    #[arg(short, long = "sort", value_name = "COLUMN[:desc]", value_parser = parse_sort_key)]
+    pub sort: Vec<SortKey>,
+    /// Columns to write, comma-separated (default: all)
+    #[arg(short, long, value_name = "COLUMNS", value_delimiter = ',', value_parser = parse_column)]
+    pub columns: Vec<LogColumn>,
+    /// Logged attributes to write after the columns, by NPS name, comma-separated (e.g. `NP-Policy-Name,Called-Station-Id`)
+    #[arg(short, long, value_name = "NAMES", value_delimiter = ',', conflicts_with = "all_attributes")]
+    pub attributes: Vec<String>,
+    /// Write every attribute logged for the selected requests after the columns
+    #[arg(long)]
+    pub all_attributes: bool,
+    #[arg(long, value_enum, default_value_t = Format::Csv)]
+    pub format: Format,
+    /// Output file (default: standard output)
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Csv,
+    Tsv,
+    /// Excel workbook, needs --output
+    Xlsx,
+}
+
+fn parse_column(name: &str) -> Result<LogColumn, String> {
+    LogColumn::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = LogColumn::all().into_iter().map(LogColumn::name).collect();
+        format!("unknown column '{name}' (expected one of: {})", names.join(", "))
+    })
+}
+
+fn parse_sort_key(text: &str) -> Result<SortKey, String> {
+    let (name, descending) = match text.rsplit_once(':') {
+        Some((name, order)) if order.eq_ignore_ascii_case("desc") => (name, true),
+        Some((name, order)) if order.eq_ignore_ascii_case("asc") => (name, false),
+        _ => (text, false),
+    };
+    Ok(SortKey { column: parse_column(name)?, descending })
+}
+
+fn parse_category(name: &str) -> Result<ReasonCategory, String> {
+    ReasonCategory::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = ReasonCategory::ALL.into_iter().map(ReasonCategory::name).collect();
+        format!("unknown category '{name}' (expected one of: {})", names.join(", "))
+    })
+}
+
+/// `radius-log query`.
+///
+/// # Errors
+/// Fails on an invalid query, an unreadable input or an output error.
+pub fn run(args: &QueryArgs) -> anyhow::Result<ExitCode> {
+    anyhow::ensure!(args.format != Format::Xlsx || args.output.is_some(), "--format xlsx needs --output");
+    let query = args.filter.query()?;
+
+    let time = args.time.settings();
+    let (items, report) = input::load(&args.files, &time)?;
+
+    let errors = args.filter.errors();
+    let sort = if args.sort.is_empty() { SortSpec::by(LogColumn::Timestamp, false) } else { args.sort.iter().copied().collect() };
+    let ids = filter_and_sort(&items, &query, errors.as_ref(), &sort);
+
+    let columns: Vec<ExportColumn> = if args.columns.is_empty() { LogColumn::all() } else { args.columns.clone() }
+        .into_iter()
+        .map(|column| ExportColumn { column, header: column.name().to_string(), width_px: 150 })
+        .collect();
+    let attributes = if args.all_attributes { attribute_names(&items, &ids) } else { args.attributes.clone() };
+
+    match (args.format, &args.output) {
+        (Format::Xlsx, Some(path)) => export_xlsx(path, &items, &ids, &columns, &attributes, &time)?,
+        (format, output) => {
+            let delimiter = if format == Format::Tsv { '\t' } else { ',' };
+            let written = match output {
+                Some(path) => {
+                    let file = File::create(path).with_context(|| format!("cannot create {}", path.display()))?;
+                    write_csv(BufWriter::new(file), &items, &ids, &columns, &attributes, delimiter)
+                }
+                None => write_csv(BufWriter::new(io::stdout().lock()), &items, &ids, &columns, &attributes, delimiter),
+            };
+            // `radius-log query ... | head` is not an error
+            match written {
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+                other => other.context("cannot write the output")?,
+            }
+        }
+    }
+    Ok(input::exit_status(&report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cli, Command};
+    use clap::Parser;
+
+    #[test]
+    fn parses_arguments() {
+        let cli = Cli::try_parse_from([
+            "radius-log", "query", "--file", "IN2410.log", "-f", "logs", "--sort", "user", "--sort", "time:desc",
+            "--columns", "time,user,ap", "--errors-only", "--category", "certificate", "--format", "tsv",
+            "-a", "NP-Policy-Name,Called-Station-Id",
+        ])
+        .expect("valid arguments");
+        let Command::Query(args) = cli.command else { panic!("query command") };
+        assert_eq!(args.files, [PathBuf::from("IN2410.log"), PathBuf::from("logs")]);
+        assert_eq!(args.sort, [
+            SortKey { column: LogColumn::User, descending: false },
+            SortKey { column: LogColumn::Timestamp, descending: true },
+        ]);
+        assert_eq!(args.columns, [LogColumn::Timestamp, LogColumn::User, LogColumn::ApName]);
+        assert_eq!(args.attributes, ["NP-Policy-Name", "Called-Station-Id"]);
+        assert_eq!((args.filter.category, args.format), (Some(ReasonCategory::Certificate), Format::Tsv));
+
+        assert!(Cli::try_parse_from(["radius-log", "query", "--file", "a.log", "--sort", "nope"]).is_err());
+        assert!(Cli::try_parse_from(["radius-log", "query", "--file", "a.log", "--category", "policy"]).is_err(), "needs --errors-only");
+        assert!(Cli::try_parse_from(["radius-log", "query"]).is_err(), "--file is required");
+        assert!(Cli::try_parse_from(["radius-log", "query", "-f", "a.log", "-a", "Class", "--all-attributes"]).is_err());
+        assert!(Cli::try_parse_from(["radius-log", "query", "-f", "a.log", "--zone", "Mars/Olympus"]).is_err());
+    }
+
+    #[test]
+    fn writes_csv_and_reports_truncated_records() {
+        let folder = std::env::temp_dir().join(format!("radius-log-cli-{}-query", std::process::id()));
+        std::fs::create_dir_all(&folder).expect("folder");
+        let (log, output) = (folder.join("IN240115.log"), folder.join("out.csv"));
+        std::fs::write(&log, concat!(
+            r#"<Event><Timestamp data_type="4">01/15/2024 08:00:00.123</Timestamp><Computer-Name data_type="1">NPS01</Computer-Name><Packet-Type data_type="0">1</Packet-Type><User-Name data_type="1">CORP\jdoe</User-Name><Class data_type="1">311 1 10.0.0.1 01/15/2024 1</Class></Event>"#, "\n",
+            r#"<Event><Timestamp data_type="4">01/15/2024 08:00:00.456</Timestamp><Computer-Name data_type="1">NPS01</Computer-Name><Packet-Type data_type="0">3</Packet-Type><Class data_type="1">311 1 10.0.0.1 01/15/2024 1</Class><Reason-Code data_type="0">16</Reason-Code></Event>"#, "\n",
+            // Cut short by the server: no </Event>
+            r#"<Event><Timestamp data_type="4">01/15/2024 08:01:00.000</Timestamp><User-Name data_type="1">alice"#,
+        ))
+        .expect("write log");
+
+        let cli = Cli::try_parse_from([
+            "radius-log", "query", "-f", log.to_str().expect("UTF-8 path"), "--columns", "time,user,response",
+            "--zone", "Europe/Paris", "--server-zone", "utc", "--output", output.to_str().expect("UTF-8 path"),
+        ])
+        .expect("valid arguments");
+        let Command::Query(args) = cli.command else { panic!("query command") };
+        assert_eq!(run(&args).expect("run"), ExitCode::from(crate::EXIT_SKIPPED_RECORDS));
+        // Logged in UTC, written in Paris time (UTC+1 in winter)
+        assert_eq!(
+            std::fs::read_to_string(&output).expect("output"),
+            "timestamp,user,response\r\n01/15/2024 09:00:00.123,CORP\\jdoe,Access-Reject\r\n"
+        );
+        let _ = std::fs::remove_dir_all(&folder);
+    }
+}
@@ -0,0 +1,64 @@
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::Context;
+use clap::Args;
+use radius_log_core::{filter_and_sort, SortSpec, Statistics, DEFAULT_TOP};
+
+use crate::input::{self, TimeArgs};
+use crate::query::FilterArgs;
+
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    /// Log file, or folder of .log files (repeatable)
+    #[arg(short, long = "file", value_name = "PATH", required = true)]
+    pub files: Vec<PathBuf>,
+    #[command(flatten)]
+    pub filter: FilterArgs,
+    #[command(flatten)]
+    pub time: TimeArgs,
+    /// Rows of each "top" list
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_TOP)]
+    pub top: usize,
+    /// One JSON object instead of the text summary
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `radius-log stats`.
+///
+/// # Errors
+/// Fails on an invalid query, an unreadable input or an output error.
+pub fn run(args: &StatsArgs) -> anyhow::Result<ExitCode> {
+    let query = args.filter.query()?;
+    let (items, report) = input::load(&args.files, &args.time.settings())?;
+    // Statistics do not depend on the order
+    let ids = filter_and_sort(&items, &query, args.filter.errors().as_ref(), &SortSpec::default());
+    let stats = Statistics::compute(&items, &ids, args.top);
+
+    let text = if args.json { serde_json::to_string_pretty(&stats)? + "\n" } else { stats.to_text() };
+    match io::stdout().lock().write_all(text.as_bytes()) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        other => other.context("cannot write the output")?,
+    }
+    Ok(input::exit_status(&report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cli, Command};
+    use clap::Parser;
+
+    #[test]
+    fn parses_arguments() {
+        let cli = Cli::try_parse_from(["radius-log", "stats", "-f", "logs", "--top", "3", "--json", "-q", "user:jdoe"]).expect("valid arguments");
+        let Command::Stats(args) = cli.command else { panic!("stats command") };
+        assert_eq!((args.top, args.json, args.filter.query.as_str()), (3, true, "user:jdoe"));
+
+        let cli = Cli::try_parse_from(["radius-log", "stats", "-f", "logs"]).expect("valid arguments");
+        let Command::Stats(args) = cli.command else { panic!("stats command") };
+        assert_eq!((args.top, args.json), (DEFAULT_TOP, false));
+    }
+}
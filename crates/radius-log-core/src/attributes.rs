@@ -0,0 +1,91 @@
+use std::fmt::Write;
+
+/// RADIUS attributes in the order they were logged, keyed by their NPS name
+/// (`Called-Station-Id`, `NP-Policy-Name`...).
+///
+/// Names are compared case-insensitively. An event may carry the same
+/// attribute several times (Class, Tunnel-*...): every occurrence is kept.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AttributeMap(Vec<(String, String)>);
+
+impl AttributeMap {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Value of the first occurrence of `name`.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    /// Appends an occurrence, even if `name` is already present.
+    pub fn push(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.0.push((name.into(), value.into()));
+    }
+
+    /// Replaces the value of the first occurrence of `name`, or appends it.
+    pub fn set(&mut self, name: &str, value: &str) {
+        match self.0.iter_mut().find(|(n, _)| n.eq_ignore_ascii_case(name)) {
+            Some((_, v)) => value.clone_into(v),
+            None => self.push(name, value),
+        }
+    }
+
+    /// Folds `other` into this map: new names are appended, existing ones take the newer value
+    /// (repeated occurrences collapse into one).
+    pub fn merge(&mut self, other: &Self) {
+        for (name, value) in other.iter() {
+            self.set(name, value);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.0.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// One `Name: value` line per attribute (clipboard, tooltips).
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for (name, value) in self.iter() {
+            let _ = writeln!(out, "{name}: {value}");
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_order_and_merges() {
+        let mut event = AttributeMap::new();
+        event.push("Packet-Type", "1");
+        event.push("Class", "a");
+        event.push("Class", "b");
+        assert_eq!(event.get("class"), Some("a"));
+        assert_eq!(event.len(), 3);
+
+        let mut response = AttributeMap::new();
+        response.push("Packet-Type", "3");
+        response.push("Reason-Code", "16");
+
+        let mut merged = AttributeMap::new();
+        merged.merge(&event);
+        merged.merge(&response);
+        assert_eq!(merged.to_text(), "Packet-Type: 3\nClass: b\nReason-Code: 16\n");
+    }
+}
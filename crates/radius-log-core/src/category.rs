@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::RadiusRequest;
+
+/// Kind of outcome behind an NPS reason code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ReasonCategory {
+    Success,
+    /// Wrong password, unknown user or domain, incomplete credentials.
+    Credentials,
+    /// Disabled, expired or locked out account, logon hours, password to change.
+    AccountState,
+    /// Certificate validation and the TLS side of EAP.
+    Certificate,
+    /// No matching network / connection request policy, method not allowed.
+    Policy,
+    /// NPS, domain controller or remote RADIUS server unreachable or failing.
+    Infrastructure,
+    /// Any other code, or a reject logged without one.
+    Other,
+}
+
+impl ReasonCategory {
+    pub const ALL: [Self; 7] =
+        [Self::Success, Self::Credentials, Self::AccountState, Self::Certificate, Self::Policy, Self::Infrastructure, Self::Other];
+
+    /// Categories of the failed exchanges.
+    pub const FAILURES: [Self; 6] =
+        [Self::Credentials, Self::AccountState, Self::Certificate, Self::Policy, Self::Infrastructure, Self::Other];
+
+    #[must_use]
+    pub const fn ftl_key(self) -> &'static str {
+        match self {
+            Self::Success => "cat-success",
+            Self::Credentials => "cat-credentials",
+            Self::AccountState => "cat-account-state",
+            Self::Certificate => "cat-certificate",
+            Self::Policy => "cat-policy",
+            Self::Infrastructure => "cat-infrastructure",
+            Self::Other => "cat-other",
+        }
+    }
+
+    /// Short name, as written on the command line.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Credentials => "credentials",
+            Self::AccountState => "account_state",
+            Self::Certificate => "certificate",
+            Self::Policy => "policy",
+            Self::Infrastructure => "infrastructure",
+            Self::Other => "other",
+        }
+    }
+
+    /// Category named `name` (case-insensitive, `-` or `_`), see [`ReasonCategory::name`].
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.replace('-', "_");
+        Self::ALL.into_iter().find(|cat| cat.name().eq_ignore_ascii_case(&name))
+    }
+
+    /// Built-in category of a reason code.
+    #[must_use]
+    pub const fn of_code(code: u32) -> Self {
+        match code {
+            0 => Self::Success,
+            7 | 8 | 16 | 17 | 263 => Self::Credentials,
+            33..=38 | 64 | 65 | 68 | 72 => Self::AccountState,
+            18 | 20 | 21 | 22 | 48 | 49 | 66 | 67 | 69 | 70 => Self::Policy,
+            1..=6 | 9 | 10 | 23 | 32 | 80 | 96 | 97 | 112..=118 | 260..=262 | 266 | 271 => Self::Infrastructure,
+            73 | 256..=259 | 264 | 265 | 267..=270 | 272..=302 => Self::Certificate,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Classification of the reason codes: the built-in table, with per-code
+/// overrides (`"reason_categories"` in the configuration, e.g. `{ "8": "AccountState" }`).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ReasonCategories(BTreeMap<u32, ReasonCategory>);
+
+impl ReasonCategories {
+    /// Overrides the category of `code`; `None` restores the built-in one.
+    pub fn set(&mut self, code: u32, category: Option<ReasonCategory>) {
+        match category {
+            Some(category) => self.0.insert(code, category),
+            None => self.0.remove(&code),
+        };
+    }
+
+    #[must_use]
+    pub fn of_code(&self, code: u32) -> ReasonCategory {
+        self.0.get(&code).copied().unwrap_or_else(|| ReasonCategory::of_code(code))
+    }
+
+    /// Category of an exchange, from its Reason-Code or else its response.
+    /// `None` while it has no outcome (accounting, request without response).
+    #[must_use]
+    pub fn classify(&self, req: &RadiusRequest) -> Option<ReasonCategory> {
+        match (req.reason_code, req.resp_type.as_str()) {
+            (Some(code), _) if code != 0 => Some(self.of_code(code)),
+            (_, "Access-Reject") => Some(ReasonCategory::Other),
+            (_, "Access-Accept") => Some(ReasonCategory::Success),
+            _ => None,
+        }
+    }
+}
+
+/// Rows targeted by the errors-only mode and the error navigation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorFilter {
+    /// Category to look for; `None` targets every failure.
+    pub category: Option<ReasonCategory>,
+    pub categories: ReasonCategories,
+}
+
+impl ErrorFilter {
+    #[must_use]
+    pub fn targets(&self, req: &RadiusRequest) -> bool {
+        match (self.categories.classify(req), self.category) {
+            (Some(found), Some(wanted)) => found == wanted,
+            (Some(found), None) => found != ReasonCategory::Success,
+            (None, _) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn req(resp_type: &str, code: Option<&str>) -> RadiusRequest {
+        RadiusRequest { resp_type: resp_type.to_string(), reason_code: code.and_then(|code| code.parse().ok()), ..Default::default() }
+    }
+
+    #[test]
+    fn classifies_codes_and_responses() {
+        let categories = ReasonCategories::default();
+        assert_eq!(categories.classify(&req("Access-Reject", Some("16"))), Some(ReasonCategory::Credentials));
+        assert_eq!(categories.classify(&req("Access-Reject", Some("268"))), Some(ReasonCategory::Certificate));
+        assert_eq!(categories.classify(&req("Access-Reject", Some("48"))), Some(ReasonCategory::Policy));
+        assert_eq!(categories.classify(&req("", Some("117"))), Some(ReasonCategory::Infrastructure));
+        assert_eq!(categories.classify(&req("Access-Reject", Some("34"))), Some(ReasonCategory::AccountState));
+        assert_eq!(categories.classify(&req("Access-Reject", None)), Some(ReasonCategory::Other));
+        assert_eq!(categories.classify(&req("Access-Accept", Some("0"))), Some(ReasonCategory::Success));
+        assert_eq!(categories.classify(&req("Accounting-Response", Some("0"))), None);
+    }
+
+    #[test]
+    fn overrides_and_targets() {
+        let mut categories: ReasonCategories = serde_json::from_str(r#"{ "8": "AccountState" }"#).expect("parse");
+        assert_eq!(categories.of_code(8), ReasonCategory::AccountState);
+        categories.set(8, None);
+        assert_eq!(categories.of_code(8), ReasonCategory::Credentials);
+
+        assert_eq!(ReasonCategory::from_name("Account-State"), Some(ReasonCategory::AccountState));
+        assert_eq!(ReasonCategory::from_name("pki"), None);
+
+        let any = ErrorFilter::default();
+        assert!(any.targets(&req("Access-Reject", Some("16"))));
+        assert!(!any.targets(&req("Access-Accept", Some("0"))));
+        let certs = ErrorFilter { category: Some(ReasonCategory::Certificate), categories };
+        assert!(certs.targets(&req("Access-Reject", Some("268"))));
+        assert!(!certs.targets(&req("Access-Reject", Some("16"))));
+    }
+}
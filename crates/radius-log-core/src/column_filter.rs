@@ -0,0 +1,162 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{LogColumn, RadiusRequest};
+use crate::net::{parse_mac, MacNotation};
+use crate::time::TimeRange;
+
+/// Criterion on one column of the list (header filter).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColumnFilter {
+    /// Cell contains the text (case-insensitive).
+    Contains(String),
+    /// Cell is exactly one of the values (pick-list).
+    OneOf(Vec<String>),
+    /// Instant of the request in the range (Timestamp column).
+    Range(TimeRange),
+}
+
+/// Filters of the list columns, combined with AND; at most one per column.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnFilters(Vec<(LogColumn, ColumnFilter)>);
+
+impl ColumnFilters {
+    #[must_use]
+    pub fn get(&self, col: LogColumn) -> Option<&ColumnFilter> {
+        self.0.iter().find(|(c, _)| *c == col).map(|(_, f)| f)
+    }
+
+    /// Sets (`Some`) or removes (`None`) the filter of `col`.
+    pub fn set(&mut self, col: LogColumn, filter: Option<ColumnFilter>) {
+        self.0.retain(|(c, _)| *c != col);
+        if let Some(filter) = filter {
+            self.0.push((col, filter));
+        }
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (LogColumn, &ColumnFilter)> {
+        self.0.iter().map(|(c, f)| (*c, f))
+    }
+
+    /// Rewrites the MAC pick-list after `items` were shown in `notation`: each
+    /// address becomes the texts it now has in `items` (its `notation` spelling
+    /// if no row has it), other values are kept. Returns whether it changed.
+    pub fn respell_macs(&mut self, items: &[RadiusRequest], notation: MacNotation) -> bool {
+        let Some((_, ColumnFilter::OneOf(values))) = self.0.iter_mut().find(|(c, _)| *c == LogColumn::Mac) else {
+            return false;
+        };
+        let addrs: HashSet<u64> = values.iter().filter_map(|v| parse_mac(v)).collect();
+        let mut texts: HashMap<u64, Vec<&str>> = HashMap::new();
+        for item in items {
+            if let Some(mac) = item.mac_addr.filter(|mac| addrs.contains(mac)) {
+                let spellings = texts.entry(mac).or_default();
+                if !spellings.contains(&item.mac.as_str()) {
+                    spellings.push(&item.mac);
+                }
+            }
+        }
+
+        let mut respelled: Vec<String> = Vec::new();
+        for value in values.iter() {
+            let spellings: Vec<String> = parse_mac(value).map_or_else(
+                || vec![value.clone()],
+                |mac| texts.get(&mac).map_or_else(
+                    || vec![notation.format(mac).unwrap_or_else(|| value.clone())],
+                    |found| found.iter().map(|t| (*t).to_string()).collect(),
+                ),
+            );
+            for text in spellings {
+                if !respelled.contains(&text) {
+                    respelled.push(text);
+                }
+            }
+        }
+        let changed = respelled != *values;
+        *values = respelled;
+        changed
+    }
+}
+
+/// Distinct texts of `col` among `items`, with their number of rows, most frequent first.
+#[must_use]
+pub fn distinct_values(items: &[RadiusRequest], col: LogColumn) -> Vec<(String, usize)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for item in items {
+        *counts.entry(item.column_text(col)).or_default() += 1;
+    }
+    let mut values: Vec<(String, usize)> = counts.into_iter().map(|(v, n)| (v.to_string(), n)).collect();
+    values.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::Query;
+
+    fn req(user: &str, server: &str) -> RadiusRequest {
+        RadiusRequest { user: user.to_string(), server: server.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn counts_distinct_values() {
+        let items = vec![req("bob", "NPS01"), req("alice", "NPS02"), req("bob", "NPS02"), req("carol", "NPS02")];
+        assert_eq!(distinct_values(&items, LogColumn::Server), vec![("NPS02".to_string(), 3), ("NPS01".to_string(), 1)]);
+        let users = distinct_values(&items, LogColumn::User);
+        assert_eq!(users[0], ("bob".to_string(), 2));
+        assert_eq!(users[1].0, "alice");
+    }
+
+    #[test]
+    fn respells_mac_pick_list() {
+        let logged = ["aa:bb:cc:dd:ee:ff", "AA-BB-CC-DD-EE-FF", "not-a-mac"];
+        let mut items: Vec<RadiusRequest> = logged.iter().map(|&mac| {
+            let mut req = RadiusRequest { mac: mac.to_string(), mac_addr: parse_mac(mac), ..Default::default() };
+            req.attributes.push("Calling-Station-Id", mac);
+            req
+        }).collect();
+        let mut filters = ColumnFilters::default();
+        let picked = ["aa:bb:cc:dd:ee:ff", "not-a-mac", "11:22:33:44:55:66"].map(str::to_string).to_vec();
+        filters.set(LogColumn::Mac, Some(ColumnFilter::OneOf(picked)));
+
+        MacNotation::Bare.apply_all(&mut items);
+        assert!(filters.respell_macs(&items, MacNotation::Bare));
+        let expected = ["AABBCCDDEEFF", "not-a-mac", "112233445566"].map(str::to_string).to_vec();
+        assert_eq!(filters.get(LogColumn::Mac), Some(&ColumnFilter::OneOf(expected)));
+        let q = Query::default().with_column_filters(&filters);
+        assert_eq!((0..3).filter(|&i| q.matches(&items[i])).count(), 3);
+        assert!(!filters.respell_macs(&items, MacNotation::Bare));
+
+        // Back to the log spellings: one address may have several
+        MacNotation::AsLogged.apply_all(&mut items);
+        assert!(filters.respell_macs(&items, MacNotation::AsLogged));
+        let expected = ["aa:bb:cc:dd:ee:ff", "AA-BB-CC-DD-EE-FF", "not-a-mac", "112233445566"].map(str::to_string).to_vec();
+        assert_eq!(filters.get(LogColumn::Mac), Some(&ColumnFilter::OneOf(expected)));
+    }
+
+    #[test]
+    fn filters_combine_with_and_and_round_trip() {
+        let items = [req("bob", "NPS01"), req("alice", "NPS02"), req("Bob", "NPS02"), req("carol", "NPS02")];
+        let mut filters = ColumnFilters::default();
+        filters.set(LogColumn::Server, Some(ColumnFilter::OneOf(vec!["NPS02".to_string()])));
+        filters.set(LogColumn::User, Some(ColumnFilter::Contains("B".to_string())));
+        let q = Query::default().with_column_filters(&filters);
+        assert_eq!((0..4).filter(|&i| q.matches(&items[i])).collect::<Vec<_>>(), vec![2]);
+
+        // Pick-lists are exact
+        filters.set(LogColumn::User, Some(ColumnFilter::OneOf(vec!["bob".to_string()])));
+        let q = Query::parse("NOT carol").expect("valid").with_column_filters(&filters);
+        assert!((0..4).all(|i| !q.matches(&items[i])));
+
+        filters.set(LogColumn::User, None);
+        let json = serde_json::to_string(&filters).expect("serialize");
+        assert_eq!(serde_json::from_str::<ColumnFilters>(&json).expect("deserialize"), filters);
+        assert_eq!(filters.get(LogColumn::User), None);
+    }
+}
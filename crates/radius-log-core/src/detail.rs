@@ -0,0 +1,103 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Context;
+
+use crate::format::LogFormat;
+use crate::model::Event;
+use crate::parser::map_packet_type;
+use crate::reasons::map_reason;
+
+/// Where one source event of a request was logged.
+///
+/// Requests keep these instead of the events themselves, so memory does not
+/// grow with the attributes of every event: the session detail reads them
+/// again with [`read_events`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventRef {
+    /// Log file, `None` when the source was parsed from memory (see [`crate::parse_reader`]).
+    pub file: Option<Arc<Path>>,
+    /// Byte offset of the record in the file.
+    pub offset: u64,
+    /// Length of the record in bytes.
+    pub len: u32,
+}
+
+/// Reads the events of `refs` again from their log files, in order.
+///
+/// # Errors
+/// Returns an error if a file cannot be read, or if a record no longer
+/// parses (file rewritten since it was loaded).
+pub fn read_events(refs: &[EventRef]) -> anyhow::Result<Vec<Event>> {
+    let mut files: HashMap<&Path, File> = HashMap::new();
+    refs.iter()
+        .map(|at| {
+            let path = at.file.as_deref().context("event not read from a file")?;
+            let file = match files.entry(path) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(File::open(path).with_context(|| path.display().to_string())?),
+            };
+            let mut bytes = vec![0; at.len as usize];
+            file.seek(SeekFrom::Start(at.offset))?;
+            file.read_exact(&mut bytes).with_context(|| format!("{} @ {}", path.display(), at.offset))?;
+            let format = LogFormat::detect(&bytes).unwrap_or(LogFormat::Xml);
+            format.deserialize(&bytes).with_context(|| format!("{} @ {}", path.display(), at.offset))
+        })
+        .collect()
+}
+
+/// Event sequence of an exchange for the session detail view: one header
+/// line per event (timestamp, packet type, reason code), then its attributes.
+#[must_use]
+pub fn events_text(events: &[Event]) -> String {
+    let mut out = String::new();
+    for (i, event) in events.iter().enumerate() {
+        let _ = write!(out, "#{}  {}  {}", i + 1, event.timestamp.as_deref().unwrap_or("-"),
+            map_packet_type(event.packet_type.as_deref().unwrap_or("?")));
+        if let Some(code) = &event.reason_code {
+            let _ = write!(out, "  [{}]", map_reason(code));
+        }
+        out.push('\n');
+        for (name, value) in event.attributes.iter() {
+            let _ = writeln!(out, "    {name}: {value}");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{parse_full_logic, parse_reader};
+    use crate::test_support::{temp_log, SAMPLE};
+    use std::fs;
+    use std::io::Cursor;
+
+    #[test]
+    fn reads_source_events_again() {
+        let path = temp_log("detail", SAMPLE);
+        let (reqs, _) = parse_full_logic(&path, None).expect("parse");
+        let events = read_events(&reqs[0].events).expect("read");
+        let _ = fs::remove_file(&path);
+
+        let packets: Vec<_> = events.iter().map(|e| e.packet_type.as_deref()).collect();
+        assert_eq!(packets, vec![Some("1"), Some("3")]);
+        let text = events_text(&events);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "#1  01/15/2024 08:00:00.123  Access-Request");
+        assert!(lines.contains(&"    SAM-Account-Name: CORP\\jdoe"));
+        let reject = lines.iter().find(|l| l.starts_with("#2")).expect("second event");
+        assert!(reject.contains("Access-Reject") && reject.ends_with("(16)]"), "{reject}");
+
+        // Gone since the load
+        assert!(read_events(&reqs[0].events).is_err());
+        let (reqs, _) = parse_reader(Cursor::new(SAMPLE.as_bytes()), SAMPLE.len() as u64, None).expect("parse");
+        assert!(read_events(&reqs[0].events).is_err(), "parsed from memory");
+    }
+}
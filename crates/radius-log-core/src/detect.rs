@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+use std::ops::Range;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::category::{ReasonCategories, ReasonCategory};
+use crate::model::RadiusRequest;
+use crate::time::TimeRange;
+
+/// Credential attack recognized by [`detect_attacks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AttackKind {
+    /// One source failing against many distinct users.
+    PasswordSpray,
+    /// One user failing many times.
+    BruteForce,
+}
+
+impl AttackKind {
+    #[must_use]
+    pub const fn ftl_key(self) -> &'static str {
+        match self {
+            Self::PasswordSpray => "attack-password-spray",
+            Self::BruteForce => "attack-brute-force",
+        }
+    }
+
+    /// Short name, as written by the command line.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::PasswordSpray => "password_spray",
+            Self::BruteForce => "brute_force",
+        }
+    }
+}
+
+/// What the failures of a finding have in common.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Source {
+    Mac,
+    AccessPoint,
+    /// Client IP address (the NAS that relayed the requests).
+    ClientIp,
+    User,
+}
+
+impl Source {
+    /// Sources of a password spray.
+    pub const SPRAY: [Self; 3] = [Self::Mac, Self::AccessPoint, Self::ClientIp];
+
+    #[must_use]
+    pub const fn ftl_key(self) -> &'static str {
+        match self {
+            Self::Mac => "col-mac",
+            Self::AccessPoint => "col-ap-name",
+            Self::ClientIp => "col-ap-ip",
+            Self::User => "col-user",
+        }
+    }
+
+    /// Field of the search query language.
+    #[must_use]
+    pub const fn field(self) -> &'static str {
+        match self {
+            Self::Mac => "mac",
+            Self::AccessPoint => "ap_name",
+            Self::ClientIp => "ap_ip",
+            Self::User => "user",
+        }
+    }
+
+    fn value(self, req: &RadiusRequest) -> &str {
+        match self {
+            Self::Mac => &req.mac,
+            Self::AccessPoint => &req.ap_name,
+            Self::ClientIp => &req.ap_ip,
+            Self::User => &req.user,
+        }
+    }
+}
+
+/// Thresholds of [`detect_attacks`] (saved in the config, see the README).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DetectionSettings {
+    /// Sliding window the failures are counted in, in seconds.
+    pub window_secs: u32,
+    /// Distinct users one source must fail against within the window.
+    pub spray_users: usize,
+    /// Failures of one user within the window.
+    pub brute_force_failures: usize,
+    /// Failure categories counted as attempts.
+    pub categories: Vec<ReasonCategory>,
+}
+
+impl Default for DetectionSettings {
+    fn default() -> Self {
+        Self {
+            window_secs: 600,
+            spray_users: 10,
+            brute_force_failures: 10,
+            // Wrong password, then the lockout it causes
+            categories: vec![ReasonCategory::Credentials, ReasonCategory::AccountState],
+        }
+    }
+}
+
+/// A burst of failures over the thresholds: every failure of `source` = `value`
+/// in overlapping windows that each reached a threshold.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub kind: AttackKind,
+    pub source: Source,
+    pub value: String,
+    pub first: DateTime<Utc>,
+    pub last: DateTime<Utc>,
+    pub failures: usize,
+    /// Distinct users of the failures.
+    pub users: usize,
+    /// The failed requests (indices into the analysed items), in time order.
+    pub rows: Vec<usize>,
+    /// Distinct Reason-Codes of the failures, sorted (0 for rejects without one).
+    pub codes: Vec<u32>,
+}
+
+impl Finding {
+    /// Search query selecting the failures of the source with the reason codes
+    /// that were counted, to use with [`Finding::range`]: it shows exactly [`Finding::rows`].
+    #[must_use]
+    pub fn query(&self) -> String {
+        let value = self.value.replace('\\', "\\\\").replace('"', "\\\"");
+        let codes: Vec<String> = self.codes.iter()
+            .map(|&code| if code == 0 { "(response=Access-Reject NOT code>0)".to_string() } else { format!("code={code}") })
+            .collect();
+        let codes = if codes.len() > 1 { format!("({})", codes.join(" OR ")) } else { codes.concat() };
+        format!("{}=\"{value}\" {codes}", self.source.field())
+    }
+
+    /// Period of the burst.
+    #[must_use]
+    pub const fn range(&self) -> TimeRange {
+        TimeRange { from: Some(self.first), to: Some(self.last) }
+    }
+}
+
+// A failure being analysed: instant, request, row
+type Failure<'a> = (DateTime<Utc>, &'a RadiusRequest, usize);
+
+/// Credential attacks among the rows `ids` of `items`, ordered by start.
+///
+/// A password spray is one MAC / access point / client IP failing against
+/// `spray_users` distinct users within `window_secs`; a brute force is one
+/// user failing `brute_force_failures` times within it. Only the failures of
+/// `settings.categories` count; requests without a timestamp are ignored.
+#[must_use]
+pub fn detect_attacks(items: &[RadiusRequest], ids: &[usize], categories: &ReasonCategories, settings: &DetectionSettings) -> Vec<Finding> {
+    let mut failures: Vec<Failure> = ids
+        .iter()
+        .filter_map(|&i| Some((items.get(i)?, i)))
+        .filter(|(req, _)| categories.classify(req).is_some_and(|category| settings.categories.contains(&category)))
+        .filter_map(|(req, i)| Some((req.time?, req, i)))
+        .collect();
+    failures.sort_by_key(|&(time, _, i)| (time, i));
+
+    let window = TimeDelta::seconds(i64::from(settings.window_secs));
+    let mut findings = Vec::new();
+    let checks = Source::SPRAY
+        .into_iter()
+        .map(|source| (AttackKind::PasswordSpray, source, settings.spray_users))
+        .chain([(AttackKind::BruteForce, Source::User, settings.brute_force_failures)]);
+    for (kind, source, threshold) in checks {
+        let mut groups: HashMap<&str, Vec<Failure>> = HashMap::new();
+        for &failure in &failures {
+            let value = source.value(failure.1);
+            if !value.is_empty() {
+                groups.entry(value).or_default().push(failure);
+            }
+        }
+        for (value, group) in groups {
+            for run in bursts(&group, window, threshold.max(1), kind == AttackKind::PasswordSpray) {
+                let burst = &group[run];
+                let mut users: Vec<&str> = burst.iter().map(|(_, req, _)| req.user.as_str()).collect();
+                users.sort_unstable();
+                users.dedup();
+                let mut codes: Vec<u32> = burst.iter().map(|(_, req, _)| req.reason_code.unwrap_or(0)).collect();
+                codes.sort_unstable();
+                codes.dedup();
+                findings.push(Finding {
+                    kind,
+                    source,
+                    value: value.to_string(),
+                    first: burst[0].0,
+                    last: burst[burst.len() - 1].0,
+                    failures: burst.len(),
+                    users: users.len(),
+                    rows: burst.iter().map(|&(_, _, i)| i).collect(),
+                    codes,
+                });
+            }
+        }
+    }
+    findings.sort_by(|a, b| (a.first, a.kind, a.source, &a.value).cmp(&(b.first, b.kind, b.source, &b.value)));
+    findings
+}
+
+// Runs of `failures` (in time order) covered by windows reaching `threshold`
+// distinct users, or failures; overlapping windows make one run
+fn bursts(failures: &[Failure], window: TimeDelta, threshold: usize, distinct_users: bool) -> Vec<Range<usize>> {
+    let mut runs: Vec<Range<usize>> = Vec::new();
+    let mut users: HashMap<&str, usize> = HashMap::new();
+    let mut left = 0;
+    for (right, &(time, req, _)) in failures.iter().enumerate() {
+        *users.entry(req.user.as_str()).or_default() += 1;
+        while time - failures[left].0 > window {
+            let user = failures[left].1.user.as_str();
+            if let Some(count) = users.get_mut(user) {
+                *count -= 1;
+                if *count == 0 {
+                    users.remove(user);
+                }
+            }
+            left += 1;
+        }
+
+        let size = if distinct_users { users.len() } else { right + 1 - left };
+        if size >= threshold {
+            match runs.last_mut() {
+                Some(run) if run.end >= left => run.end = right + 1,
+                _ => runs.push(left..right + 1),
+            }
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(minute: u32, user: &str, mac: &str) -> RadiusRequest {
+        RadiusRequest {
+            time: DateTime::from_timestamp(1_705_305_600 + i64::from(minute) * 60, 0),
+            user: user.to_string(),
+            mac: mac.to_string(),
+            resp_type: "Access-Reject".to_string(),
+            reason_code: Some(16),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn flags_sprays_and_brute_forces() {
+        let mut items: Vec<RadiusRequest> = (0..4).map(|i| failure(i, &format!("user{i}"), "AA-BB-CC-DD-EE-FF")).collect();
+        // jdoe: three failures in 2 minutes from different devices, then one an hour later
+        items.extend([failure(10, "jdoe", "01"), failure(11, "jdoe", "02"), failure(12, "jdoe", "03"), failure(70, "jdoe", "04")]);
+        // Another source, spread out: under the thresholds
+        items.extend((0..4).map(|i| failure(i * 20, &format!("other{i}"), "11-22-33-44-55-66")));
+        let ids: Vec<usize> = (0..items.len()).collect();
+        let settings = DetectionSettings { window_secs: 300, spray_users: 4, brute_force_failures: 3, ..Default::default() };
+
+        let findings = detect_attacks(&items, &ids, &ReasonCategories::default(), &settings);
+        let summary: Vec<(AttackKind, Source, &str, usize, usize)> =
+            findings.iter().map(|f| (f.kind, f.source, f.value.as_str(), f.users, f.failures)).collect();
+        assert_eq!(summary, [
+            (AttackKind::PasswordSpray, Source::Mac, "AA-BB-CC-DD-EE-FF", 4, 4),
+            (AttackKind::BruteForce, Source::User, "jdoe", 1, 3),
+        ]);
+        assert_eq!(findings[1].rows, [4, 5, 6]);
+        assert_eq!(findings[0].query(), "mac=\"AA-BB-CC-DD-EE-FF\" code=16");
+        assert!(findings[1].range().contains(items[5].time) && !findings[1].range().contains(items[7].time));
+
+        // The query shows the failures counted, not the other failures of the source (here a policy one)
+        items.push(RadiusRequest { reason_code: Some(48), ..failure(2, "printer", "AA-BB-CC-DD-EE-FF") });
+        let shown = |finding: &Finding, items: &[RadiusRequest]| -> Vec<usize> {
+            let query = crate::Query::parse(&finding.query()).expect("valid query");
+            (0..items.len()).filter(|&i| query.matches(&items[i]) && finding.range().contains(items[i].time)).collect()
+        };
+        let spray = &detect_attacks(&items, &ids, &ReasonCategories::default(), &settings)[0];
+        assert_eq!(shown(spray, &items), spray.rows);
+        // Unless the configuration counts it
+        let mut categories = ReasonCategories::default();
+        categories.set(48, Some(ReasonCategory::Credentials));
+        let ids: Vec<usize> = (0..items.len()).collect();
+        let spray = &detect_attacks(&items, &ids, &categories, &settings)[0];
+        assert_eq!((spray.query().as_str(), spray.rows.as_slice()), ("mac=\"AA-BB-CC-DD-EE-FF\" (code=16 OR code=48)", &[0, 1, 2, 12, 3][..]));
+        assert_eq!(shown(spray, &items), [0, 1, 2, 3, 12], "same rows, in file order");
+        items.pop();
+
+        // Successes and other categories are not attempts
+        items[5].reason_code = None;
+        items[5].resp_type = "Access-Accept".to_string();
+        assert_eq!(detect_attacks(&items, &ids, &ReasonCategories::default(), &settings).len(), 1);
+    }
+
+    #[test]
+    fn escapes_query_values() {
+        let finding = Finding {
+            kind: AttackKind::BruteForce,
+            source: Source::User,
+            value: "CORP\\j\"doe".to_string(),
+            first: DateTime::default(),
+            last: DateTime::default(),
+            failures: 1,
+            users: 1,
+            rows: vec![0],
+            codes: vec![0, 16],
+        };
+        assert_eq!(finding.query(), "user=\"CORP\\\\j\\\"doe\" ((response=Access-Reject NOT code>0) OR code=16)");
+        let query = crate::Query::parse(&finding.query()).expect("valid query");
+        let req = RadiusRequest { user: "CORP\\j\"doe".to_string(), ..failure(0, "", "") };
+        assert!(query.matches(&req));
+    }
+}
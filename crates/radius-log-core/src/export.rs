@@ -0,0 +1,265 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::Path;
+
+use rust_xlsxwriter::{Color, Format, Workbook, XlsxError};
+
+use crate::model::{LogColumn, RadiusRequest};
+use crate::time::TimeSettings;
+
+// Excel number format of the Timestamp cells
+const DATE_FORMAT: &str = "yyyy-mm-dd hh:mm:ss.000";
+
+// Width of the attribute columns, in pixels
+const ATTRIBUTE_WIDTH_PX: u32 = 150;
+
+/// One column of an export, in display order.
+#[derive(Debug, Clone)]
+pub struct ExportColumn {
+    pub column: LogColumn,
+    /// Header text (localized by the caller).
+    pub header: String,
+    /// Width in pixels, as in the list view.
+    pub width_px: u32,
+}
+
+/// Names of the attributes logged for the rows `ids` of `items`, in order of first
+/// appearance (case-insensitive): the attribute columns of a full export.
+#[must_use]
+pub fn attribute_names(items: &[RadiusRequest], ids: &[usize]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut names = Vec::new();
+    for req in ids.iter().filter_map(|&i| items.get(i)) {
+        for (name, _) in req.attributes.iter() {
+            if seen.insert(name.to_ascii_lowercase()) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    names
+}
+
+/// Writes the rows `ids` of `items` (the filtered, sorted view) to an `.xlsx` workbook:
+/// the `columns`, then one column per attribute of `attributes` (NPS names).
+///
+/// Timestamps are real Excel dates (the wall-clock time shown by `time`),
+/// the header row is bold, frozen and carries an autofilter, and rows keep
+/// the green / red colour of their response.
+///
+/// # Errors
+/// Returns the writer error, e.g. when the view exceeds Excel's row limit.
+pub fn write_xlsx(
+    items: &[RadiusRequest],
+    ids: &[usize],
+    columns: &[ExportColumn],
+    attributes: &[String],
+    time: &TimeSettings,
+) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("RADIUS")?;
+
+    let header_format = Format::new().set_bold();
+    for (c, col) in columns.iter().enumerate() {
+        let c = c as u16;
+        sheet.write_string_with_format(0, c, &col.header, &header_format)?;
+        sheet.set_column_width_pixels(c, col.width_px)?;
+    }
+    let first_attribute = columns.len();
+    for (a, name) in attributes.iter().enumerate() {
+        let c = (first_attribute + a) as u16;
+        sheet.write_string_with_format(0, c, name, &header_format)?;
+        sheet.set_column_width_pixels(c, ATTRIBUTE_WIDTH_PX)?;
+    }
+
+    // One (text, date) format pair per row colour
+    let mut formats = HashMap::new();
+    for (r, req) in ids.iter().filter_map(|&i| items.get(i)).enumerate() {
+        let row = r as u32 + 1;
+        let (text_format, date_format) = formats.entry(req.bg_color).or_insert_with(|| row_formats(req.bg_color));
+
+        for (c, col) in columns.iter().enumerate() {
+            let c = c as u16;
+            match (col.column, time.shown_time(req)) {
+                (LogColumn::Timestamp, Some(shown)) => sheet.write_datetime_with_format(row, c, shown, date_format)?,
+                (column, _) => sheet.write_string_with_format(row, c, req.column_text(column), text_format)?,
+            };
+        }
+        for (a, name) in attributes.iter().enumerate() {
+            sheet.write_string_with_format(row, (first_attribute + a) as u16, req.attribute(name).unwrap_or_default(), text_format)?;
+        }
+    }
+
+    let width = columns.len() + attributes.len();
+    if width > 0 {
+        sheet.set_freeze_panes(1, 0)?;
+        sheet.autofilter(0, 0, ids.len() as u32, width as u16 - 1)?;
+    }
+
+    workbook.save_to_buffer()
+}
+
+/// [`write_xlsx`] straight to a file.
+///
+/// # Errors
+/// Returns the writer or I/O error.
+pub fn export_xlsx(
+    path: impl AsRef<Path>,
+    items: &[RadiusRequest],
+    ids: &[usize],
+    columns: &[ExportColumn],
+    attributes: &[String],
+    time: &TimeSettings,
+) -> anyhow::Result<()> {
+    let buffer = write_xlsx(items, ids, columns, attributes, time)?;
+    std::fs::write(path, buffer)?;
+    Ok(())
+}
+
+/// Writes the rows `ids` of `items` as delimited text (`,` for CSV, `\t` for TSV), header first:
+/// the `columns`, then the `attributes` (NPS names, empty cell when a request lacks one).
+///
+/// Cells are the list texts; a cell containing the delimiter, a quote or a line
+/// break is quoted, RFC 4180 style. A cell that a spreadsheet would read as a
+/// formula (starting with `=`, `+`, `-`, `@`, a tab or a carriage return) gets a
+/// leading `'`: user names and station ids are chosen by the client.
+///
+/// # Errors
+/// Returns the I/O error of `out`.
+pub fn write_csv(
+    mut out: impl io::Write,
+    items: &[RadiusRequest],
+    ids: &[usize],
+    columns: &[ExportColumn],
+    attributes: &[String],
+    delimiter: char,
+) -> io::Result<()> {
+    let mut line = String::new();
+    let mut push_row = |out: &mut dyn io::Write, cells: &mut dyn Iterator<Item = &str>| {
+        line.clear();
+        for (c, cell) in cells.enumerate() {
+            if c > 0 {
+                line.push(delimiter);
+            }
+            let cell = &*neutralize_formula(cell);
+            if cell.contains([delimiter, '"', '\n', '\r']) {
+                line.push('"');
+                line.push_str(&cell.replace('"', "\"\""));
+                line.push('"');
+            } else {
+                line.push_str(cell);
+            }
+        }
+        line.push_str("\r\n");
+        out.write_all(line.as_bytes())
+    };
+
+    push_row(&mut out, &mut columns.iter().map(|col| col.header.as_str()).chain(attributes.iter().map(String::as_str)))?;
+    for req in ids.iter().filter_map(|&i| items.get(i)) {
+        let attribute_cells = attributes.iter().map(|name| req.attribute(name).unwrap_or_default());
+        push_row(&mut out, &mut columns.iter().map(|col| req.column_text(col.column)).chain(attribute_cells))?;
+    }
+    out.flush()
+}
+
+// CSV injection: `'` makes spreadsheets show the cell as text instead of evaluating it
+fn neutralize_formula(cell: &str) -> Cow<'_, str> {
+    if cell.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        Cow::Owned(format!("'{cell}"))
+    } else {
+        Cow::Borrowed(cell)
+    }
+}
+
+// Same colours as the list: white text on the response colour
+fn row_formats(bg_color: Option<(u8, u8, u8)>) -> (Format, Format) {
+    let mut text = Format::new();
+    if let Some((r, g, b)) = bg_color {
+        let rgb = (u32::from(r) << 16) | (u32::from(g) << 8) | u32::from(b);
+        text = text.set_background_color(Color::RGB(rgb)).set_font_color(Color::White);
+    }
+    let date = text.clone().set_num_format(DATE_FORMAT);
+    (text, date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_reader;
+    use crate::test_support::SAMPLE;
+    use std::io::{Cursor, Read};
+
+    fn columns(cols: &[LogColumn]) -> Vec<ExportColumn> {
+        cols.iter().map(|&column| ExportColumn { column, header: format!("{column:?}"), width_px: 120 }).collect()
+    }
+
+    // Raw XML of one part of the package
+    fn part(xlsx: &[u8], name: &str) -> String {
+        let mut zip = zip::ZipArchive::new(Cursor::new(xlsx)).expect("zip");
+        let mut out = String::new();
+        zip.by_name(name).expect("part").read_to_string(&mut out).expect("read");
+        out
+    }
+
+    #[test]
+    fn writes_view_in_column_order() {
+        let (items, _) = parse_reader(Cursor::new(SAMPLE.as_bytes()), SAMPLE.len() as u64, None).expect("parse");
+        let cols = columns(&[LogColumn::User, LogColumn::Timestamp, LogColumn::ResponseType]);
+        // Sorted view: second request first
+        let attributes = ["NP-Policy-Name".to_string()];
+        let xlsx = write_xlsx(&items, &[1, 0], &cols, &attributes, &TimeSettings::default()).expect("write");
+
+        let sheet = part(&xlsx, "xl/worksheets/sheet1.xml");
+        assert!(sheet.contains("<pane ySplit=\"1\""), "frozen header");
+        assert!(sheet.contains("<autoFilter ref=\"A1:D3\"/>"));
+        // Timestamps are numbers (serial dates), not shared strings
+        assert!(sheet.contains("<c r=\"B2\" s=\"") && !sheet.contains("<c r=\"B2\" s=\"1\" t=\"s\""));
+
+        let strings = part(&xlsx, "xl/sharedStrings.xml");
+        let alice = strings.find(">alice<").expect("alice");
+        let jdoe = strings.find(">CORP\\jdoe<").expect("jdoe");
+        assert!(strings.find(">User<").expect("header") < alice && alice < jdoe);
+        assert!(strings.contains(">NP-Policy-Name<"));
+
+        let styles = part(&xlsx, "xl/styles.xml");
+        assert!(styles.contains("FFDC3545") && styles.contains("FF198754"), "row colours");
+    }
+
+    #[test]
+    fn writes_delimited_text() {
+        let mut items = vec![
+            RadiusRequest { user: "CORP\\jdoe".to_string(), reason: "Denied, see \"policy\"".to_string(), ..Default::default() },
+            RadiusRequest { user: "alice".to_string(), ..Default::default() },
+        ];
+        items[0].attributes.push("NP-Policy-Name", "Wifi");
+        items[1].attributes.push("Called-Station-Id", "AA-BB");
+        items[1].attributes.push("np-policy-name", "Lan");
+        let cols = columns(&[LogColumn::User, LogColumn::Reason]);
+        let attributes = attribute_names(&items, &[1, 0]);
+        assert_eq!(attributes, ["Called-Station-Id", "np-policy-name"]);
+
+        let mut out = Vec::new();
+        write_csv(&mut out, &items, &[1, 0], &cols, &attributes, ',').expect("write");
+        assert_eq!(
+            String::from_utf8(out).expect("utf-8"),
+            "User,Reason,Called-Station-Id,np-policy-name\r\nalice,,AA-BB,Lan\r\nCORP\\jdoe,\"Denied, see \"\"policy\"\"\",,Wifi\r\n"
+        );
+
+        // Client-controlled values are never formulas in a spreadsheet
+        let hostile = vec![RadiusRequest {
+            user: "=HYPERLINK(\"http://x\",\"a\")".to_string(),
+            mac: "+1-2".to_string(),
+            ap_name: "@SUM(A1)".to_string(),
+            server: "-5".to_string(),
+            ..Default::default()
+        }];
+        let cols = columns(&[LogColumn::User, LogColumn::Mac, LogColumn::ApName, LogColumn::Server]);
+        let mut out = Vec::new();
+        write_csv(&mut out, &hostile, &[0], &cols, &[], '\t').expect("write");
+        assert_eq!(
+            String::from_utf8(out).expect("utf-8").lines().nth(1),
+            Some("\"'=HYPERLINK(\"\"http://x\"\",\"\"a\"\")\"\t'+1-2\t'@SUM(A1)\t'-5")
+        );
+    }
+}
@@ -0,0 +1,336 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::RwLock;
+
+use rayon::prelude::*;
+use regex::Regex;
+
+use crate::category::ErrorFilter;
+use crate::index::SearchIndex;
+use crate::model::RadiusRequest;
+use crate::query::Query;
+use crate::sort::SortSpec;
+
+// Optimization: Zero-allocation case-insensitive substring search
+#[must_use]
+pub fn contains_ignore_case(haystack: &str, needle_lower: &str) -> bool {
+    if needle_lower.is_empty() { return true; }
+    let needle_len = needle_lower.len();
+    let haystack_len = haystack.len();
+
+    if needle_len > haystack_len { return false; }
+
+    let needle_bytes = needle_lower.as_bytes();
+    let haystack_bytes = haystack.as_bytes();
+
+    for i in 0..=(haystack_len - needle_len) {
+        let mut split_match = true;
+        for j in 0..needle_len {
+            if !haystack_bytes[i + j].to_ascii_lowercase().eq(&needle_bytes[j]) {
+                split_match = false;
+                break;
+            }
+        }
+        if split_match { return true; }
+    }
+    false
+}
+
+impl RadiusRequest {
+    // OPTIMIZATION: Case-insensitive search without massive intermediate allocation
+    // Change signature to accept &str (already lowercase)
+    // Also the plain-text terms of a `Query`
+    #[must_use]
+    pub fn matches(&self, query_lower: &str) -> bool {
+        if query_lower.is_empty() { return true; }
+
+        // Use the zero-allocation helper
+        self.searched_texts().any(|text| contains_ignore_case(text, query_lower))
+    }
+
+    /// Regex counterpart of [`RadiusRequest::matches`], over the same columns.
+    #[must_use]
+    pub fn matches_regex(&self, re: &Regex) -> bool {
+        self.searched_texts().any(|text| re.is_match(text))
+    }
+
+    // Columns searched by plain text (the attributes are searched with `attr:`)
+    fn searched_texts(&self) -> impl Iterator<Item = &str> {
+        [&self.timestamp, &self.user, &self.mac, &self.ap_ip, &self.ap_name, &self.server, &self.reason, &self.req_type, &self.resp_type]
+            .into_iter()
+            .map(String::as_str)
+    }
+}
+
+/// Returns the indices of `items` matching `query` / the errors filter, sorted by `sort`.
+///
+/// With `errors`, only the failed sessions are kept: every row of a session
+/// with a targeted row, except its successful responses.
+#[must_use]
+pub fn filter_and_sort(
+    items: &[RadiusRequest],
+    query: &Query,
+    errors: Option<&ErrorFilter>,
+    sort: &SortSpec,
+) -> Vec<usize> {
+    filter_and_sort_indexed(items, None, query, errors, sort)
+}
+
+/// [`filter_and_sort`] that only checks the rows `index` cannot rule out.
+///
+/// `index` must reflect `items` as they are: pass [`SearchIndex::current`] for their generation.
+#[must_use]
+pub fn filter_and_sort_indexed(
+    items: &[RadiusRequest],
+    index: Option<&SearchIndex>,
+    query: &Query,
+    errors: Option<&ErrorFilter>,
+    sort: &SortSpec,
+) -> Vec<usize> {
+    filter_and_sort_cancellable(items, index, query, errors, sort, &|| false).unwrap_or_default()
+}
+
+/// [`filter_and_sort_indexed`] that gives up (`None`) as soon as `cancelled` returns `true`,
+/// e.g. when a newer search was typed. Filtering and sorting both run on the rayon pool.
+#[must_use]
+pub fn filter_and_sort_cancellable(
+    items: &[RadiusRequest],
+    index: Option<&SearchIndex>,
+    query: &Query,
+    errors: Option<&ErrorFilter>,
+    sort: &SortSpec,
+    cancelled: &(dyn Fn() -> bool + Sync),
+) -> Option<Vec<usize>> {
+    let failed_session_ids: HashSet<&str> = errors.map_or_else(HashSet::new, |errors| {
+        items
+            .par_iter()
+            .filter(|item| !item.session_id.is_empty() && errors.targets(item))
+            .map(|item| item.session_id.as_str())
+            .collect()
+    });
+
+    let keep = |&i: &usize| {
+        // Checked per row: a stale pass stops within a few rows per thread
+        if cancelled() {
+            return false;
+        }
+        let item = &items[i];
+        if let Some(errors) = errors {
+            let in_failed_session = !item.session_id.is_empty() && failed_session_ids.contains(item.session_id.as_str());
+            let success = item.resp_type == "Access-Accept" || item.resp_type == "Accounting-Response";
+            if !errors.targets(item) && (!in_failed_session || success) {
+                return false;
+            }
+        }
+        query.matches(item)
+    };
+
+    // 1. Filtering (Local collection, in parallel: regexes and attribute scans are not cheap)
+    let candidates = index.and_then(|index| query.candidates(index));
+    let mut ids: Vec<usize> = candidates.map_or_else(
+        || (0..items.len()).into_par_iter().filter(keep).collect(),
+        |rows| rows.into_par_iter().map(|row| row as usize).filter(keep).collect(),
+    );
+
+    if cancelled() {
+        return None;
+    }
+
+    // 2. Sorting (On the local collection)
+    ids.par_sort_unstable_by(|&a_idx, &b_idx| sort.compare(items, a_idx, b_idx));
+    (!cancelled()).then_some(ids)
+}
+
+/// Incremental counterpart of [`filter_and_sort`] for tail updates.
+///
+/// `ids` must be the current sorted result for the same criteria; only the
+/// `touched` rows (new or modified) are re-evaluated and spliced back in place.
+/// "Errors only" depends on every row of a session, so that mode falls back to
+/// a full pass.
+pub fn refilter_touched(
+    items: &[RadiusRequest],
+    ids: &mut Vec<usize>,
+    touched: &[usize],
+    query: &Query,
+    errors: Option<&ErrorFilter>,
+    sort: &SortSpec,
+) {
+    if errors.is_some() {
+        *ids = filter_and_sort(items, query, errors, sort);
+        return;
+    }
+
+    let touched_set: HashSet<usize> = touched.iter().copied().collect();
+    ids.retain(|i| !touched_set.contains(i));
+
+    for &i in touched {
+        let Some(item) = items.get(i) else { continue };
+        if !query.matches(item) { continue; }
+        // Insert after the rows that sort before it (ties are ordered by row)
+        let pos = ids.partition_point(|&j| sort.compare(items, j, i) != Ordering::Greater);
+        ids.insert(pos, i);
+    }
+}
+
+/// Filters and sorts `all_items` into `filtered_ids`, holding each lock as briefly as possible.
+///
+/// `query` is parsed once by the caller (see [`Query::parse`]). `generation` is the
+/// version of `all_items` (see [`SearchIndex`]), read under its lock. `index` is
+/// locked after `all_items`: writers must take the locks in the same order.
+///
+/// # Panics
+/// Panics if one of the locks is poisoned.
+pub fn apply_filter_logic(
+    all_items: &RwLock<Vec<RadiusRequest>>,
+    generation: &AtomicU64,
+    index: &RwLock<SearchIndex>,
+    filtered_ids: &RwLock<Vec<usize>>,
+    query: &Query,
+    errors: Option<&ErrorFilter>,
+    sort: &SortSpec,
+) {
+    // 1. Data reading (Read Lock)
+    let items = all_items.read().expect("Lock failed");
+    let index = index.read().expect("Lock failed");
+    let ids = filter_and_sort_indexed(&items, index.current(generation.load(AtomicOrdering::SeqCst)), query, errors, sort);
+
+    // Release read locks before write lock
+    drop(index);
+    drop(items);
+
+    // 2. Result writing (Write Lock - brief)
+    let mut filt_guard = filtered_ids.write().expect("Lock failed");
+    *filt_guard = ids;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::category::ReasonCategory;
+    use crate::model::LogColumn;
+
+    fn q(text: &str) -> Query {
+        Query::parse(text).expect("valid query")
+    }
+
+    fn req(user: &str, resp_type: &str, session_id: &str) -> RadiusRequest {
+        RadiusRequest {
+            user: user.to_string(),
+            resp_type: resp_type.to_string(),
+            session_id: session_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn contains_ignore_case_basic() {
+        assert!(contains_ignore_case("JDoe@Corp", "jdoe"));
+        assert!(contains_ignore_case("anything", ""));
+        assert!(!contains_ignore_case("ab", "abc"));
+        assert!(!contains_ignore_case("alice", "bob"));
+    }
+
+    #[test]
+    fn query_and_sort() {
+        let items = vec![req("bob", "Access-Accept", "1"), req("alice", "Access-Accept", "2"), req("carol", "Access-Accept", "3")];
+        assert_eq!(filter_and_sort(&items, &q(""), None, &SortSpec::by(LogColumn::User, false)), vec![1, 0, 2]);
+        assert_eq!(filter_and_sort(&items, &q(""), None, &SortSpec::by(LogColumn::User, true)), vec![2, 0, 1]);
+        assert_eq!(filter_and_sort(&items, &q("  ALI "), None, &SortSpec::by(LogColumn::User, false)), vec![1]);
+
+        // Second level breaks the ties of the first
+        let items = vec![req("bob", "Access-Accept", "1"), req("alice", "Access-Reject", "2"), req("bob", "Access-Reject", "3")];
+        let mut sort = SortSpec::by(LogColumn::User, false);
+        sort.click(LogColumn::ResponseType, true);
+        sort.click(LogColumn::ResponseType, true);
+        assert_eq!(filter_and_sort(&items, &q(""), None, &sort), vec![1, 2, 0]);
+    }
+
+    #[test]
+    fn timestamps_sort_chronologically() {
+        let items: Vec<RadiusRequest> = ["12/31/2023 23:59:59.000", "01/15/2024 08:00:00.000", "02/01/2023 00:00:00.000"]
+            .iter()
+            .map(|ts| {
+                let mut r = req("u", "Access-Accept", "1");
+                r.logged_at = crate::time::parse_nps_timestamp(ts);
+                r.time = r.logged_at.map(|t| t.and_utc());
+                (*ts).clone_into(&mut r.timestamp);
+                r
+            })
+            .collect();
+        assert_eq!(filter_and_sort(&items, &q(""), None, &SortSpec::by(LogColumn::Timestamp, false)), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn addresses_sort_numerically() {
+        let items: Vec<RadiusRequest> = [("10.0.0.100", "AA-BB-CC-DD-EE-02"), ("10.0.0.9", "aabb.ccdd.ee01"), ("n/a", "aa:bb:cc:dd:ee:03")]
+            .iter()
+            .map(|&(ip, mac)| RadiusRequest {
+                ap_ip: ip.to_string(),
+                ap_ip_addr: ip.parse().ok(),
+                mac: mac.to_string(),
+                mac_addr: crate::net::parse_mac(mac),
+                ..Default::default()
+            })
+            .collect();
+        assert_eq!(filter_and_sort(&items, &q(""), None, &SortSpec::by(LogColumn::ApIp, false)), vec![2, 1, 0]);
+        assert_eq!(filter_and_sort(&items, &q(""), None, &SortSpec::by(LogColumn::Mac, false)), vec![1, 0, 2]);
+    }
+
+    #[test]
+    fn errors_only_keeps_rejected_sessions() {
+        let items = vec![req("bob", "Access-Reject", "1"), req("alice", "Access-Accept", "2"), req("bob", "", "1")];
+        assert_eq!(filter_and_sort(&items, &q(""), Some(&ErrorFilter::default()), &SortSpec::by(LogColumn::User, false)).len(), 2);
+        assert!(filter_and_sort(&items, &q("alice"), Some(&ErrorFilter::default()), &SortSpec::by(LogColumn::User, false)).is_empty());
+    }
+
+    #[test]
+    fn errors_only_targets_a_category() {
+        let mut items = vec![req("bob", "Access-Reject", "1"), req("alice", "Access-Reject", "2"), req("bob", "", "1")];
+        items[0].reason_code = Some(16);
+        items[1].reason_code = Some(268);
+        let certs = ErrorFilter { category: Some(ReasonCategory::Certificate), ..Default::default() };
+        assert_eq!(filter_and_sort(&items, &q(""), Some(&certs), &SortSpec::by(LogColumn::User, false)), vec![1]);
+        let credentials = ErrorFilter { category: Some(ReasonCategory::Credentials), ..Default::default() };
+        assert_eq!(filter_and_sort(&items, &q(""), Some(&credentials), &SortSpec::by(LogColumn::User, false)), vec![0, 2]);
+    }
+
+    #[test]
+    fn cancelled_pass_gives_up() {
+        let items = vec![req("bob", "Access-Reject", "1"), req("alice", "Access-Accept", "2")];
+        let run = |cancelled: &(dyn Fn() -> bool + Sync)| filter_and_sort_cancellable(&items, None, &q(""), Some(&ErrorFilter::default()), &SortSpec::by(LogColumn::User, false), cancelled);
+        assert_eq!(run(&|| false), Some(vec![0]));
+        assert_eq!(run(&|| true), None);
+    }
+
+    #[test]
+    fn refilter_touched_matches_full_pass() {
+        let mut items = vec![req("bob", "Access-Accept", "1"), req("dave", "Access-Accept", "2"), req("alice", "Access-Accept", "3")];
+        let mut ids = filter_and_sort(&items, &q("o"), None, &SortSpec::by(LogColumn::User, false));
+        assert_eq!(ids, vec![0]);
+
+        // One row modified so it now matches, two rows appended (one matching)
+        items[1].user = "bo".to_string();
+        items.push(req("carol", "Access-Accept", "4"));
+        items.push(req("eve", "Access-Reject", "5"));
+        refilter_touched(&items, &mut ids, &[1, 3, 4], &q("o"), None, &SortSpec::by(LogColumn::User, false));
+        assert_eq!(ids, filter_and_sort(&items, &q("o"), None, &SortSpec::by(LogColumn::User, false)));
+        assert_eq!(ids, vec![1, 0, 3]);
+    }
+
+    #[test]
+    fn apply_writes_result() {
+        let all = RwLock::new(vec![req("bob", "Access-Accept", "1")]);
+        let generation = AtomicU64::new(1);
+        let index = RwLock::new(SearchIndex::build(&all.read().expect("Lock failed"), 1));
+        let ids = RwLock::new(Vec::new());
+        apply_filter_logic(&all, &generation, &index, &ids, &q("bob"), None, &SortSpec::by(LogColumn::Timestamp, true));
+        assert_eq!(*ids.read().expect("Lock failed"), vec![0]);
+
+        // Rows replaced (same count) before the index is rebuilt: it is not trusted
+        *all.write().expect("Lock failed") = vec![req("alice", "Access-Accept", "1")];
+        generation.store(2, AtomicOrdering::SeqCst);
+        apply_filter_logic(&all, &generation, &index, &ids, &q("alice"), None, &SortSpec::by(LogColumn::Timestamp, true));
+        assert_eq!(*ids.read().expect("Lock failed"), vec![0]);
+    }
+}
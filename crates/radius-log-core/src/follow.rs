@@ -0,0 +1,194 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::model::RadiusRequest;
+use crate::parser::list_log_files;
+use crate::report::ParseReport;
+use crate::tail::{LogTail, TailRead};
+use crate::time::TimeSettings;
+
+/// What a [`LogFollower::poll`] found.
+#[derive(Debug, Default)]
+pub struct FollowUpdate {
+    /// Requests that got their outcome since the previous poll, in log order.
+    pub completed: Vec<RadiusRequest>,
+    /// Records read, and the ones that could not be parsed.
+    pub report: ParseReport,
+    /// File now followed, when the folder rotated to a new one.
+    pub switched_to: Option<PathBuf>,
+}
+
+/// `tail -f` for NPS logs: follows a file, or the newest `.log` file of a folder
+/// across the daily rotation, and hands out each request once, when it is complete
+/// (see [`RadiusRequest::is_complete`]).
+///
+/// Requests are grouped per file: an exchange cut by a rotation is reported
+/// from the part logged in the new file.
+pub struct LogFollower {
+    source: PathBuf,
+    time: TimeSettings,
+    tail: Option<LogTail>,
+    requests: Vec<RadiusRequest>,
+    // Parallel to `requests`
+    reported: Vec<bool>,
+    // Files of the folder already left: never followed again
+    left: HashSet<PathBuf>,
+}
+
+impl LogFollower {
+    /// Starts following `path` (a log file or a folder of `.log` files).
+    ///
+    /// With `from_start`, the first poll reports the requests already logged;
+    /// otherwise only the ones completed from now on.
+    ///
+    /// # Errors
+    /// Returns an error if the file or folder cannot be read.
+    pub fn open(path: impl AsRef<Path>, time: TimeSettings, from_start: bool) -> anyhow::Result<Self> {
+        let mut follower = Self {
+            source: path.as_ref().to_path_buf(),
+            time,
+            tail: None,
+            requests: Vec::new(),
+            reported: Vec::new(),
+            left: HashSet::new(),
+        };
+        if let Some(file) = follower.newest_file()? {
+            follower.follow(&file)?;
+            if !from_start {
+                follower.reported = follower.requests.iter().map(RadiusRequest::is_complete).collect();
+            }
+        }
+        Ok(follower)
+    }
+
+    /// File currently followed (`None` while a followed folder has no log file).
+    #[must_use]
+    pub fn current_file(&self) -> Option<&Path> {
+        self.tail.as_ref().map(LogTail::path)
+    }
+
+    /// Reads what was appended to the current file, then moves to a newer file of the folder, if any.
+    ///
+    /// # Errors
+    /// Returns an error if a file cannot be read.
+    pub fn poll(&mut self) -> anyhow::Result<FollowUpdate> {
+        let mut update = FollowUpdate::default();
+        if let Some(tail) = &mut self.tail {
+            match tail.read_appended()? {
+                TailRead::Appended(chunk) => {
+                    update.report.merge(chunk.report);
+                    let touched = tail.merge(chunk.events, &mut self.requests);
+                    for &i in &touched {
+                        self.time.apply(&mut self.requests[i]);
+                    }
+                }
+                // Rewritten in place: everything in it is new
+                TailRead::Truncated => {
+                    let path = tail.path().to_path_buf();
+                    update.report.merge(self.follow(&path)?);
+                }
+            }
+        }
+
+        if let Some(newest) = self.newest_file()? {
+            if self.current_file() != Some(newest.as_path()) {
+                // The current file was drained above: report its last requests before leaving it
+                if let Some(previous) = self.current_file() {
+                    self.left.insert(previous.to_path_buf());
+                }
+                self.collect_completed(&mut update.completed);
+                update.report.merge(self.follow(&newest)?);
+                update.switched_to = Some(newest);
+            }
+        }
+
+        self.collect_completed(&mut update.completed);
+        Ok(update)
+    }
+
+    // Appends the complete requests not reported yet to `completed`
+    fn collect_completed(&mut self, completed: &mut Vec<RadiusRequest>) {
+        self.reported.resize(self.requests.len(), false);
+        for (req, reported) in self.requests.iter().zip(self.reported.iter_mut()) {
+            if !*reported && req.is_complete() {
+                *reported = true;
+                completed.push(req.clone());
+            }
+        }
+    }
+
+    // Parses `file` entirely, replacing the requests of the previous file
+    fn follow(&mut self, file: &Path) -> anyhow::Result<ParseReport> {
+        let (tail, mut requests, report) = LogTail::open(file, None)?;
+        self.time.apply_all(&mut requests);
+        self.tail = Some(tail);
+        self.requests = requests;
+        self.reported.clear();
+        Ok(report)
+    }
+
+    // The source itself, or the newest log file of the source folder not left yet
+    fn newest_file(&self) -> anyhow::Result<Option<PathBuf>> {
+        if !self.source.is_dir() {
+            return Ok(Some(self.source.clone()));
+        }
+        let files = list_log_files(&self.source)?;
+        Ok(files.into_iter().rev().find(|file| !self.left.contains(file)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::SAMPLE;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    fn append(path: &Path, data: &str) {
+        let mut f = OpenOptions::new().append(true).create(true).open(path).expect("open");
+        f.write_all(data.as_bytes()).expect("append");
+    }
+
+    fn users(update: &FollowUpdate) -> Vec<&str> {
+        update.completed.iter().map(|req| req.user.as_str()).collect()
+    }
+
+    #[test]
+    fn reports_completed_requests_across_rotation() {
+        let folder = std::env::temp_dir().join(format!("radius-log-core-{}-follow", std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).expect("folder");
+        let lines: Vec<&str> = SAMPLE.lines().collect();
+
+        // jdoe is rejected before we start, alice only sent her request
+        let day1 = folder.join("IN240115.log");
+        append(&day1, &format!("{}\n{}\n{}\n", lines[0], lines[1], lines[2]));
+        let mut follower = LogFollower::open(&folder, TimeSettings::default(), false).expect("open");
+        assert!(follower.poll().expect("poll").completed.is_empty());
+
+        // Her response, half written then complete
+        let (first_half, second_half) = lines[3].split_at(40);
+        append(&day1, first_half);
+        assert!(follower.poll().expect("poll").completed.is_empty());
+        append(&day1, &format!("{second_half}\n"));
+        let update = follower.poll().expect("poll");
+        assert_eq!(users(&update), ["alice"]);
+        assert_eq!(update.completed[0].resp_type, "Access-Accept");
+        assert!(follower.poll().expect("poll").completed.is_empty(), "reported once");
+
+        // Rotation: bob's exchange ends the old file and the next day starts in a new one, between two polls
+        let bob = [lines[2], lines[3]].map(|line| line.replace("alice", "bob").replace("01/15/2024 2<", "01/15/2024 3<"));
+        append(&day1, &format!("{}\n{}\n", bob[0], bob[1]));
+        let day2 = folder.join("IN240116.log");
+        append(&day2, &format!("{}\n{}\n", lines[0], lines[1]));
+        let update = follower.poll().expect("poll");
+        assert_eq!(update.switched_to.as_deref(), Some(day2.as_path()));
+        assert_eq!(users(&update), ["bob", "CORP\\jdoe"]);
+        assert_eq!(follower.current_file(), Some(day2.as_path()));
+
+        // From the start: what is already logged comes first
+        let mut replay = LogFollower::open(&day1, TimeSettings::default(), true).expect("open");
+        assert_eq!(users(&replay.poll().expect("poll")), ["CORP\\jdoe", "alice", "bob"]);
+        let _ = fs::remove_dir_all(&folder);
+    }
+}
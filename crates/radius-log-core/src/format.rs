@@ -0,0 +1,51 @@
+use crate::model::Event;
+use crate::odbc::parse_odbc_line;
+use crate::xml::parse_xml_event;
+
+/// On-disk layout of an NPS/IAS log file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// "DTS compliant" XML: a sequence of `<Event>` elements.
+    Xml,
+    /// Legacy IAS / "ODBC-compatible" format: one comma-separated record per line.
+    Odbc,
+}
+
+impl LogFormat {
+    /// Guesses the format from the first bytes of a file.
+    ///
+    /// XML logs start with `<` (after an optional BOM and whitespace);
+    /// anything else is treated as the comma-separated format.
+    /// Returns `None` while there is nothing but whitespace to look at.
+    #[must_use]
+    pub fn detect(head: &[u8]) -> Option<Self> {
+        let head = head.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(head);
+        head.iter()
+            .find(|b| !b.is_ascii_whitespace())
+            .map(|&b| if b == b'<' { Self::Xml } else { Self::Odbc })
+    }
+
+    /// Deserializes one raw record of this format.
+    ///
+    /// # Errors
+    /// Returns why the record is not valid (encoding, XML error, missing columns).
+    pub fn deserialize(self, bytes: &[u8]) -> anyhow::Result<Event> {
+        let text = std::str::from_utf8(bytes)?;
+        match self {
+            Self::Xml => Ok(parse_xml_event(text)?),
+            Self::Odbc => parse_odbc_line(text).ok_or_else(|| anyhow::anyhow!("not an ODBC record (missing Packet-Type column)")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_format() {
+        assert_eq!(LogFormat::detect(b"\xEF\xBB\xBF\r\n<Event>"), Some(LogFormat::Xml));
+        assert_eq!(LogFormat::detect(b" \r\n"), None);
+        assert_eq!(LogFormat::detect(b"\"NPS01\",\"IAS\",01/15/2024"), Some(LogFormat::Odbc));
+    }
+}
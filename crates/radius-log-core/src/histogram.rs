@@ -0,0 +1,271 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::ops::Range;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::model::RadiusRequest;
+use crate::time::{TimeRange, Zone};
+
+/// Most intervals of a [`TimeSeries`] (11 years of hours, 69 days of minutes).
+pub const MAX_BUCKETS: usize = 100_000;
+
+/// Width of the intervals of a [`TimeSeries`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BucketSize {
+    Minute,
+    FiveMinutes,
+    Hour,
+}
+
+impl BucketSize {
+    pub const ALL: [Self; 3] = [Self::Minute, Self::FiveMinutes, Self::Hour];
+
+    #[must_use]
+    pub const fn seconds(self) -> i64 {
+        match self {
+            Self::Minute => 60,
+            Self::FiveMinutes => 300,
+            Self::Hour => 3600,
+        }
+    }
+
+    /// Fluent key of the display name.
+    #[must_use]
+    pub const fn ftl_key(self) -> &'static str {
+        match self {
+            Self::Minute => "bucket-minute",
+            Self::FiveMinutes => "bucket-5min",
+            Self::Hour => "bucket-hour",
+        }
+    }
+
+    /// Short name, as written on the command line.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Minute => "minute",
+            Self::FiveMinutes => "5min",
+            Self::Hour => "hour",
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|size| size.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Finest size that keeps a chart of `span` readable: minutes up to 6 hours,
+    /// 5 minutes up to 2 days, hours beyond.
+    #[must_use]
+    pub fn for_span(span: TimeDelta) -> Self {
+        if span <= TimeDelta::hours(6) {
+            Self::Minute
+        } else if span <= TimeDelta::days(2) {
+            Self::FiveMinutes
+        } else {
+            Self::Hour
+        }
+    }
+}
+
+/// Requests whose timestamp falls in one interval, by outcome.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Bucket {
+    pub start: DateTime<Utc>,
+    pub accepted: usize,
+    pub rejected: usize,
+    /// No Accept or Reject (accounting, challenge or request left unanswered).
+    pub other: usize,
+    /// Requests per non-zero Reason-Code.
+    pub reasons: BTreeMap<u32, usize>,
+}
+
+impl Bucket {
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.accepted + self.rejected + self.other
+    }
+}
+
+/// Requests per interval, from the first to the last timestamp, without gaps:
+/// shows *when* rejects started, which the list cannot.
+///
+/// Intervals are aligned on UTC minutes / hours (local hours for zones with a
+/// whole-hour offset).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimeSeries {
+    pub size: BucketSize,
+    pub buckets: Vec<Bucket>,
+    /// Requests left out, too far from the others to fit in [`MAX_BUCKETS`] intervals
+    /// (e.g. a server clock reset to 1970).
+    pub outside: usize,
+}
+
+impl TimeSeries {
+    /// Time series of the rows `ids` of `items`, in buckets of `size`
+    /// ([`BucketSize::for_span`] of their span when `None`).
+    ///
+    /// At most [`MAX_BUCKETS`] intervals are kept, where the requests are the
+    /// densest; the others are counted in [`TimeSeries::outside`].
+    /// `None` when none of them has a timestamp.
+    #[must_use]
+    pub fn compute(items: &[RadiusRequest], ids: &[usize], size: Option<BucketSize>) -> Option<Self> {
+        let mut timed: Vec<(DateTime<Utc>, &RadiusRequest)> =
+            ids.iter().filter_map(|&i| items.get(i)).filter_map(|req| Some((req.time?, req))).collect();
+        timed.sort_by_key(|&(time, _)| time);
+
+        // Widest span that fits in the buckets (one is lost to the alignment)
+        let max_span = TimeDelta::seconds(size.unwrap_or(BucketSize::Hour).seconds() * (MAX_BUCKETS as i64 - 1));
+        let kept = densest_run(&timed, max_span);
+        let outside = timed.len() - kept.len();
+        let timed = &timed[kept];
+        let first = timed.first()?.0;
+        let last = timed.last()?.0;
+
+        let size = size.unwrap_or_else(|| BucketSize::for_span(last - first));
+        let secs = size.seconds();
+        let origin = first.timestamp().div_euclid(secs) * secs;
+        let count = (last.timestamp().div_euclid(secs) * secs - origin) / secs + 1;
+        let mut buckets: Vec<Bucket> = (0..count)
+            .map(|i| Bucket { start: DateTime::from_timestamp(origin + i * secs, 0).unwrap_or_default(), ..Default::default() })
+            .collect();
+
+        for &(time, req) in timed {
+            let bucket = &mut buckets[((time.timestamp() - origin) / secs) as usize];
+            match req.resp_type.as_str() {
+                "Access-Accept" => bucket.accepted += 1,
+                "Access-Reject" => bucket.rejected += 1,
+                _ => bucket.other += 1,
+            }
+            if let Some(code) = req.reason_code.filter(|&code| code != 0) {
+                *bucket.reasons.entry(code).or_default() += 1;
+            }
+        }
+        Some(Self { size, buckets, outside })
+    }
+
+    /// Largest bucket (height of a chart).
+    #[must_use]
+    pub fn max_total(&self) -> usize {
+        self.buckets.iter().map(Bucket::total).max().unwrap_or(0)
+    }
+
+    /// Instants covered by the buckets `first..=last` (in any order, clamped): the
+    /// time filter of a range selected on a chart.
+    #[must_use]
+    pub fn range(&self, first: usize, last: usize) -> TimeRange {
+        let end = self.buckets.len().saturating_sub(1);
+        let (first, last) = (first.min(last).min(end), first.max(last).min(end));
+        let bound = |i: usize| self.buckets.get(i).map(|bucket| bucket.start);
+        TimeRange {
+            from: bound(first),
+            to: bound(last).map(|start| start + TimeDelta::seconds(self.size.seconds()) - TimeDelta::nanoseconds(1)),
+        }
+    }
+
+    /// Writes the series as CSV: one line per bucket with its start (wall-clock
+    /// time in `zone`), the counts per outcome, then one column per Reason-Code seen.
+    ///
+    /// # Errors
+    /// Returns the I/O error of `out`.
+    pub fn write_csv(&self, mut out: impl io::Write, zone: Zone) -> io::Result<()> {
+        let codes: BTreeSet<u32> = self.buckets.iter().flat_map(|bucket| bucket.reasons.keys().copied()).collect();
+
+        out.write_all(b"start,total,accepted,rejected,other")?;
+        for code in &codes {
+            write!(out, ",code_{code}")?;
+        }
+        out.write_all(b"\r\n")?;
+
+        for bucket in &self.buckets {
+            let start = zone.to_wall_clock(bucket.start).format("%Y-%m-%d %H:%M:%S");
+            write!(out, "{start},{},{},{},{}", bucket.total(), bucket.accepted, bucket.rejected, bucket.other)?;
+            for code in &codes {
+                write!(out, ",{}", bucket.reasons.get(code).copied().unwrap_or(0))?;
+            }
+            out.write_all(b"\r\n")?;
+        }
+        out.flush()
+    }
+}
+
+// Longest run of `timed` (in time order) spanning at most `max_span`, the first one on ties
+fn densest_run<T>(timed: &[(DateTime<Utc>, T)], max_span: TimeDelta) -> Range<usize> {
+    let mut best = 0..timed.len().min(1);
+    let mut left = 0;
+    for right in 0..timed.len() {
+        while timed[right].0 - timed[left].0 > max_span {
+            left += 1;
+        }
+        if right + 1 - left > best.len() {
+            best = left..right + 1;
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_reader;
+    use crate::test_support::SAMPLE;
+    use crate::time::TimeSettings;
+    use std::io::Cursor;
+
+    #[test]
+    fn buckets_requests_and_exports() {
+        // jdoe rejected at 08:00, alice accepted at 08:01, jdoe rejected again at 08:03
+        let lines: Vec<&str> = SAMPLE.lines().collect();
+        let again = |line: &str| line.replace("08:00:00", "08:03:00").replace("01/15/2024 1<", "01/15/2024 3<");
+        let log = format!("{SAMPLE}{}\n{}\n", again(lines[0]), again(lines[1]));
+        let (mut items, _) = parse_reader(Cursor::new(log.clone()), log.len() as u64, None).expect("parse");
+        let time = TimeSettings { default_server_zone: Zone::Utc, ..Default::default() };
+        time.apply_all(&mut items);
+        let ids: Vec<usize> = (0..items.len()).collect();
+
+        let series = TimeSeries::compute(&items, &ids, None).expect("series");
+        assert_eq!(series.size, BucketSize::Minute);
+        let counts: Vec<(usize, usize, usize)> = series.buckets.iter().map(|b| (b.accepted, b.rejected, b.other)).collect();
+        assert_eq!(counts, [(0, 1, 0), (1, 0, 0), (0, 0, 0), (0, 1, 0)], "empty minutes are kept");
+        assert_eq!(series.buckets[3].reasons, BTreeMap::from([(16, 1)]));
+        assert_eq!(series.max_total(), 1);
+
+        let range = series.range(3, 1);
+        assert_eq!(range.from.map(|t| t.to_rfc3339()), Some("2024-01-15T08:01:00+00:00".to_string()));
+        assert!(range.contains(items[2].time) && !range.contains(items[0].time));
+
+        let mut csv = Vec::new();
+        series.write_csv(&mut csv, Zone::Utc).expect("write");
+        let csv = String::from_utf8(csv).expect("utf-8");
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+        assert_eq!(lines[..2], ["start,total,accepted,rejected,other,code_16", "2024-01-15 08:00:00,1,0,1,0,1"]);
+        assert_eq!(lines.len(), 6);
+
+        let hourly = TimeSeries::compute(&items, &ids, Some(BucketSize::Hour)).expect("series");
+        assert_eq!(hourly.buckets.len(), 1);
+        assert!(TimeSeries::compute(&items, &[], None).is_none());
+    }
+
+    #[test]
+    fn leaves_out_distant_timestamps() {
+        let at = |secs: i64| RadiusRequest { time: DateTime::from_timestamp(secs, 0), ..Default::default() };
+        // Two hours of traffic in 2024, and one request from a clock reset to 1970
+        let items: Vec<RadiusRequest> = [1_705_305_600, 1_705_309_200, 1_705_312_800, 60].into_iter().map(at).collect();
+        let ids: Vec<usize> = (0..items.len()).collect();
+
+        let series = TimeSeries::compute(&items, &ids, None).expect("series");
+        assert_eq!((series.size, series.buckets.len(), series.outside), (BucketSize::Minute, 121, 1));
+        let minutes = TimeSeries::compute(&items, &ids, Some(BucketSize::Minute)).expect("series");
+        assert_eq!((minutes.buckets.len(), minutes.outside), (121, 1));
+    }
+
+    #[test]
+    fn picks_bucket_size_from_span() {
+        assert_eq!(BucketSize::for_span(TimeDelta::minutes(90)), BucketSize::Minute);
+        assert_eq!(BucketSize::for_span(TimeDelta::hours(24)), BucketSize::FiveMinutes);
+        assert_eq!(BucketSize::for_span(TimeDelta::days(30)), BucketSize::Hour);
+        assert_eq!(BucketSize::from_name("5MIN"), Some(BucketSize::FiveMinutes));
+    }
+}
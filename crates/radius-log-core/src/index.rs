@@ -0,0 +1,349 @@
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::filter::contains_ignore_case;
+use crate::model::{LogColumn, RadiusRequest};
+
+/// Columns answered by the [`SearchIndex`]: few distinct values, many rows each.
+///
+/// With the Timestamp, they are the columns searched by plain words.
+pub const INDEXED_COLUMNS: [LogColumn; 8] = [
+    LogColumn::User,
+    LogColumn::Mac,
+    LogColumn::ApIp,
+    LogColumn::ApName,
+    LogColumn::Server,
+    LogColumn::Reason,
+    LogColumn::Type,
+    LogColumn::ResponseType,
+];
+
+/// Distinct values of one column, their rows, and a trigram index over the values.
+#[derive(Debug, Default)]
+struct Vocabulary {
+    values: Vec<String>,
+    ids: HashMap<String, u32>,
+    /// Rows of each value, ascending.
+    rows: Vec<Vec<u32>>,
+    /// Values containing each (ASCII-lowercased) trigram, ascending.
+    trigrams: HashMap<[u8; 3], Vec<u32>>,
+}
+
+impl Vocabulary {
+    fn intern(&mut self, text: &str) -> u32 {
+        if let Some(&id) = self.ids.get(text) {
+            return id;
+        }
+        let id = self.values.len() as u32;
+        for trigram in trigrams(text) {
+            self.trigrams.entry(trigram).or_default().push(id);
+        }
+        self.values.push(text.to_string());
+        self.ids.insert(text.to_string(), id);
+        self.rows.push(Vec::new());
+        id
+    }
+
+    fn add_row(&mut self, id: u32, row: u32) {
+        let rows = &mut self.rows[id as usize];
+        // Appended rows are the common case
+        if rows.last().is_none_or(|&last| last < row) {
+            rows.push(row);
+        } else if let Err(pos) = rows.binary_search(&row) {
+            rows.insert(pos, row);
+        }
+    }
+
+    fn remove_row(&mut self, id: u32, row: u32) {
+        let rows = &mut self.rows[id as usize];
+        if let Ok(pos) = rows.binary_search(&row) {
+            rows.remove(pos);
+        }
+    }
+
+    // Values that may contain `hint` (all of them when it is shorter than a trigram), then `pred`
+    fn rows_where(&self, hint: Option<&str>, pred: impl Fn(&str) -> bool) -> Vec<u32> {
+        let needle: Vec<[u8; 3]> = hint.map(trigrams).unwrap_or_default();
+        let ids: Vec<u32> = if needle.is_empty() {
+            (0..self.values.len() as u32).collect()
+        } else {
+            let mut lists: Vec<&[u32]> = needle.iter().map(|t| self.trigrams.get(t).map_or(&[][..], Vec::as_slice)).collect();
+            lists.sort_unstable_by_key(|l| l.len());
+            lists[1..].iter().fold(lists[0].to_vec(), |acc, list| intersect(&acc, list))
+        };
+        let lists: Vec<&[u32]> =
+            ids.into_iter().filter(|&id| pred(&self.values[id as usize])).map(|id| self.rows[id as usize].as_slice()).collect();
+        union_all(&lists)
+    }
+}
+
+// Distinct ASCII-lowercased byte trigrams of `text`, sorted
+fn trigrams(text: &str) -> Vec<[u8; 3]> {
+    let lower = text.as_bytes().to_ascii_lowercase();
+    let mut out: Vec<[u8; 3]> = lower.windows(3).map(|w| [w[0], w[1], w[2]]).collect();
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+/// Value index of the [`INDEXED_COLUMNS`], used by the filter to skip the rows
+/// a query cannot match instead of scanning them all.
+///
+/// Built once after a load and kept up to date with [`SearchIndex::update`] when
+/// tail mode appends or completes rows. The owner of the rows numbers each version
+/// of them (a generation, bumped whenever they are replaced or edited); the index
+/// only answers for the generation it was built or updated for, see [`SearchIndex::current`].
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    columns: Vec<Vocabulary>,
+    /// Value id of each row, per indexed column.
+    row_values: Vec<[u32; INDEXED_COLUMNS.len()]>,
+    /// Bit set of the lowercased bytes found in the timestamps (the plain-word column not indexed).
+    timestamp_bytes: [u64; 4],
+    /// Generation of the rows indexed, `None` for the default (empty) index.
+    generation: Option<u64>,
+}
+
+impl SearchIndex {
+    /// Indexes `items`, version `generation` of the rows (one column per thread).
+    #[must_use]
+    pub fn build(items: &[RadiusRequest], generation: u64) -> Self {
+        let built: Vec<(Vocabulary, Vec<u32>)> = INDEXED_COLUMNS
+            .par_iter()
+            .map(|&col| {
+                let mut vocabulary = Vocabulary::default();
+                let ids: Vec<u32> = items
+                    .iter()
+                    .enumerate()
+                    .map(|(row, item)| {
+                        let id = vocabulary.intern(item.column_text(col));
+                        vocabulary.rows[id as usize].push(row as u32);
+                        id
+                    })
+                    .collect();
+                (vocabulary, ids)
+            })
+            .collect();
+
+        let row_values = (0..items.len()).map(|row| std::array::from_fn(|c| built[c].1[row])).collect();
+        let mut index = Self {
+            columns: built.into_iter().map(|(vocabulary, _)| vocabulary).collect(),
+            row_values,
+            timestamp_bytes: [0; 4],
+            generation: Some(generation),
+        };
+        for item in items {
+            index.add_timestamp(&item.timestamp);
+        }
+        index
+    }
+
+    /// Generation of the rows the index reflects (`None` before the first build).
+    #[must_use]
+    pub const fn generation(&self) -> Option<u64> {
+        self.generation
+    }
+
+    /// The index, if it reflects `generation` of the rows: what the filter functions take.
+    #[must_use]
+    pub fn current(&self, generation: u64) -> Option<&Self> {
+        (self.generation == Some(generation)).then_some(self)
+    }
+
+    /// Number of indexed rows; the index only answers for the exact rows it was built from.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.row_values.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.row_values.is_empty()
+    }
+
+    /// Re-indexes the `touched` rows of `items` (new or modified, as returned by
+    /// [`LogTail::merge`](crate::LogTail::merge)), which are now at `generation`.
+    ///
+    /// The index stays current only if it was current for the previous generation
+    /// (`generation - 1`): an index already stale is not made current by an update.
+    pub fn update(&mut self, items: &[RadiusRequest], touched: &[usize], generation: u64) {
+        self.generation = self.generation.filter(|&previous| previous + 1 == generation).map(|_| generation);
+        if self.columns.is_empty() {
+            self.columns = INDEXED_COLUMNS.iter().map(|_| Vocabulary::default()).collect();
+        }
+        let mut touched = touched.to_vec();
+        touched.sort_unstable();
+        for row in touched {
+            let Some(item) = items.get(row) else { continue };
+            self.add_timestamp(&item.timestamp);
+            let ids: [u32; INDEXED_COLUMNS.len()] = std::array::from_fn(|c| self.columns[c].intern(item.column_text(INDEXED_COLUMNS[c])));
+            match self.row_values.get(row).copied() {
+                Some(old) => {
+                    for (c, vocabulary) in self.columns.iter_mut().enumerate() {
+                        if old[c] != ids[c] {
+                            vocabulary.remove_row(old[c], row as u32);
+                            vocabulary.add_row(ids[c], row as u32);
+                        }
+                    }
+                    self.row_values[row] = ids;
+                }
+                None => {
+                    // Rows are only ever appended: fill any gap the caller skipped
+                    while self.row_values.len() <= row {
+                        let next = self.row_values.len();
+                        let ids = if next == row {
+                            ids
+                        } else {
+                            self.add_timestamp(&items[next].timestamp);
+                            std::array::from_fn(|c| self.columns[c].intern(items[next].column_text(INDEXED_COLUMNS[c])))
+                        };
+                        for (c, vocabulary) in self.columns.iter_mut().enumerate() {
+                            vocabulary.add_row(ids[c], next as u32);
+                        }
+                        self.row_values.push(ids);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rows (ascending) whose `col` text satisfies `pred`; `hint` is lowercase text the value must
+    /// contain, to narrow the values with trigrams. `None` when `col` is not indexed.
+    pub(crate) fn rows_where(&self, col: LogColumn, hint: Option<&str>, pred: impl Fn(&str) -> bool) -> Option<Vec<u32>> {
+        let c = INDEXED_COLUMNS.iter().position(|&indexed| indexed == col)?;
+        self.columns.get(c).map(|vocabulary| vocabulary.rows_where(hint, pred))
+    }
+
+    /// Rows (ascending) with `needle` (lowercase) in one of the columns searched by plain words.
+    /// `None` when it may be part of a timestamp, which are not indexed.
+    pub(crate) fn rows_containing(&self, needle: &str) -> Option<Vec<u32>> {
+        if self.columns.is_empty() || needle.bytes().all(|b| self.timestamp_bytes[usize::from(b / 64)] & (1 << (b % 64)) != 0) {
+            return None;
+        }
+        let lists: Vec<Vec<u32>> =
+            self.columns.iter().map(|vocabulary| vocabulary.rows_where(Some(needle), |text| contains_ignore_case(text, needle))).collect();
+        Some(union_all(&lists.iter().map(Vec::as_slice).collect::<Vec<_>>()))
+    }
+
+    fn add_timestamp(&mut self, timestamp: &str) {
+        for b in timestamp.bytes() {
+            let b = b.to_ascii_lowercase();
+            self.timestamp_bytes[usize::from(b / 64)] |= 1 << (b % 64);
+        }
+    }
+}
+
+/// Rows in both ascending lists.
+pub fn intersect(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    small.iter().copied().filter(|x| large.binary_search(x).is_ok()).collect()
+}
+
+/// Rows in any of the ascending lists, ascending.
+pub fn union_all(lists: &[&[u32]]) -> Vec<u32> {
+    let mut out: Vec<u32> = lists.iter().flat_map(|l| l.iter().copied()).collect();
+    if lists.len() > 1 {
+        out.sort_unstable();
+        out.dedup();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filter::{filter_and_sort, filter_and_sort_indexed};
+    use crate::query::Query;
+    use crate::sort::SortSpec;
+
+    fn req(user: &str, ap_name: &str, reason: &str) -> RadiusRequest {
+        RadiusRequest {
+            user: user.to_string(),
+            ap_name: ap_name.to_string(),
+            reason: reason.to_string(),
+            server: "NPS01".to_string(),
+            resp_type: "Access-Accept".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn items() -> Vec<RadiusRequest> {
+        vec![
+            req("CORP\\jdoe", "BAT-B-AP01", ""),
+            req("alice", "BAT-A-AP07", "Authentication failed"),
+            req("svc-backup", "BAT-B-AP02", ""),
+            req("CORP\\JDoe2", "LAB-AP", "Certificate expired"),
+            req("bob", "BAT-B-AP01", "Authentication failed"),
+        ]
+    }
+
+    fn rows(index: &SearchIndex, text: &str) -> Option<Vec<u32>> {
+        Query::parse(text).expect("valid").candidates(index)
+    }
+
+    #[test]
+    fn answers_field_terms_and_falls_back() {
+        let items = items();
+        let index = SearchIndex::build(&items, 1);
+        assert_eq!(index.len(), 5);
+
+        assert_eq!(rows(&index, "user:jdoe"), Some(vec![0, 3]));
+        assert_eq!(rows(&index, "user:oe"), Some(vec![0, 3]), "short needles scan the values");
+        assert_eq!(rows(&index, "user=alice OR user~^svc-"), Some(vec![1, 2]));
+        assert_eq!(rows(&index, "ap:bat-b reason:failed"), Some(vec![4]));
+        // Only the indexed part narrows an AND
+        assert_eq!(rows(&index, "ap:bat-b AND NOT bob"), Some(vec![0, 2, 4]));
+        // Plain words search the indexed columns, unless they may be part of a timestamp
+        assert_eq!(rows(&index, "jdoe"), Some(vec![0, 3]));
+        assert_eq!(rows(&index, "user:alice OR np"), Some(vec![0, 1, 2, 3, 4]));
+        assert_eq!(rows(&index, "response:reject"), Some(vec![]));
+        let timed = SearchIndex::build(&[RadiusRequest { timestamp: "01/15/2024 08:00:00.123".to_string(), ..req("bob", "", "") }], 1);
+        assert_eq!(rows(&timed, "bob"), Some(vec![0]));
+        assert_eq!(rows(&timed, "08:00"), None);
+        // OR needs every branch
+        assert_eq!(rows(&index, "user:jdoe OR /jdoe/"), None);
+        assert_eq!(rows(&index, "NP-Policy-Name:wifi"), None);
+    }
+
+    #[test]
+    fn indexed_filter_matches_scan() {
+        let items = items();
+        let index = SearchIndex::build(&items, 1);
+        for text in ["", "user:jdoe", "ap:bat-b reason:failed", "ap:bat-b AND NOT bob", "user!=bob", "jdoe", "accept", "server=nps01 user:a"] {
+            let query = Query::parse(text).expect("valid");
+            assert_eq!(
+                filter_and_sort_indexed(&items, index.current(1), &query, None, &SortSpec::by(LogColumn::User, false)),
+                filter_and_sort(&items, &query, None, &SortSpec::by(LogColumn::User, false)),
+                "{text}"
+            );
+        }
+        // Only the generation it was built for is answered
+        assert!(index.current(2).is_none() && SearchIndex::default().current(0).is_none());
+    }
+
+    #[test]
+    fn updates_incrementally() {
+        let mut items = items();
+        let mut index = SearchIndex::build(&items[..3], 1);
+
+        // Tail: two new rows, one completed (response with its reason)
+        items[2].reason = "Certificate expired".to_string();
+        index.update(&items, &[3, 2, 4], 2);
+        assert_eq!((index.len(), index.generation()), (5, Some(2)));
+        assert_eq!(rows(&index, "reason:certificate"), Some(vec![2, 3]));
+        assert_eq!(rows(&index, "reason~^$"), Some(vec![0]));
+        assert_eq!(rows(&index, "user:bob"), Some(vec![4]));
+
+        // Same answers as a fresh build
+        let fresh = SearchIndex::build(&items, 2);
+        for text in ["reason:failed", "ap:bat-b", "user:o", "expired"] {
+            assert_eq!(rows(&index, text), rows(&fresh, text), "{text}");
+        }
+
+        // A stale index stays stale
+        let mut stale = SearchIndex::build(&items, 1);
+        stale.update(&items, &[4], 3);
+        assert_eq!(stale.generation(), None);
+    }
+}
@@ -0,0 +1,64 @@
+//! Parsing and filtering engine for Microsoft NPS/IAS RADIUS logs.
+//!
+//! This crate has no dependency on Win32: the GUI and any other tooling
+//! (CLI, Linux log collectors...) share the exact same parser.
+
+#![allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_possible_wrap)]
+
+mod attributes;
+mod category;
+mod column_filter;
+mod detail;
+mod detect;
+mod export;
+mod filter;
+mod follow;
+mod format;
+mod histogram;
+mod index;
+mod model;
+mod net;
+mod odbc;
+mod parser;
+mod preset;
+mod progress;
+mod query;
+mod reasons;
+mod report;
+mod sort;
+mod stats;
+mod stream;
+mod tail;
+mod time;
+mod worker;
+mod xml;
+#[cfg(test)]
+mod test_support;
+
+pub use attributes::AttributeMap;
+pub use category::{ErrorFilter, ReasonCategories, ReasonCategory};
+pub use column_filter::{distinct_values, ColumnFilter, ColumnFilters};
+pub use detail::{events_text, read_events, EventRef};
+pub use detect::{detect_attacks, AttackKind, DetectionSettings, Finding, Source};
+pub use export::{attribute_names, export_xlsx, write_csv, write_xlsx, ExportColumn};
+pub use filter::{apply_filter_logic, contains_ignore_case, filter_and_sort, filter_and_sort_cancellable, filter_and_sort_indexed, refilter_touched};
+pub use follow::{FollowUpdate, LogFollower};
+pub use format::LogFormat;
+pub use histogram::{Bucket, BucketSize, TimeSeries, MAX_BUCKETS};
+pub use index::{SearchIndex, INDEXED_COLUMNS};
+pub use model::{Event, LogColumn, RadiusRequest};
+pub use net::{parse_mac, MacNotation};
+pub use odbc::parse_odbc_line;
+pub use parser::{file_time_span, list_log_files, map_packet_type, parse_full_logic, parse_reader, process_group, read_records, ReadOutcome, SessionGrouper};
+pub use preset::{presets_from_json, presets_to_json, upsert_preset, FilterPreset};
+pub use progress::{NoProgress, ProgressSink};
+pub use query::{Query, QueryError};
+pub use reasons::map_reason;
+pub use report::{FailedFile, OutOfRangeFile, ParseReport, SkippedEvent};
+pub use sort::{SortKey, SortSpec};
+pub use stats::{Count, DistinctCounts, ReasonCount, Statistics, TimeSpan, DEFAULT_TOP};
+pub use stream::{EventSplitter, LineSplitter, RawEvent, RecordSplitter};
+pub use tail::{LogTail, TailChunk, TailRead};
+pub use time::{parse_nps_timestamp, TimeFilter, TimeRange, TimeSettings, Zone, NPS_TIMESTAMP_FORMAT};
+pub use worker::{FilterJob, FilterRequest, FilterWorker};
+pub use xml::parse_xml_event;
@@ -0,0 +1,181 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::attributes::AttributeMap;
+use crate::detail::EventRef;
+
+/// One NPS log record (`<Event>` element or ODBC line).
+///
+/// The attributes used to build the list are extracted into typed fields;
+/// every attribute, including those, is kept in `attributes`.
+#[derive(Debug, Clone, Default)]
+pub struct Event {
+    pub timestamp: Option<String>,
+    pub packet_type: Option<String>,
+    pub class: Option<String>,
+    pub acct_session_id: Option<String>,
+    pub server: Option<String>,
+    pub ap_ip: Option<String>,
+    pub ap_name: Option<String>,
+    pub client_friendly_name: Option<String>,
+    pub mac: Option<String>,
+    pub user_name: Option<String>,
+    pub sam_account: Option<String>,
+    pub reason_code: Option<String>,
+    pub attributes: AttributeMap,
+}
+
+impl Event {
+    /// Builds an event from its attributes, filling the typed fields from their NPS names.
+    #[must_use]
+    pub fn from_attributes(attributes: AttributeMap) -> Self {
+        let get = |name: &str| attributes.get(name).map(str::to_string);
+        Self {
+            timestamp: get("Timestamp"),
+            packet_type: get("Packet-Type"),
+            class: get("Class"),
+            acct_session_id: get("Acct-Session-Id"),
+            server: get("Computer-Name"),
+            ap_ip: get("Client-IP-Address"),
+            ap_name: get("NAS-Identifier"),
+            client_friendly_name: get("Client-Friendly-Name"),
+            mac: get("Calling-Station-Id"),
+            user_name: get("User-Name"),
+            sam_account: get("SAM-Account-Name"),
+            reason_code: get("Reason-Code"),
+            attributes,
+        }
+    }
+}
+
+/// One authentication/accounting exchange, built from all the events
+/// sharing the same Class / Acct-Session-Id.
+#[derive(Clone, Debug, Default)]
+pub struct RadiusRequest {
+    /// Timestamp column text (see [`crate::TimeSettings::apply`]).
+    pub timestamp: String,
+    /// Timestamp of the request as logged, in the wall-clock time of its server.
+    pub logged_at: Option<NaiveDateTime>,
+    /// Instant of the request; used for chronological sorting.
+    pub time: Option<DateTime<Utc>>,
+    pub req_type: String,
+    pub server: String,
+    pub ap_ip: String,
+    /// `ap_ip` parsed, for numeric sorting.
+    pub ap_ip_addr: Option<IpAddr>,
+    pub ap_name: String,
+    /// MAC column text (see [`crate::MacNotation::apply`]).
+    pub mac: String,
+    /// Calling-Station-Id as a 48-bit MAC value, when it is one.
+    pub mac_addr: Option<u64>,
+    pub user: String,
+    pub resp_type: String,
+    pub reason: String,
+    /// Reason-Code of the response that set `reason` (a later event logging 0 does not replace it).
+    pub reason_code: Option<u32>,
+    pub class_id: String,
+    pub session_id: String,
+    pub bg_color: Option<(u8, u8, u8)>,
+    /// Attributes of all the events of the exchange (see [`AttributeMap::merge`]):
+    /// for display and attribute searches, not for the outcome (see `reason_code`).
+    pub attributes: AttributeMap,
+    /// Where the source events of the exchange were logged, in log order
+    /// (request, challenges, response, accounting...); see [`crate::read_events`].
+    pub events: Vec<EventRef>,
+    /// The exchange has its outcome (see [`RadiusRequest::is_complete`]).
+    pub complete: bool,
+    /// An Access-Challenge was logged (EAP exchange in several rounds).
+    pub challenged: bool,
+}
+
+impl RadiusRequest {
+    #[must_use]
+    pub fn to_tsv(&self) -> String {
+        format!("{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.timestamp, self.req_type, self.server, self.ap_ip,
+            self.ap_name, self.mac, self.user, self.reason)
+    }
+
+    /// Text displayed for `col` (list cell, clipboard, tooltips).
+    #[must_use]
+    pub fn column_text(&self, col: LogColumn) -> &str {
+        match col {
+            LogColumn::Timestamp => &self.timestamp,
+            LogColumn::Type => &self.req_type,
+            LogColumn::Server => &self.server,
+            LogColumn::ApIp => &self.ap_ip,
+            LogColumn::ApName => &self.ap_name,
+            LogColumn::Mac => &self.mac,
+            LogColumn::User => &self.user,
+            LogColumn::ResponseType => &self.resp_type,
+            LogColumn::Reason => &self.reason,
+            LogColumn::Session => &self.session_id,
+        }
+    }
+
+    /// Whether the exchange has its outcome: a final response (Accept, Reject,
+    /// Accounting-Response), an accounting record, or a reason code on a discarded request.
+    #[must_use]
+    pub const fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Value of any logged attribute of the exchange, by NPS name.
+    #[must_use]
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub enum LogColumn {
+    Timestamp, Type, Server, ApIp, ApName, Mac, User, ResponseType, Reason, Session,
+}
+
+impl LogColumn {
+    #[must_use]
+    pub fn all() -> Vec<Self> {
+        vec![Self::Timestamp, Self::Type, Self::Server, Self::ApIp,
+             Self::ApName, Self::Mac, Self::User, Self::ResponseType, Self::Reason, Self::Session]
+    }
+
+    #[must_use]
+    pub const fn ftl_key(self) -> &'static str {
+        match self {
+            Self::Timestamp => "col-timestamp", Self::Type => "col-type", Self::Server => "col-server",
+            Self::ApIp => "col-ap-ip", Self::ApName => "col-ap-name", Self::Mac => "col-mac",
+            Self::User => "col-user", Self::ResponseType => "col-responsetype", Self::Reason => "col-reason",
+            Self::Session => "col-session",
+        }
+    }
+    /// Short name of the column, as written in queries and on the command line.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Timestamp => "timestamp", Self::Type => "type", Self::Server => "server",
+            Self::ApIp => "ap_ip", Self::ApName => "ap_name", Self::Mac => "mac",
+            Self::User => "user", Self::ResponseType => "response", Self::Reason => "reason",
+            Self::Session => "session",
+        }
+    }
+
+    /// Column named `name` (case-insensitive), [`LogColumn::name`] or one of its aliases.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "timestamp" | "time" | "date" => Self::Timestamp,
+            "type" | "req_type" | "request" => Self::Type,
+            "server" | "nps" => Self::Server,
+            "ap_ip" | "ip" | "nas_ip" => Self::ApIp,
+            "ap_name" | "ap" | "nas" => Self::ApName,
+            "mac" => Self::Mac,
+            "user" => Self::User,
+            "response" | "resp_type" | "result" => Self::ResponseType,
+            "reason" => Self::Reason,
+            "session" | "class" => Self::Session,
+            _ => return None,
+        })
+    }
+}
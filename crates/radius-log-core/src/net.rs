@@ -0,0 +1,103 @@
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::model::RadiusRequest;
+
+/// Parses a MAC address written in any usual notation (`AA-BB-CC-DD-EE-FF`,
+/// `aa:bb:cc:dd:ee:ff`, `aabb.ccdd.eeff`, `AABBCCDDEEFF`) into its 48-bit value.
+#[must_use]
+pub fn parse_mac(s: &str) -> Option<u64> {
+    let mut value = 0u64;
+    let mut digits = 0;
+    for c in s.trim().chars() {
+        if matches!(c, '-' | ':' | '.') { continue; }
+        value = (value << 4) | u64::from(c.to_digit(16)?);
+        digits += 1;
+    }
+    (digits == 12).then_some(value)
+}
+
+/// How MAC addresses (Calling-Station-Id) are displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MacNotation {
+    /// As written in the log.
+    #[default]
+    AsLogged,
+    /// `AA-BB-CC-DD-EE-FF` (Windows).
+    Hyphen,
+    /// `aa:bb:cc:dd:ee:ff` (IEEE / Linux).
+    Colon,
+    /// `aabb.ccdd.eeff` (Cisco).
+    Dotted,
+    /// `AABBCCDDEEFF`.
+    Bare,
+}
+
+impl MacNotation {
+    pub const ALL: [Self; 5] = [Self::AsLogged, Self::Hyphen, Self::Colon, Self::Dotted, Self::Bare];
+
+    /// Formats a 48-bit MAC value, `None` for [`MacNotation::AsLogged`].
+    #[must_use]
+    pub fn format(self, mac: u64) -> Option<String> {
+        let lower = format!("{mac:012x}");
+        let upper = lower.to_ascii_uppercase();
+        // Hex digits are ASCII: splitting at any byte offset is safe
+        let groups = |hex: &str, size: usize, sep: &str| {
+            (0..hex.len()).step_by(size).map(|i| &hex[i..i + size]).collect::<Vec<_>>().join(sep)
+        };
+        match self {
+            Self::AsLogged => None,
+            Self::Hyphen => Some(groups(&upper, 2, "-")),
+            Self::Colon => Some(groups(&lower, 2, ":")),
+            Self::Dotted => Some(groups(&lower, 4, ".")),
+            Self::Bare => Some(upper),
+        }
+    }
+
+    /// Renders the MAC column of `req` in this notation (left as logged if it is not a MAC).
+    pub fn apply(self, req: &mut RadiusRequest) {
+        let Some(mac) = req.mac_addr else { return };
+        let text = self.format(mac).or_else(|| req.attributes.get("Calling-Station-Id").map(str::to_string));
+        if let Some(text) = text {
+            req.mac = text;
+        }
+    }
+
+    /// [`MacNotation::apply`] on every request, in parallel.
+    pub fn apply_all(self, requests: &mut [RadiusRequest]) {
+        requests.par_iter_mut().for_each(|req| self.apply(req));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::process_group;
+    use crate::xml::parse_xml_event;
+
+    #[test]
+    fn parses_every_notation() {
+        let expected = Some(0xaabb_ccdd_eeff);
+        for s in ["AA-BB-CC-DD-EE-FF", "aa:bb:cc:dd:ee:ff", "aabb.ccdd.eeff", "AABBCCDDEEFF", " aa-bb-cc-dd-ee-ff "] {
+            assert_eq!(parse_mac(s), expected, "{s}");
+        }
+        assert_eq!(parse_mac("AA-BB-CC-DD-EE"), None);
+        assert_eq!(parse_mac("AA-BB-CC-DD-EE-FF:Corp"), None);
+        assert_eq!(parse_mac("host01"), None);
+    }
+
+    #[test]
+    fn formats_and_applies_notation() {
+        assert_eq!(MacNotation::Hyphen.format(0x0011_2233_44aa).as_deref(), Some("00-11-22-33-44-AA"));
+        assert_eq!(MacNotation::Colon.format(0x0011_2233_44aa).as_deref(), Some("00:11:22:33:44:aa"));
+        assert_eq!(MacNotation::Dotted.format(0x0011_2233_44aa).as_deref(), Some("0011.2233.44aa"));
+        assert_eq!(MacNotation::Bare.format(0x0011_2233_44aa).as_deref(), Some("0011223344AA"));
+
+        let ev = parse_xml_event("<Event><Packet-Type>1</Packet-Type><Calling-Station-Id>aabb.ccdd.eeff</Calling-Station-Id></Event>").expect("valid");
+        let mut req = process_group(&[ev]);
+        MacNotation::Hyphen.apply(&mut req);
+        assert_eq!(req.mac, "AA-BB-CC-DD-EE-FF");
+        MacNotation::AsLogged.apply(&mut req);
+        assert_eq!(req.mac, "aabb.ccdd.eeff");
+    }
+}
@@ -0,0 +1,115 @@
+//! Legacy IAS / NPS "ODBC-compatible" (database format) log files.
+//!
+//! One event per line, comma-separated, strings between double quotes, every
+//! attribute at a fixed position:
+//! `"NPS01","IAS",01/15/2024,08:00:00,1,"CORP\jdoe",...`
+
+use crate::attributes::AttributeMap;
+use crate::model::Event;
+
+// Column names, by position, spelled like the matching XML elements
+const COLUMNS: &[&str] = &[
+    "Computer-Name", "Service-Name", "Record-Date", "Record-Time", "Packet-Type", "User-Name",
+    "Fully-Qualifed-User-Name", "Called-Station-Id", "Calling-Station-Id", "Callback-Number",
+    "Framed-IP-Address", "NAS-Identifier", "NAS-IP-Address", "NAS-Port", "Client-Vendor",
+    "Client-IP-Address", "Client-Friendly-Name", "Event-Timestamp", "Port-Limit", "NAS-Port-Type",
+    "Connect-Info", "Framed-Protocol", "Service-Type", "Authentication-Type", "NP-Policy-Name",
+    "Reason-Code", "Class", "Session-Timeout", "Idle-Timeout", "Termination-Action",
+    "EAP-Friendly-Name", "Acct-Status-Type", "Acct-Delay-Time", "Acct-Input-Octets",
+    "Acct-Output-Octets", "Acct-Session-Id", "Acct-Authentic", "Acct-Session-Time",
+    "Acct-Input-Packets", "Acct-Output-Packets", "Acct-Terminate-Cause", "Acct-Multi-Session-Id",
+    "Acct-Link-Count", "Acct-Interim-Interval", "Tunnel-Type", "Tunnel-Medium-Type",
+    "Tunnel-Client-Endpt", "Tunnel-Server-Endpt", "Acct-Tunnel-Connection", "Tunnel-Pvt-Group-ID",
+    "Tunnel-Assignment-ID", "Tunnel-Preference", "MS-Acct-Auth-Type", "MS-Acct-EAP-Type",
+    "MS-RAS-Version", "MS-RAS-Vendor", "MS-CHAP-Error", "MS-CHAP-Domain",
+    "MS-MPPE-Encryption-Types", "MS-MPPE-Encryption-Policy", "Proxy-Policy-Name", "Provider-Type",
+    "Provider-Name", "Remote-Server-Address", "MS-RAS-Client-Name", "MS-RAS-Client-Version",
+];
+
+// Position of the last column required to make sense of a record
+const PACKET_TYPE: usize = 4;
+
+/// Parses one line of an ODBC-format log, `None` if it is not a valid record.
+///
+/// Every non-empty column becomes an attribute; columns past the documented
+/// ones are named `Column-<n>`.
+#[must_use]
+pub fn parse_odbc_line(line: &str) -> Option<Event> {
+    let fields = split_csv_line(line.trim_end_matches(['\r', '\n']));
+    if fields.len() <= PACKET_TYPE || fields[PACKET_TYPE].is_empty() {
+        return None;
+    }
+
+    let mut attributes = AttributeMap::new();
+    for (i, value) in fields.into_iter().enumerate() {
+        if value.is_empty() { continue; }
+        match COLUMNS.get(i) {
+            Some(&name) => attributes.push(name, value),
+            None => attributes.push(format!("Column-{i}"), value),
+        }
+    }
+
+    let mut event = Event::from_attributes(attributes);
+    event.timestamp = match (event.attributes.get("Record-Date"), event.attributes.get("Record-Time")) {
+        (Some(date), Some(time)) => Some(format!("{date} {time}")),
+        (date, time) => date.or(time).map(str::to_string),
+    };
+    Some(event)
+}
+
+// Splits on commas outside double quotes; `""` inside quotes is a literal quote
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_quoted_fields() {
+        assert_eq!(split_csv_line(r#""a,b",,3,"say ""hi""""#), vec!["a,b", "", "3", "say \"hi\""]);
+    }
+
+    #[test]
+    fn maps_positional_columns() {
+        let line = "\"NPS01\",\"IAS\",01/15/2024,08:00:00,3,\"CORP\\jdoe\",,\"00-11-22-33-44-55:SSID\",\"AA-BB-CC-DD-EE-FF\",,,\"AP-NAS\",,,,\"10.0.0.9\",\"AP-LOBBY\",,,,,,,,\"Wifi Policy\",16,\"311 1 10.0.0.1 01/15/2024 1\",,,,,,,,,\"sess-1\"\r\n";
+        let ev = parse_odbc_line(line).expect("valid line");
+        assert_eq!(ev.timestamp.as_deref(), Some("01/15/2024 08:00:00"));
+        assert_eq!(ev.packet_type.as_deref(), Some("3"));
+        assert_eq!(ev.server.as_deref(), Some("NPS01"));
+        assert_eq!(ev.user_name.as_deref(), Some("CORP\\jdoe"));
+        assert_eq!(ev.mac.as_deref(), Some("AA-BB-CC-DD-EE-FF"));
+        assert_eq!(ev.ap_name.as_deref(), Some("AP-NAS"));
+        assert_eq!(ev.ap_ip.as_deref(), Some("10.0.0.9"));
+        assert_eq!(ev.client_friendly_name.as_deref(), Some("AP-LOBBY"));
+        assert_eq!(ev.reason_code.as_deref(), Some("16"));
+        assert_eq!(ev.class.as_deref(), Some("311 1 10.0.0.1 01/15/2024 1"));
+        assert_eq!(ev.acct_session_id.as_deref(), Some("sess-1"));
+        assert_eq!(ev.attributes.get("Called-Station-Id"), Some("00-11-22-33-44-55:SSID"));
+        assert_eq!(ev.attributes.get("NP-Policy-Name"), Some("Wifi Policy"));
+    }
+
+    #[test]
+    fn rejects_short_lines() {
+        assert!(parse_odbc_line("").is_none());
+        assert!(parse_odbc_line("\"NPS01\",\"IAS\",01/15/2024,08:00:00,").is_none());
+    }
+}
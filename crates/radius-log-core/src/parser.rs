@@ -0,0 +1,519 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use chrono::{DateTime, Utc};
+use rayon::prelude::*;
+
+use crate::detail::EventRef;
+use crate::format::LogFormat;
+use crate::model::{Event, RadiusRequest};
+use crate::net::parse_mac;
+use crate::progress::ProgressSink;
+use crate::reasons::map_reason;
+use crate::report::ParseReport;
+use crate::time::{parse_nps_timestamp, TimeSettings, Zone};
+use crate::stream::{EventSplitter, LineSplitter, RecordSplitter};
+
+// Events deserialized per rayon batch: bounds the raw XML held in memory at once
+const CHUNK_EVENTS: usize = 4096;
+
+// Bytes read at each end of a file to find its first and last timestamps
+const SPAN_PROBE: u64 = 64 * 1024;
+
+/// Parses a whole NPS/IAS log file (XML or ODBC format) and groups its events into requests.
+///
+/// The file is streamed: memory usage is proportional to the resulting
+/// requests, not to the size of the file.
+/// Returns the grouped requests and the [`ParseReport`] of the file: malformed
+/// or truncated records are skipped and listed there, they never fail the parse.
+/// Requests reference their events in the file (see [`EventRef`]).
+///
+/// # Errors
+/// Returns an error if the file cannot be opened or read.
+pub fn parse_full_logic(path: &str, progress: Option<&dyn ProgressSink>) -> anyhow::Result<(Vec<RadiusRequest>, ParseReport)> {
+    let file = File::open(path)?;
+    let total_len = file.metadata()?.len();
+    let grouper = SessionGrouper::for_file(path);
+    let (requests, report) = parse_source(BufReader::with_capacity(1 << 20, file), total_len, grouper, progress)?;
+    Ok((requests, report.with_file(path)))
+}
+
+/// Streaming parser behind [`parse_full_logic`], usable on any buffered source.
+///
+/// The format (XML or ODBC comma-separated) is detected from the first bytes.
+/// `total_len` is only used to compute the percentage sent to `progress`
+/// (0 at start, then proportional to the bytes consumed, 100 at the end).
+/// The source has no path: the events of the requests cannot be read again.
+///
+/// # Errors
+/// Propagates I/O errors of `reader`.
+pub fn parse_reader<R: BufRead>(reader: R, total_len: u64, progress: Option<&dyn ProgressSink>) -> anyhow::Result<(Vec<RadiusRequest>, ParseReport)> {
+    parse_source(reader, total_len, SessionGrouper::default(), progress)
+}
+
+fn parse_source<R: BufRead>(mut reader: R, total_len: u64, mut grouper: SessionGrouper, progress: Option<&dyn ProgressSink>) -> anyhow::Result<(Vec<RadiusRequest>, ParseReport)> {
+    let format = LogFormat::detect(reader.fill_buf()?).unwrap_or(LogFormat::Xml);
+    let mut requests = Vec::new();
+
+    let outcome = read_records(format, reader, 0, total_len, progress, |events| {
+        for (span, ev) in events {
+            grouper.push(&mut requests, span, &ev);
+        }
+    })?;
+
+    // A whole file is complete: an ODBC last line just lacks its line break,
+    // while an XML element left open was cut
+    let mut report = outcome.report;
+    let blank = outcome.pending.iter().take_while(|b| b.is_ascii_whitespace()).count();
+    if blank < outcome.pending.len() {
+        let offset = outcome.offset + blank as u64;
+        report.raw_count += 1;
+        match format {
+            LogFormat::Odbc => match format.deserialize(&outcome.pending[blank..]) {
+                Ok(ev) => {
+                    report.recovered += 1;
+                    grouper.push(&mut requests, offset..outcome.offset + outcome.pending.len() as u64, &ev);
+                }
+                Err(e) => report.skip(offset, format!("{e:#}")),
+            },
+            LogFormat::Xml => report.skip(offset, "truncated event: missing </Event>".to_string()),
+        }
+    }
+
+    Ok((requests, report))
+}
+
+/// First and last instants of a log file, read from its first and last records
+/// only, so folders can skip the files outside a time range without parsing them.
+///
+/// Returns `None` if the format is unknown or no timestamp is found near either end.
+///
+/// # Errors
+/// Returns an error if the file cannot be opened or read.
+pub fn file_time_span(path: &str, time: &TimeSettings) -> anyhow::Result<Option<(DateTime<Utc>, DateTime<Utc>)>> {
+    let mut file = File::open(path)?;
+    let len = file.metadata()?.len();
+
+    let mut head = Vec::new();
+    (&mut file).take(SPAN_PROBE).read_to_end(&mut head)?;
+    let Some(format) = LogFormat::detect(&head) else { return Ok(None) };
+
+    let tail_start = len.saturating_sub(SPAN_PROBE);
+    let mut tail = Vec::new();
+    file.seek(SeekFrom::Start(tail_start))?;
+    file.read_to_end(&mut tail)?;
+    // The probe may start mid-record: resume at the next line
+    if tail_start > 0 {
+        let cut = memchr::memchr(b'\n', &tail).map_or(tail.len(), |i| i + 1);
+        tail.drain(..cut);
+    }
+
+    let first = probe_instants(format, &head, time).into_iter().min();
+    let last = probe_instants(format, &tail, time).into_iter().max();
+    Ok(first.zip(last))
+}
+
+/// The `.log` files of `folder`, oldest first (by modification time), as NPS rotates them.
+///
+/// # Errors
+/// Returns an error if the folder cannot be read.
+pub fn list_log_files(folder: impl AsRef<Path>) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files: Vec<(PathBuf, SystemTime)> = std::fs::read_dir(folder)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("log")))
+        .filter_map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect();
+    files.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+    Ok(files.into_iter().map(|(path, _)| path).collect())
+}
+
+fn probe_instants(format: LogFormat, bytes: &[u8], time: &TimeSettings) -> Vec<DateTime<Utc>> {
+    let mut instants = Vec::new();
+    let _ = read_records(format, Cursor::new(bytes), 0, bytes.len() as u64, None, |events| {
+        instants.extend(events.iter().filter_map(|(_, ev)| time.event_instant(ev)));
+    });
+    instants
+}
+
+/// Where a [`read_records`] pass stopped.
+pub struct ReadOutcome {
+    /// Complete records read, recovered or skipped (without the pending one).
+    pub report: ParseReport,
+    /// Offset of the first byte not turned into a record.
+    pub offset: u64,
+    /// Trailing bytes of an incomplete record, to be fed again on the next pass.
+    pub pending: Vec<u8>,
+}
+
+/// Reads every complete record of `reader` (positioned at byte `start_offset`
+/// of the source) with the splitter matching `format`, handing deserialized
+/// events to `on_chunk` in source order.
+///
+/// Each event comes with the bytes of its record in the source.
+/// Records that fail to deserialize are skipped and listed in the report;
+/// the next record is parsed normally.
+///
+/// # Errors
+/// Propagates I/O errors of `reader`.
+pub fn read_records<R: BufRead>(
+    format: LogFormat,
+    reader: R,
+    start_offset: u64,
+    total_len: u64,
+    progress: Option<&dyn ProgressSink>,
+    on_chunk: impl FnMut(Vec<(Range<u64>, Event)>),
+) -> std::io::Result<ReadOutcome> {
+    match format {
+        LogFormat::Xml => read_chunks(EventSplitter::with_offset(reader, start_offset), format, total_len, progress, on_chunk),
+        LogFormat::Odbc => read_chunks(LineSplitter::with_offset(reader, start_offset), format, total_len, progress, on_chunk),
+    }
+}
+
+fn read_chunks<S: RecordSplitter>(
+    mut splitter: S,
+    format: LogFormat,
+    total_len: u64,
+    progress: Option<&dyn ProgressSink>,
+    mut on_chunk: impl FnMut(Vec<(Range<u64>, Event)>),
+) -> std::io::Result<ReadOutcome> {
+    let total_len = total_len.max(1);
+    let mut chunk = Vec::with_capacity(CHUNK_EVENTS);
+    let mut report = ParseReport::default();
+    let mut last_progress = 0u8;
+
+    // Send initial 0% progress to show the progress bar
+    if let Some(p) = progress {
+        p.report(0);
+    }
+
+    loop {
+        let next = splitter.next_record()?;
+        let at_end = next.is_none();
+        if let Some(raw) = next {
+            chunk.push(raw);
+        }
+
+        if chunk.len() >= CHUNK_EVENTS || (at_end && !chunk.is_empty()) {
+            report.raw_count += chunk.len();
+            // Deserialization is the expensive part: hand the whole chunk to rayon,
+            // grouping stays sequential to preserve event order inside a session.
+            let parsed: Vec<(Range<u64>, anyhow::Result<Event>)> = chunk.par_drain(..)
+                .map(|raw| (raw.offset..raw.offset + raw.bytes.len() as u64, format.deserialize(&raw.bytes)))
+                .collect();
+            let mut events = Vec::with_capacity(parsed.len());
+            for (span, res) in parsed {
+                match res {
+                    Ok(ev) => events.push((span, ev)),
+                    Err(e) => report.skip(span.start, format!("{e:#}")),
+                }
+            }
+            report.recovered += events.len();
+            on_chunk(events);
+
+            if let Some(p) = progress {
+                let pct = ((splitter.offset().min(total_len) * 99) / total_len) as u8;
+                if pct > last_progress {
+                    p.report(pct);
+                    last_progress = pct;
+                }
+            }
+        }
+
+        if at_end { break; }
+    }
+
+    // Send 100% progress to hide the progress bar
+    if let Some(p) = progress {
+        p.report(100);
+    }
+
+    Ok(ReadOutcome { report, offset: splitter.offset(), pending: splitter.pending().to_vec() })
+}
+
+/// Groups events by Class (or Acct-Session-Id) in order of first appearance,
+/// folding each event into its request as soon as it arrives.
+///
+/// The grouper only remembers which request index each session key maps to,
+/// so it can keep merging into the same `requests` vector later (tail mode).
+#[derive(Default)]
+pub struct SessionGrouper {
+    class_map: HashMap<String, usize>,
+    // Source of the events, referenced by the requests
+    file: Option<Arc<Path>>,
+}
+
+impl SessionGrouper {
+    /// Grouper for the events of the log file `path`.
+    pub fn for_file(path: impl AsRef<Path>) -> Self {
+        Self { class_map: HashMap::new(), file: Some(Arc::from(path.as_ref())) }
+    }
+
+    /// Folds `ev`, read from the bytes `span` of the source, into its session
+    /// in `requests` (appending a new request if needed), references it as one
+    /// of the request's events and returns the index of the request it went to.
+    pub fn push(&mut self, requests: &mut Vec<RadiusRequest>, span: Range<u64>, ev: &Event) -> usize {
+        let key_opt = ev.class.as_deref()
+            .or(ev.acct_session_id.as_deref())
+            .filter(|s: &&str| !s.is_empty());
+
+        let existing = key_opt.and_then(|k| self.class_map.get(k).copied());
+        let idx = existing.unwrap_or_else(|| {
+            if let Some(k) = key_opt {
+                self.class_map.insert(k.to_string(), requests.len());
+            }
+            requests.push(RadiusRequest::default());
+            requests.len() - 1
+        });
+        requests[idx].apply_event(ev);
+        requests[idx].events.push(EventRef { file: self.file.clone(), offset: span.start, len: (span.end - span.start) as u32 });
+        idx
+    }
+}
+
+/// Collapses all the events of one exchange (request + responses) into a single row,
+/// without references to them (they were not read from a file).
+#[must_use]
+pub fn process_group(group: &[Event]) -> RadiusRequest {
+    let mut req = RadiusRequest::default();
+    for event in group {
+        req.apply_event(event);
+    }
+    req
+}
+
+impl RadiusRequest {
+    /// Folds one more event of the exchange into this request.
+    pub fn apply_event(&mut self, event: &Event) {
+        let req = self;
+        let p_type = event.packet_type.as_deref().unwrap_or("");
+        if p_type == "1" || p_type == "4" {
+            if let Some(val) = &event.timestamp {
+                req.timestamp.clone_from(val);
+                req.logged_at = parse_nps_timestamp(val);
+                req.time = req.logged_at.and_then(|t| Zone::Local.resolve(t));
+            }
+            if let Some(val) = &event.acct_session_id { req.session_id.clone_from(val); }
+            if let Some(val) = &event.server { req.server.clone_from(val); }
+            if let Some(val) = &event.ap_ip {
+                req.ap_ip.clone_from(val);
+                req.ap_ip_addr = val.trim().parse().ok();
+            }
+            if let Some(val) = &event.client_friendly_name { req.ap_name.clone_from(val); }
+            else if let Some(val) = &event.ap_name { req.ap_name.clone_from(val); }
+            if let Some(val) = &event.mac {
+                req.mac.clone_from(val);
+                req.mac_addr = parse_mac(val);
+            }
+            if let Some(val) = &event.class { req.class_id.clone_from(val); }
+            req.req_type = map_packet_type(p_type);
+
+            // The "Unknown" string is hardcoded: no localization in parallel code (Rayon)
+            if let Some(user) = &event.sam_account { req.user.clone_from(user); }
+            else if let Some(user) = &event.user_name { req.user.clone_from(user); }
+            else {
+                req.user = "Unknown User".to_string();
+            }
+        } else {
+            let this_resp_type = map_packet_type(p_type);
+            let code = event.reason_code.as_deref().unwrap_or("0");
+            if req.reason.is_empty() || code != "0" {
+                 req.resp_type.clone_from(&this_resp_type);
+                 req.reason = map_reason(code);
+                 req.reason_code = event.reason_code.as_deref().and_then(|code| code.trim().parse().ok());
+            }
+            match p_type {
+                "2" => req.bg_color = Some((25, 135, 84)),   // Modern Success (Bootstrap Green)
+                "3" => req.bg_color = Some((220, 53, 69)),   // Modern Danger (Bootstrap Red)
+                _ => {},
+            }
+        }
+        if matches!(p_type, "2" | "3" | "4" | "5") || event.reason_code.as_deref().is_some_and(|code| code.trim() != "0") {
+            req.complete = true;
+        }
+        req.challenged |= p_type == "11";
+        req.attributes.merge(&event.attributes);
+    }
+}
+
+#[must_use]
+pub fn map_packet_type(code: &str) -> String {
+    match code {
+        "1" => "Access-Request".to_string(),
+        "2" => "Access-Accept".to_string(),
+        "3" => "Access-Reject".to_string(),
+        "4" => "Accounting-Request".to_string(),
+        "5" => "Accounting-Response".to_string(),
+        "11" => "Access-Challenge".to_string(),
+        _ => format!("Type {code}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::category::{ReasonCategories, ReasonCategory};
+    use crate::test_support::{temp_log, SAMPLE};
+    use std::fmt::Write;
+    use std::fs;
+    use std::io::Cursor;
+    use std::sync::Mutex;
+
+    #[test]
+    fn parses_and_groups_sample() {
+        let path = temp_log("sample", SAMPLE);
+        let (reqs, report) = parse_full_logic(&path, None).expect("parse");
+        let _ = fs::remove_file(&path);
+
+        assert_eq!((report.raw_count, report.recovered, report.lost()), (4, 4, 0));
+        assert_eq!(reqs.len(), 2);
+        let first = &reqs[0];
+        assert_eq!(first.user, "CORP\\jdoe");
+        assert_eq!(first.ap_name, "AP-LOBBY");
+        assert_eq!(first.ap_ip, "10.0.0.9");
+        assert_eq!(first.resp_type, "Access-Reject");
+        assert!(first.reason.ends_with("(16)"));
+        assert_eq!(first.bg_color, Some((220, 53, 69)));
+        assert_eq!(first.attribute("SAM-Account-Name"), Some("CORP\\jdoe"));
+        assert_eq!(first.attribute("Reason-Code"), Some("16"));
+        assert_eq!(first.reason_code, Some(16));
+        assert_eq!(reqs[1].resp_type, "Access-Accept");
+    }
+
+    #[test]
+    fn later_events_keep_the_reason_code() {
+        // Accounting logged after the reject, with Reason-Code 0
+        let accounting = SAMPLE.lines().nth(1).expect("reject").replace(">3</Packet-Type>", ">5</Packet-Type>").replace(">16<", ">0<");
+        let log = format!("{SAMPLE}{accounting}\n");
+        let (reqs, _) = parse_reader(Cursor::new(log.as_bytes()), log.len() as u64, None).expect("parse");
+        assert_eq!(reqs[0].attribute("Reason-Code"), Some("0"), "the merged map keeps the newest value");
+        assert_eq!((reqs[0].resp_type.as_str(), reqs[0].reason_code), ("Access-Reject", Some(16)));
+        assert_eq!(ReasonCategories::default().classify(&reqs[0]), Some(ReasonCategory::Credentials));
+    }
+
+    #[test]
+    fn reads_file_time_span() {
+        let mut body = String::new();
+        // Enough events for the head and tail probes not to overlap
+        for i in 0..600 {
+            let _ = writeln!(body, "<Event><Timestamp data_type=\"4\">01/{:02}/2024 08:00:00.000</Timestamp><Packet-Type data_type=\"0\">1</Packet-Type><Class data_type=\"1\">{i}</Class><Filler data_type=\"1\">{}</Filler></Event>", 1 + i / 20, "x".repeat(200));
+        }
+        let path = temp_log("span", &body);
+        let time = TimeSettings { default_server_zone: Zone::Utc, ..Default::default() };
+        let span = file_time_span(&path, &time).expect("read");
+        let _ = fs::remove_file(&path);
+
+        let at = |s: &str| parse_nps_timestamp(s).expect("valid").and_utc();
+        assert_eq!(span, Some((at("01/01/2024 08:00:00"), at("01/30/2024 08:00:00"))));
+
+        let empty = temp_log("span-empty", "");
+        assert_eq!(file_time_span(&empty, &time).expect("read"), None);
+        let _ = fs::remove_file(&empty);
+    }
+
+    #[test]
+    fn references_source_events() {
+        let path = temp_log("refs", SAMPLE);
+        let (reqs, _) = parse_full_logic(&path, None).expect("parse");
+        let _ = fs::remove_file(&path);
+
+        let second = SAMPLE.find('\n').expect("first line") + 1;
+        let spans: Vec<(u64, u32)> = reqs[0].events.iter().map(|at| (at.offset, at.len)).collect();
+        assert_eq!(spans, [(0, second as u32 - 1), (second as u64, SAMPLE[second..].find('\n').expect("second line") as u32)]);
+        assert!(reqs[0].events.iter().all(|at| at.file.as_deref() == Some(Path::new(&path))));
+        assert!(reqs[0].is_complete() && !reqs[0].challenged);
+    }
+
+    #[test]
+    fn reports_progress_to_closure() {
+        let path = temp_log("progress", SAMPLE);
+        let seen = Mutex::new(Vec::new());
+        let sink = |pct: u8| seen.lock().expect("Lock failed").push(pct);
+        parse_full_logic(&path, Some(&sink)).expect("parse");
+        let _ = fs::remove_file(&path);
+
+        let seen = seen.into_inner().expect("Lock failed");
+        assert_eq!(seen.first(), Some(&0));
+        assert_eq!(seen.last(), Some(&100));
+    }
+
+    #[test]
+    fn streams_more_events_than_one_chunk() {
+        let sessions = CHUNK_EVENTS + 10;
+        let mut log = String::new();
+        for i in 0..sessions {
+            writeln!(log, "<Event><Packet-Type>1</Packet-Type><User-Name>u{i}</User-Name><Class>c{i}</Class></Event>").expect("write");
+            writeln!(log, "<Event><Packet-Type>2</Packet-Type><Class>c{i}</Class></Event>").expect("write");
+        }
+        let (reqs, report) = parse_reader(Cursor::new(log.as_bytes()), log.len() as u64, None).expect("parse");
+        assert_eq!(report.raw_count, sessions * 2);
+        assert_eq!(reqs.len(), sessions);
+        assert!(reqs.iter().all(|r| r.resp_type == "Access-Accept"));
+        assert_eq!(reqs[CHUNK_EVENTS].user, format!("u{CHUNK_EVENTS}"));
+    }
+
+    #[test]
+    fn truncated_trailing_event_is_reported() {
+        let log = format!("{SAMPLE}\r\n<Event><Packet-Type>1</Packet-Type><Class>x</Cla");
+        let (reqs, report) = parse_reader(Cursor::new(log.as_bytes()), log.len() as u64, None).expect("parse");
+        assert_eq!(reqs.len(), 2);
+        assert_eq!((report.raw_count, report.recovered, report.lost()), (5, 4, 1));
+        assert_eq!(report.skipped[0].offset, SAMPLE.len() as u64 + 2);
+        assert!(report.skipped[0].error.contains("truncated"));
+    }
+
+    #[test]
+    fn malformed_events_are_skipped_and_reported() {
+        let (request, rest) = SAMPLE.split_at(SAMPLE.find('\n').expect("first line") + 1);
+        // A broken element, then one whose closing tag was lost: both are skipped,
+        // parsing resumes at the next <Event>
+        let broken = "<Event><Packet-Type>2</Class></Event>\n<Event><Packet-Type>5</Packet-Type>\n";
+        let log = format!("{request}{broken}{rest}");
+        let path = temp_log("malformed", &log);
+        let (reqs, report) = parse_full_logic(&path, None).expect("parse");
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(reqs.len(), 2);
+        assert_eq!(reqs[0].resp_type, "Access-Reject");
+        assert_eq!((report.raw_count, report.recovered, report.lost()), (6, 4, 2));
+        let offsets: Vec<u64> = report.skipped.iter().map(|s| s.offset).collect();
+        assert_eq!(offsets, vec![request.len() as u64, (request.len() + broken.find("\n<").expect("second") + 1) as u64]);
+        assert!(report.skipped.iter().all(|s| s.file == path && !s.error.is_empty()));
+    }
+
+    #[test]
+    fn parses_odbc_format() {
+        let log = "\"NPS01\",\"IAS\",01/15/2024,08:00:00,1,\"CORP\\jdoe\",,,\"AA-BB-CC-DD-EE-FF\",,,,,,,\"10.0.0.9\",\"AP-LOBBY\",,,,,,,,,,\"c1\"\r\n\
+                   \"NPS01\",\"IAS\",01/15/2024,08:00:00,3,,,,,,,,,,,,,,,,,,,,,16,\"c1\"";
+        let (reqs, report) = parse_reader(Cursor::new(log.as_bytes()), log.len() as u64, None).expect("parse");
+        assert_eq!((reqs.len(), report.raw_count, report.lost()), (1, 2, 0));
+        assert_eq!(reqs[0].user, "CORP\\jdoe");
+        assert_eq!(reqs[0].ap_name, "AP-LOBBY");
+        assert_eq!(reqs[0].mac, "AA-BB-CC-DD-EE-FF");
+        assert_eq!(reqs[0].resp_type, "Access-Reject");
+        assert!(reqs[0].reason.ends_with("(16)"));
+    }
+
+    #[test]
+    fn empty_file_yields_nothing() {
+        let path = temp_log("empty", "");
+        let (reqs, report) = parse_full_logic(&path, None).expect("parse");
+        let _ = fs::remove_file(&path);
+        assert!(reqs.is_empty());
+        assert_eq!(report, ParseReport::default());
+    }
+
+    #[test]
+    fn packet_types() {
+        assert_eq!(map_packet_type("11"), "Access-Challenge");
+        assert_eq!(map_packet_type("42"), "Type 42");
+    }
+}
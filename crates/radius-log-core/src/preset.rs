@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+
+use crate::category::ReasonCategory;
+use crate::column_filter::ColumnFilters;
+use crate::model::LogColumn;
+use crate::query::Query;
+use crate::sort::SortSpec;
+
+/// Named view: search text, errors-only mode, sort and columns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FilterPreset {
+    pub name: String,
+    #[serde(default)]
+    pub query: String,
+    /// `query` is one regular expression (the Regex box).
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub errors_only: bool,
+    /// Failure category of the errors-only mode; `None` keeps every failure.
+    #[serde(default)]
+    pub error_category: Option<ReasonCategory>,
+    /// Sort keys, most significant first.
+    #[serde(default)]
+    pub sort: SortSpec,
+    /// Columns in display order; empty keeps the current ones.
+    #[serde(default)]
+    pub visible_columns: Vec<LogColumn>,
+    #[serde(default)]
+    pub column_filters: ColumnFilters,
+}
+
+impl FilterPreset {
+    /// Compiles the search text as the search box would.
+    ///
+    /// # Errors
+    /// Returns the query error, with the preset name.
+    pub fn query(&self) -> anyhow::Result<Query> {
+        let parsed = if self.regex { Query::regex(&self.query) } else { Query::parse(&self.query) };
+        parsed.map_err(|e| anyhow::anyhow!("preset '{}': {e}", self.name))
+    }
+}
+
+/// Adds `preset`, replacing the one with the same name; returns `true` if one was replaced.
+pub fn upsert_preset(presets: &mut Vec<FilterPreset>, preset: FilterPreset) -> bool {
+    if let Some(existing) = presets.iter_mut().find(|p| p.name == preset.name) {
+        *existing = preset;
+        true
+    } else {
+        presets.push(preset);
+        false
+    }
+}
+
+/// Presets as a JSON array, to share with other users.
+///
+/// # Errors
+/// Returns the serializer error.
+pub fn presets_to_json(presets: &[FilterPreset]) -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(presets)?)
+}
+
+/// Reads presets exported by [`presets_to_json`] (a single preset object is accepted too).
+///
+/// # Errors
+/// Fails on invalid JSON, an unnamed preset or a query that does not compile.
+pub fn presets_from_json(json: &str) -> anyhow::Result<Vec<FilterPreset>> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        Many(Vec<FilterPreset>),
+        One(FilterPreset),
+    }
+
+    let presets = match serde_json::from_str(json)? {
+        OneOrMany::Many(presets) => presets,
+        OneOrMany::One(preset) => vec![preset],
+    };
+    for preset in &presets {
+        if preset.name.trim().is_empty() {
+            anyhow::bail!("a preset has no name");
+        }
+        preset.query()?;
+    }
+    Ok(presets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::column_filter::ColumnFilter;
+
+    fn preset(name: &str, query: &str) -> FilterPreset {
+        FilterPreset {
+            name: name.to_string(),
+            query: query.to_string(),
+            regex: false,
+            errors_only: false,
+            error_category: None,
+            sort: SortSpec::default(),
+            visible_columns: Vec::new(),
+            column_filters: ColumnFilters::default(),
+        }
+    }
+
+    #[test]
+    fn round_trips_and_replaces_by_name() {
+        let mut cert = preset("Certificate failures", "code=268 OR code=265");
+        cert.errors_only = true;
+        cert.error_category = Some(ReasonCategory::Certificate);
+        cert.sort.click(LogColumn::User, false);
+        cert.sort.click(LogColumn::Timestamp, true);
+        cert.visible_columns = vec![LogColumn::Timestamp, LogColumn::User, LogColumn::Reason];
+        cert.column_filters.set(LogColumn::ApName, Some(ColumnFilter::Contains("BAT-B".to_string())));
+        let mut presets = vec![preset("Building B", "ap:BAT-B AND code=16"), cert];
+
+        let json = presets_to_json(&presets).expect("serialize");
+        assert_eq!(presets_from_json(&json).expect("parse"), presets);
+
+        assert!(upsert_preset(&mut presets, preset("Building B", "ap:BAT-B")));
+        assert!(!upsert_preset(&mut presets, preset("Lab", "ap:lab")));
+        assert_eq!(presets.iter().map(|p| p.query.as_str()).collect::<Vec<_>>(), ["ap:BAT-B", "code=268 OR code=265", "ap:lab"]);
+    }
+
+    #[test]
+    fn imports_minimal_and_rejects_invalid() {
+        let one = presets_from_json(r#"{ "name": "Rejects", "query": "response:reject" }"#).expect("single object");
+        assert_eq!(one, vec![preset("Rejects", "response:reject")]);
+
+        assert!(presets_from_json("[{ \"query\": \"x\" }]").is_err(), "name is required");
+        assert!(presets_from_json(r#"[{ "name": " ", "query": "x" }]"#).is_err());
+        let err = presets_from_json(r#"[{ "name": "Bad", "query": "user:(" }]"#).expect_err("query");
+        assert!(err.to_string().contains("preset 'Bad'"), "{err}");
+        let err = presets_from_json(r#"[{ "name": "Bad regex", "query": "(", "regex": true }]"#).expect_err("regex");
+        assert!(err.to_string().contains("regular expression"), "{err}");
+    }
+}
@@ -0,0 +1,35 @@
+use std::sync::mpsc::{Sender, SyncSender};
+
+/// Receives loading progress as a percentage (0 = started, 100 = finished).
+///
+/// Implementations are called from rayon worker threads, hence `Sync`.
+/// The GUI posts a window message, a CLI can draw a bar or ignore it.
+pub trait ProgressSink: Sync {
+    fn report(&self, percent: u8);
+}
+
+impl<F: Fn(u8) + Sync> ProgressSink for F {
+    fn report(&self, percent: u8) {
+        self(percent);
+    }
+}
+
+// Channel senders: a closed receiver simply stops receiving updates.
+impl ProgressSink for Sender<u8> {
+    fn report(&self, percent: u8) {
+        let _ = self.send(percent);
+    }
+}
+
+impl ProgressSink for SyncSender<u8> {
+    fn report(&self, percent: u8) {
+        let _ = self.try_send(percent);
+    }
+}
+
+/// Sink that discards every update.
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {
+    fn report(&self, _percent: u8) {}
+}
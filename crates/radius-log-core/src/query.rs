@@ -0,0 +1,733 @@
+//! Search box query language.
+//!
+//! ```text
+//! jdoe                              plain text, searched in every column
+//! "access reject"                   quoted text (spaces kept)
+//! user:jdoe                         column contains (case-insensitive)
+//! user=CORP\jdoe   user!=alice      column equals / differs (case-insensitive)
+//! code>=16   timestamp>"2024-01-15 08:00"   ap_ip<10.0.1.0
+//! NP-Policy-Name:wifi               any logged attribute, by NPS name
+//! attr:wifi                         every logged attribute value
+//! user~^svc-   mac~"^00-11-22"     column matches a regular expression
+//! /^svc-\d+$/                        regular expression searched in every column
+//! user:jdoe AND code=16 AND NOT ap_name:lab
+//! (user:alice OR user:bob) server:NPS01     juxtaposed terms are ANDed
+//! ```
+//!
+//! Comparison operators (`<`, `<=`, `>`, `>=`) compare timestamps chronologically
+//! (as logged by the server), AP IPs and MACs as addresses, numbers numerically
+//! and anything else as case-insensitive text. A name made only of hex digits,
+//! dots and hyphens (`aa:bb:cc`, `08:00`) is not a field: the whole word is
+//! searched as text.
+//!
+//! Regular expressions are case-insensitive and compiled once, when the query is parsed.
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::net::IpAddr;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use regex::{Regex, RegexBuilder};
+
+use crate::column_filter::{ColumnFilter, ColumnFilters};
+use crate::filter::contains_ignore_case;
+use crate::index::{intersect, union_all, SearchIndex};
+use crate::model::{LogColumn, RadiusRequest};
+use crate::net::parse_mac;
+use crate::time::{parse_nps_timestamp, TimeRange};
+
+/// A query that could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    /// Character position (0-based) of the error in the query.
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (column {})", self.message, self.position + 1)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// A parsed search query; the default query matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    root: Option<Node>,
+}
+
+impl Query {
+    /// Parses the search box text.
+    ///
+    /// # Errors
+    /// Returns the position and reason of the first syntax error.
+    pub fn parse(text: &str) -> Result<Self, QueryError> {
+        let tokens = Lexer::new(text).tokenize()?;
+        let mut parser = Parser { tokens, pos: 0, end: text.chars().count() };
+        if parser.tokens.is_empty() {
+            return Ok(Self::default());
+        }
+        let root = parser.or_expr()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            let message = match token.kind {
+                TokenKind::RParen => "unmatched ')'".to_string(),
+                _ => format!("unexpected {}", token.describe()),
+            };
+            return Err(QueryError { position: token.position, message });
+        }
+        Ok(Self { root: Some(root) })
+    }
+
+    /// Search box in regex mode: the whole text is one pattern searched in every column.
+    ///
+    /// # Errors
+    /// Returns the reason why the pattern is invalid.
+    pub fn regex(pattern: &str) -> Result<Self, QueryError> {
+        if pattern.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let test = Test::Regex(compile_regex(pattern, 0)?);
+        Ok(Self { root: Some(Node::Term(Term { field: None, test })) })
+    }
+
+    /// Restricts the query to the requests whose instant is in `range` (time window, time filter).
+    #[must_use]
+    pub fn within(self, range: TimeRange) -> Self {
+        self.and_first(Node::Range(range))
+    }
+
+    /// Adds the header filters of the list columns, all required along with the query.
+    #[must_use]
+    pub fn with_column_filters(self, filters: &ColumnFilters) -> Self {
+        filters.iter().fold(self, |query, (col, filter)| {
+            let field = Some(Field::Column(col));
+            let node = match filter {
+                ColumnFilter::Contains(text) => Node::Term(Term { field, test: Test::Contains(text.to_ascii_lowercase()) }),
+                ColumnFilter::OneOf(values) => Node::Term(Term { field, test: Test::OneOf(values.clone()) }),
+                ColumnFilter::Range(range) => Node::Range(*range),
+            };
+            query.and_first(node)
+        })
+    }
+
+    // `node AND query`: restrictions are checked before the text query
+    fn and_first(self, node: Node) -> Self {
+        let root = match self.root {
+            Some(Node::And(mut nodes)) => {
+                nodes.insert(0, node);
+                Node::And(nodes)
+            }
+            Some(root) => Node::And(vec![node, root]),
+            None => node,
+        };
+        Self { root: Some(root) }
+    }
+
+    /// Whether the query matches everything (empty search box).
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    #[must_use]
+    pub fn matches(&self, req: &RadiusRequest) -> bool {
+        self.root.as_ref().is_none_or(|node| node.matches(req))
+    }
+
+    /// Rows (ascending) that may match, from `index`; `None` when the rows must all be scanned.
+    /// Every row outside the result is known not to match.
+    pub(crate) fn candidates(&self, index: &SearchIndex) -> Option<Vec<u32>> {
+        self.root.as_ref()?.candidates(index)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Term(Term),
+    /// Instant of the request (not its text) in a range.
+    Range(TimeRange),
+    Not(Box<Self>),
+    And(Vec<Self>),
+    Or(Vec<Self>),
+}
+
+impl Node {
+    fn matches(&self, req: &RadiusRequest) -> bool {
+        match self {
+            Self::Term(term) => term.matches(req),
+            Self::Range(range) => range.contains(req.time),
+            Self::Not(node) => !node.matches(req),
+            Self::And(nodes) => nodes.iter().all(|n| n.matches(req)),
+            Self::Or(nodes) => nodes.iter().any(|n| n.matches(req)),
+        }
+    }
+
+    // An AND is narrowed by any indexed operand, an OR only if all of them are
+    fn candidates(&self, index: &SearchIndex) -> Option<Vec<u32>> {
+        match self {
+            Self::Term(term) => term.candidates(index),
+            Self::Range(_) | Self::Not(_) => None,
+            Self::And(nodes) => nodes.iter().filter_map(|n| n.candidates(index)).reduce(|acc, rows| intersect(&acc, &rows)),
+            Self::Or(nodes) => {
+                let all: Option<Vec<Vec<u32>>> = nodes.iter().map(|n| n.candidates(index)).collect();
+                all.map(|lists| union_all(&lists.iter().map(Vec::as_slice).collect::<Vec<_>>()))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Field {
+    Column(LogColumn),
+    /// Numeric Reason-Code (the Reason column holds its description).
+    ReasonCode,
+    /// Any logged attribute, by NPS name (always hyphenated: `NP-Policy-Name`).
+    Attribute(String),
+    /// Every logged attribute value (`attr:`).
+    AnyAttribute,
+}
+
+impl Field {
+    // A misspelled column is an error, not an attribute that no request has
+    fn from_name(name: &str, position: usize) -> Result<Self, QueryError> {
+        if name.eq_ignore_ascii_case("code") || name.eq_ignore_ascii_case("reason_code") {
+            return Ok(Self::ReasonCode);
+        }
+        if name.eq_ignore_ascii_case("attr") {
+            return Ok(Self::AnyAttribute);
+        }
+        if let Some(col) = LogColumn::from_name(name) {
+            return Ok(Self::Column(col));
+        }
+        if name.contains('-') {
+            return Ok(Self::Attribute(name.to_string()));
+        }
+        let columns: Vec<&str> = LogColumn::all().into_iter().map(LogColumn::name).collect();
+        Err(QueryError {
+            position,
+            message: format!("unknown field '{name}' (fields: {}, code, attr, or an NPS attribute name such as NP-Policy-Name)", columns.join(", ")),
+        })
+    }
+
+    // Text of a single-valued field (`attr:` tests every attribute, see `Term::matches`)
+    fn text<'a>(&self, req: &'a RadiusRequest) -> Cow<'a, str> {
+        match self {
+            Self::Column(col) => Cow::Borrowed(req.column_text(*col)),
+            Self::ReasonCode => req.reason_code.map_or(Cow::Borrowed(""), |code| Cow::Owned(code.to_string())),
+            Self::Attribute(name) => Cow::Borrowed(req.attribute(name).unwrap_or_default()),
+            Self::AnyAttribute => Cow::Borrowed(""),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Contains,
+    Equals,
+    NotEquals,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Regex,
+}
+
+impl Op {
+    const fn accepts(self, ord: Ordering) -> bool {
+        match self {
+            Self::Less => ord.is_lt(),
+            Self::LessOrEqual => ord.is_le(),
+            Self::Greater => ord.is_gt(),
+            Self::GreaterOrEqual => ord.is_ge(),
+            Self::Contains | Self::Equals | Self::NotEquals | Self::Regex => false,
+        }
+    }
+}
+
+/// Right-hand side of a comparison, typed once at parse time.
+#[derive(Debug, Clone)]
+enum Bound {
+    Time(NaiveDateTime),
+    Ip(IpAddr),
+    Mac(u64),
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone)]
+enum Test {
+    Contains(String),
+    Equals(String),
+    NotEquals(String),
+    Compare(Op, Bound),
+    Regex(Regex),
+    /// Exact, case-sensitive values (column pick-list).
+    OneOf(Vec<String>),
+}
+
+#[derive(Debug, Clone)]
+struct Term {
+    /// `None` searches every column.
+    field: Option<Field>,
+    test: Test,
+}
+
+impl Term {
+    fn new(field: Option<Field>, op: Op, value: &str, position: usize) -> Result<Self, QueryError> {
+        let lower = value.to_ascii_lowercase();
+        let test = match op {
+            Op::Contains => Test::Contains(lower),
+            Op::Equals => Test::Equals(lower),
+            Op::NotEquals => Test::NotEquals(lower),
+            Op::Regex => Test::Regex(compile_regex(value, position)?),
+            _ => Test::Compare(op, Self::bound(field.as_ref(), value, position)?),
+        };
+        Ok(Self { field, test })
+    }
+
+    fn bound(field: Option<&Field>, value: &str, position: usize) -> Result<Bound, QueryError> {
+        let value = value.trim();
+        match field {
+            Some(Field::Column(LogColumn::Timestamp)) => {
+                return parse_query_time(value).map(Bound::Time).ok_or_else(|| QueryError {
+                    position,
+                    message: format!("'{value}' is not a date (e.g. 2024-01-15, \"2024-01-15 08:00\" or \"01/15/2024 08:00:00\")"),
+                });
+            }
+            Some(Field::Column(LogColumn::ApIp)) => {
+                if let Ok(ip) = value.parse() { return Ok(Bound::Ip(ip)); }
+            }
+            Some(Field::Column(LogColumn::Mac)) => {
+                if let Some(mac) = parse_mac(value) { return Ok(Bound::Mac(mac)); }
+            }
+            _ => {}
+        }
+        Ok(value.parse().map_or_else(|_| Bound::Text(value.to_ascii_lowercase()), Bound::Number))
+    }
+
+    // Exact rows of a plain word, or of a test on an indexed column
+    fn candidates(&self, index: &SearchIndex) -> Option<Vec<u32>> {
+        let Some(field) = &self.field else {
+            return match &self.test {
+                Test::Contains(needle) => index.rows_containing(needle),
+                _ => None,
+            };
+        };
+        let Field::Column(col) = field else { return None };
+        match &self.test {
+            Test::Contains(needle) => index.rows_where(*col, Some(needle), |text| contains_ignore_case(text, needle)),
+            Test::Equals(value) => index.rows_where(*col, Some(value), |text| text.eq_ignore_ascii_case(value)),
+            Test::NotEquals(value) => index.rows_where(*col, None, |text| !text.eq_ignore_ascii_case(value)),
+            Test::Regex(re) => index.rows_where(*col, None, |text| re.is_match(text)),
+            Test::OneOf(values) => index.rows_where(*col, None, |text| values.iter().any(|v| v == text)),
+            Test::Compare(op, Bound::Text(bound)) => {
+                index.rows_where(*col, None, |text| op.accepts(text.to_ascii_lowercase().as_str().cmp(bound.as_str())))
+            }
+            // Addresses are compared by parsed value
+            Test::Compare(..) => None,
+        }
+    }
+
+    fn matches(&self, req: &RadiusRequest) -> bool {
+        let Some(field) = &self.field else {
+            return match &self.test {
+                Test::Contains(needle) => req.matches(needle),
+                Test::Regex(re) => req.matches_regex(re),
+                // Bare terms are plain text or regexes
+                _ => false,
+            };
+        };
+        if *field == Field::AnyAttribute {
+            return req.attributes.iter().any(|(_, value)| self.test_text(req, value));
+        }
+        self.test_text(req, &field.text(req))
+    }
+
+    fn test_text(&self, req: &RadiusRequest, text: &str) -> bool {
+        match &self.test {
+            Test::Contains(needle) => contains_ignore_case(text, needle),
+            Test::Equals(value) => text.eq_ignore_ascii_case(value),
+            Test::NotEquals(value) => !text.eq_ignore_ascii_case(value),
+            Test::Regex(re) => re.is_match(text),
+            Test::OneOf(values) => values.iter().any(|v| v == text),
+            Test::Compare(op, bound) => {
+                let ord = match bound {
+                    Bound::Time(t) => req.logged_at.map(|logged| logged.cmp(t)),
+                    Bound::Ip(ip) => req.ap_ip_addr.map(|addr| addr.cmp(ip)),
+                    Bound::Mac(mac) => req.mac_addr.map(|addr| addr.cmp(mac)),
+                    Bound::Number(n) => text.trim().parse::<f64>().ok().and_then(|x| x.partial_cmp(n)),
+                    Bound::Text(s) => Some(text.to_ascii_lowercase().as_str().cmp(s.as_str())),
+                };
+                ord.is_some_and(|ord| op.accepts(ord))
+            }
+        }
+    }
+}
+
+fn compile_regex(pattern: &str, position: usize) -> Result<Regex, QueryError> {
+    RegexBuilder::new(pattern).case_insensitive(true).build().map_err(|e| {
+        // Syntax errors span several lines (pattern, caret, reason): keep the reason
+        let text = e.to_string();
+        let reason = text.lines().last().unwrap_or_default().trim_start_matches("error: ");
+        QueryError { position, message: format!("invalid regular expression '{pattern}': {reason}") }
+    })
+}
+
+// NPS formats, ISO date-times without seconds, then bare dates (midnight)
+fn parse_query_time(s: &str) -> Option<NaiveDateTime> {
+    parse_nps_timestamp(s)
+        .or_else(|| ["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M", "%m/%d/%Y %H:%M"].iter().find_map(|f| NaiveDateTime::parse_from_str(s, f).ok()))
+        .or_else(|| ["%Y-%m-%d", "%m/%d/%Y"].iter().find_map(|f| NaiveDate::parse_from_str(s, f).ok()).map(|d| d.and_time(chrono::NaiveTime::MIN)))
+}
+
+#[derive(Debug, Clone)]
+enum TokenKind {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Term(Term),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    /// Character position in the query.
+    position: usize,
+}
+
+impl Token {
+    const fn describe(&self) -> &'static str {
+        match self.kind {
+            TokenKind::LParen => "'('",
+            TokenKind::RParen => "')'",
+            TokenKind::And => "AND",
+            TokenKind::Or => "OR",
+            TokenKind::Not => "NOT",
+            TokenKind::Term(_) => "search term",
+        }
+    }
+}
+
+struct Lexer {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Lexer {
+    fn new(text: &str) -> Self {
+        Self { chars: text.chars().collect(), pos: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Token>, QueryError> {
+        let mut tokens = Vec::new();
+        while let Some(c) = self.peek() {
+            let position = self.pos;
+            let kind = match c {
+                c if c.is_whitespace() => {
+                    self.pos += 1;
+                    continue;
+                }
+                '(' => {
+                    self.pos += 1;
+                    TokenKind::LParen
+                }
+                ')' => {
+                    self.pos += 1;
+                    TokenKind::RParen
+                }
+                '"' => TokenKind::Term(Term::new(None, Op::Contains, &self.quoted()?, position)?),
+                '/' => TokenKind::Term(Term::new(None, Op::Regex, &self.slashed()?, position)?),
+                _ => self.word()?,
+            };
+            tokens.push(Token { kind, position });
+        }
+        Ok(tokens)
+    }
+
+    // Text between double quotes, `\"` and `\\` escaped; the cursor is on the opening quote
+    fn quoted(&mut self) -> Result<String, QueryError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut out = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '"' => return Ok(out),
+                '\\' if matches!(self.peek(), Some('"' | '\\')) => {
+                    out.extend(self.peek());
+                    self.pos += 1;
+                }
+                c => out.push(c),
+            }
+        }
+        Err(QueryError { position: start, message: "missing closing '\"'".to_string() })
+    }
+
+    // `/pattern/`, `\/` escaped; the cursor is on the opening slash
+    fn slashed(&mut self) -> Result<String, QueryError> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut out = String::new();
+        while let Some(c) = self.peek() {
+            self.pos += 1;
+            match c {
+                '/' => return Ok(out),
+                '\\' if self.peek() == Some('/') => {
+                    out.push('/');
+                    self.pos += 1;
+                }
+                c => out.push(c),
+            }
+        }
+        Err(QueryError { position: start, message: "missing closing '/'".to_string() })
+    }
+
+    // Unquoted text up to the next space or parenthesis
+    fn bare(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| !c.is_whitespace() && c != '(' && c != ')') {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn word(&mut self) -> Result<TokenKind, QueryError> {
+        let start = self.pos;
+        let name_len = self.chars[start..].iter().take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')).count();
+        let name: String = self.chars[start..start + name_len].iter().collect();
+        // MACs, times and IPs (`aa-bb-..:Corp`, `08:00`, `10.0.0.1:1812`) are text, not fields
+        let is_field = name_len > 0 && !name.chars().all(|c| c.is_ascii_hexdigit() || matches!(c, '-' | '.'));
+
+        if is_field {
+            self.pos = start + name_len;
+            if let Some(op) = self.operator()? {
+                let value_pos = self.pos;
+                let value = match self.peek() {
+                    Some('"') => self.quoted()?,
+                    _ => self.bare(),
+                };
+                if value.is_empty() {
+                    return Err(QueryError { position: value_pos, message: format!("expected a value after '{name}'") });
+                }
+                return Ok(TokenKind::Term(Term::new(Some(Field::from_name(&name, start)?), op, &value, value_pos)?));
+            }
+            self.pos = start;
+        }
+
+        let text = self.bare();
+        Ok(match text.as_str() {
+            "AND" => TokenKind::And,
+            "OR" => TokenKind::Or,
+            "NOT" => TokenKind::Not,
+            _ => TokenKind::Term(Term::new(None, Op::Contains, &text, start)?),
+        })
+    }
+
+    fn operator(&mut self) -> Result<Option<Op>, QueryError> {
+        let next = self.chars.get(self.pos + 1).copied();
+        let (op, len) = match (self.peek(), next) {
+            (Some(':'), _) => (Op::Contains, 1),
+            (Some('~'), _) => (Op::Regex, 1),
+            (Some('!'), Some('=')) => (Op::NotEquals, 2),
+            (Some('<'), Some('=')) => (Op::LessOrEqual, 2),
+            (Some('>'), Some('=')) => (Op::GreaterOrEqual, 2),
+            (Some('='), _) => (Op::Equals, 1),
+            (Some('<'), _) => (Op::Less, 1),
+            (Some('>'), _) => (Op::Greater, 1),
+            (Some('!'), _) => return Err(QueryError { position: self.pos, message: "unknown operator '!' (use NOT or !=)".to_string() }),
+            _ => return Ok(None),
+        };
+        self.pos += len;
+        Ok(Some(op))
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Length of the query, for errors at the end.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.pos).map(|t| &t.kind)
+    }
+
+    // " after AND" for the token `back` positions before the cursor
+    fn after(&self, back: usize) -> String {
+        self.pos.checked_sub(back).and_then(|i| self.tokens.get(i)).map(|t| format!(" after {}", t.describe())).unwrap_or_default()
+    }
+
+    fn or_expr(&mut self) -> Result<Node, QueryError> {
+        let mut nodes = vec![self.and_expr()?];
+        while matches!(self.peek(), Some(TokenKind::Or)) {
+            self.pos += 1;
+            nodes.push(self.and_expr()?);
+        }
+        Ok(if nodes.len() == 1 { nodes.remove(0) } else { Node::Or(nodes) })
+    }
+
+    fn and_expr(&mut self) -> Result<Node, QueryError> {
+        let mut nodes = vec![self.unary()?];
+        loop {
+            match self.peek() {
+                Some(TokenKind::And) => self.pos += 1,
+                // Implicit AND between juxtaposed terms
+                Some(TokenKind::Term(_) | TokenKind::Not | TokenKind::LParen) => {}
+                _ => break,
+            }
+            nodes.push(self.unary()?);
+        }
+        Ok(if nodes.len() == 1 { nodes.remove(0) } else { Node::And(nodes) })
+    }
+
+    fn unary(&mut self) -> Result<Node, QueryError> {
+        let Some(token) = self.tokens.get(self.pos).cloned() else {
+            let message = format!("expected a search term{}", self.after(1));
+            return Err(QueryError { position: self.end, message });
+        };
+        self.pos += 1;
+        match token.kind {
+            TokenKind::Term(term) => Ok(Node::Term(term)),
+            TokenKind::Not => Ok(Node::Not(Box::new(self.unary()?))),
+            TokenKind::LParen => {
+                let node = self.or_expr()?;
+                if !matches!(self.peek(), Some(TokenKind::RParen)) {
+                    return Err(QueryError { position: token.position, message: "missing ')' for this '('".to_string() });
+                }
+                self.pos += 1;
+                Ok(node)
+            }
+            TokenKind::RParen if self.pos == 1 => Err(QueryError { position: token.position, message: "unmatched ')'".to_string() }),
+            TokenKind::RParen | TokenKind::And | TokenKind::Or => {
+                let message = format!("expected a search term{}, found {}", self.after(2), token.describe());
+                Err(QueryError { position: token.position, message })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attributes::AttributeMap;
+
+    fn req(user: &str, ap_name: &str, code: &str, logged: &str) -> RadiusRequest {
+        let mut attributes = AttributeMap::new();
+        attributes.push("Reason-Code".to_string(), code.to_string());
+        attributes.push("NP-Policy-Name".to_string(), "Wifi Corp".to_string());
+        RadiusRequest {
+            user: user.to_string(),
+            ap_name: ap_name.to_string(),
+            timestamp: logged.to_string(),
+            logged_at: parse_nps_timestamp(logged),
+            reason_code: code.parse().ok(),
+            attributes,
+            ..Default::default()
+        }
+    }
+
+    fn matching(query: &str, items: &[RadiusRequest]) -> Vec<usize> {
+        let q = Query::parse(query).expect("valid query");
+        (0..items.len()).filter(|&i| q.matches(&items[i])).collect()
+    }
+
+    fn items() -> Vec<RadiusRequest> {
+        vec![
+            req("CORP\\jdoe", "AP-LAB-01", "16", "01/15/2024 08:00:00.000"),
+            req("CORP\\jdoe", "AP-HALL-02", "16", "01/15/2024 09:30:00.000"),
+            req("alice", "AP-LAB-01", "0", "01/16/2024 10:00:00.000"),
+        ]
+    }
+
+    #[test]
+    fn plain_text_searches_the_columns() {
+        let items = items();
+        assert_eq!(matching("", &items), vec![0, 1, 2]);
+        assert_eq!(matching("  JDOE ", &items), vec![0, 1]);
+        // Attributes only through `attr:` and their names
+        assert_eq!(matching("\"wifi corp\"", &items), Vec::<usize>::new());
+        assert_eq!(matching("attr:\"wifi corp\"", &items), vec![0, 1, 2]);
+        assert_eq!(matching("attr=16 OR np-policy-name=lan", &items), vec![0, 1]);
+        // Hex-only names are not fields
+        assert_eq!(matching("08:00", &items), vec![0]);
+    }
+
+    #[test]
+    fn fields_and_boolean_operators() {
+        let items = items();
+        assert_eq!(matching("user:jdoe AND code=16 AND NOT ap_name:lab", &items), vec![1]);
+        assert_eq!(matching("(user:alice OR ap:hall) np-policy-name:wifi", &items), vec![1, 2]);
+        assert_eq!(matching("user=corp\\jdoe user!=alice", &items), vec![0, 1]);
+        assert_eq!(matching("NOT (code=16)", &items), vec![2]);
+    }
+
+    #[test]
+    fn comparisons() {
+        let items = items();
+        assert_eq!(matching("code>=16", &items), vec![0, 1]);
+        assert_eq!(matching("code<5", &items), vec![2]);
+        assert_eq!(matching("timestamp>\"2024-01-15 09:00\"", &items), vec![1, 2]);
+        assert_eq!(matching("time>=2024-01-16", &items), vec![2]);
+        assert_eq!(matching("user<b", &items), vec![2]);
+    }
+
+    #[test]
+    fn regular_expressions() {
+        let items = items();
+        assert_eq!(matching("user~^corp", &items), vec![0, 1]);
+        assert_eq!(matching("ap~\"-0[2-9]$\"", &items), vec![1]);
+        assert_eq!(matching("/^AP-LAB-\\d+$/", &items), vec![0, 2]);
+        assert_eq!(matching("/lab-\\/?01/ NOT user~jdoe", &items), vec![2]);
+        assert_eq!(matching("attr~\"^wifi\" user~^corp", &items), vec![0, 1]);
+
+        let q = Query::regex("^ALICE$").expect("valid");
+        assert!(q.matches(&items[2]) && !q.matches(&items[0]));
+        assert!(Query::regex("  ").expect("empty").is_empty());
+    }
+
+    #[test]
+    fn restricted_to_a_time_range() {
+        let mut items = items();
+        for r in &mut items {
+            r.time = r.logged_at.map(|t| t.and_utc());
+        }
+        let center = items[0].time.expect("parsed");
+        let q = |text: &str| Query::parse(text).expect("valid").within(TimeRange::around(center, 5400));
+        // 08:00 +/- 1h30: the 09:30 request is included, whoever it is from
+        assert_eq!((0..3).filter(|&i| q("").matches(&items[i])).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!((0..3).filter(|&i| q("ap:hall").matches(&items[i])).collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn reports_errors_with_position() {
+        let err = |q: &str| Query::parse(q).expect_err(q);
+        assert_eq!(err("user:jdoe AND").message, "expected a search term after AND");
+        assert_eq!(err("(user:jdoe").position, 0);
+        assert_eq!(err("user:jdoe)").message, "unmatched ')'");
+        assert_eq!(err("user:").message, "expected a value after 'user'");
+        assert_eq!(err("\"open").message, "missing closing '\"'");
+        assert_eq!(err("time>yesterday").position, 5);
+        assert_eq!(err("/abc").message, "missing closing '/'");
+        let bad = err("alice user~\"(svc\"");
+        assert_eq!(bad.position, 11);
+        assert!(bad.message.starts_with("invalid regular expression '(svc': unclosed group"), "{}", bad.message);
+        assert!(Query::regex("[a-").is_err());
+        assert_eq!(err("OR alice").to_string(), "expected a search term, found OR (column 1)");
+        // Typos are not attribute lookups that match nothing (or everything under NOT)
+        let typo = err("alice NOT usr:x");
+        assert_eq!(typo.position, 10);
+        assert!(typo.message.starts_with("unknown field 'usr' (fields: timestamp, type, server"), "{}", typo.message);
+        assert_eq!(err("reson:16").position, 0);
+    }
+}
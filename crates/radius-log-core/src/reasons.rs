@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+// Static cache for reason map
+static REASON_MAP: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+fn get_reason_map() -> &'static HashMap<String, String> {
+    REASON_MAP.get_or_init(|| {
+        // `include_str!` embeds the JSON file at compile time.
+        // Ensure the reason_codes.json file is next to this module
+        let json_content = include_str!("reason_codes.json");
+
+        match serde_json::from_str(json_content) {
+            Ok(map) => map,
+            Err(e) => {
+                eprintln!("Critical error loading reason_codes.json: {e}");
+                HashMap::new() // Returns an empty map on error to avoid crash
+            }
+        }
+    })
+}
+
+/// Human readable NPS reason, suffixed with the numeric code when it is not 0.
+#[must_use]
+pub fn map_reason(code: &str) -> String {
+    // Direct search in the HashMap (O(1))
+    let reason = get_reason_map()
+        .get(code)
+        .cloned()
+        .unwrap_or_else(|| format!("Code {code}"));
+
+    if code == "0" {
+        reason
+    } else {
+        format!("{reason} ({code})")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_and_unknown_codes() {
+        assert!(map_reason("0").starts_with("The connection request was successfully"));
+        assert!(map_reason("16").ends_with("(16)"));
+        assert_eq!(map_reason("9999"), "Code 9999 (9999)");
+    }
+}
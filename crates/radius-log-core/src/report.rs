@@ -0,0 +1,159 @@
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+
+use crate::time::TimeRange;
+
+/// A raw record that could not be turned into an event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedEvent {
+    /// Source file (empty when parsing an anonymous reader).
+    pub file: String,
+    /// Byte offset of the record in the file.
+    pub offset: u64,
+    pub error: String,
+}
+
+/// A file of a folder that could not be parsed at all (unreadable, locked...).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedFile {
+    pub file: String,
+    pub error: String,
+}
+
+/// A file of a folder left out because it is entirely outside the time range of the load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutOfRangeFile {
+    pub file: String,
+    /// Instants of its first and last records (see [`crate::file_time_span`]).
+    pub first: DateTime<Utc>,
+    pub last: DateTime<Utc>,
+}
+
+/// Diagnostics of a parse: how many records were read, recovered and lost, and why.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParseReport {
+    /// Raw records found (`<Event>` elements or ODBC lines), valid or not.
+    pub raw_count: usize,
+    /// Records successfully turned into events.
+    pub recovered: usize,
+    /// Records dropped, in file order.
+    pub skipped: Vec<SkippedEvent>,
+    /// Files of a folder that could not be parsed, in folder order.
+    pub failed_files: Vec<FailedFile>,
+    /// Files of a folder not parsed because they are outside the time range, in folder order.
+    pub out_of_range: Vec<OutOfRangeFile>,
+}
+
+impl ParseReport {
+    /// Number of records lost (malformed or truncated).
+    #[must_use]
+    pub const fn lost(&self) -> usize {
+        self.skipped.len()
+    }
+
+    /// Records a record that could not be parsed.
+    pub fn skip(&mut self, offset: u64, error: String) {
+        self.skipped.push(SkippedEvent { file: String::new(), offset, error });
+    }
+
+    /// Records a file of a folder that could not be parsed.
+    pub fn fail_file(&mut self, file: &str, error: String) {
+        self.failed_files.push(FailedFile { file: file.to_string(), error });
+    }
+
+    /// Records a file of a folder left out by the time range.
+    pub fn skip_file(&mut self, file: &str, first: DateTime<Utc>, last: DateTime<Utc>) {
+        self.out_of_range.push(OutOfRangeFile { file: file.to_string(), first, last });
+    }
+
+    /// Number of files left out at load time that `range` now covers, in part:
+    /// the folder must be loaded again to show their requests.
+    #[must_use]
+    pub fn out_of_range_in(&self, range: &TimeRange) -> usize {
+        self.out_of_range.iter().filter(|f| range.overlaps(f.first, f.last)).count()
+    }
+
+    /// Attributes the skipped records that have no file yet to `file`.
+    #[must_use]
+    pub fn with_file(mut self, file: &str) -> Self {
+        for s in self.skipped.iter_mut().filter(|s| s.file.is_empty()) {
+            file.clone_into(&mut s.file);
+        }
+        self
+    }
+
+    /// Adds the counts and skipped records of `other` (next file of a folder, tail read...).
+    pub fn merge(&mut self, other: Self) {
+        self.raw_count += other.raw_count;
+        self.recovered += other.recovered;
+        self.skipped.extend(other.skipped);
+        self.failed_files.extend(other.failed_files);
+        self.out_of_range.extend(other.out_of_range);
+    }
+
+    /// Human-readable report: a summary line, then one line per file not parsed
+    /// (failed or outside the time range) and per skipped record.
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{} raw events, {} recovered, {} lost", self.raw_count, self.recovered, self.lost());
+        if !self.failed_files.is_empty() {
+            let _ = write!(out, ", {} file(s) not parsed", self.failed_files.len());
+        }
+        if !self.out_of_range.is_empty() {
+            let _ = write!(out, ", {} file(s) outside the time range", self.out_of_range.len());
+        }
+        out.push('\n');
+        for f in &self.failed_files {
+            let _ = writeln!(out, "{}: {}", f.file, f.error);
+        }
+        for f in &self.out_of_range {
+            let _ = writeln!(out, "{}: outside the time range ({} - {})", f.file, f.first.to_rfc3339(), f.last.to_rfc3339());
+        }
+        for s in &self.skipped {
+            let _ = writeln!(out, "{} @ {}: {}", s.file, s.offset, s.error);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_and_formats() {
+        let mut report = ParseReport { raw_count: 3, recovered: 2, ..Default::default() };
+        report.skip(120, "unexpected end of file".to_string());
+        let report = report.with_file("a.log");
+
+        let mut next = ParseReport { raw_count: 1, recovered: 0, ..Default::default() };
+        next.skip(0, "truncated event".to_string());
+        let mut total = report;
+        total.merge(next.with_file("b.log"));
+
+        assert_eq!((total.raw_count, total.recovered, total.lost()), (4, 2, 2));
+        assert_eq!(total.to_text(), "4 raw events, 2 recovered, 2 lost\na.log @ 120: unexpected end of file\nb.log @ 0: truncated event\n");
+
+        let mut locked = ParseReport::default();
+        locked.fail_file("c.log", "access denied".to_string());
+        total.merge(locked);
+        assert_eq!(total.lost(), 2, "records only");
+        assert!(total.to_text().starts_with("4 raw events, 2 recovered, 2 lost, 1 file(s) not parsed\nc.log: access denied\na.log @ 120"));
+    }
+
+    #[test]
+    fn remembers_files_outside_the_range() {
+        let day = |d: u32| DateTime::parse_from_rfc3339(&format!("2024-01-{d:02}T00:00:00Z")).expect("valid").to_utc();
+        let mut report = ParseReport::default();
+        report.skip_file("IN240101.log", day(1), day(2));
+        report.skip_file("IN240105.log", day(5), day(6));
+        let mut total = ParseReport::default();
+        total.merge(report);
+
+        assert_eq!(total.out_of_range_in(&TimeRange { from: Some(day(4)), to: None }), 1);
+        assert_eq!(total.out_of_range_in(&TimeRange::default()), 2);
+        assert_eq!(total.out_of_range_in(&TimeRange { from: Some(day(10)), to: None }), 0);
+        assert!(total.to_text().starts_with("0 raw events, 0 recovered, 0 lost, 2 file(s) outside the time range\nIN240101.log: outside the time range (2024-01-01T00:00:00+00:00 - 2024-01-02T00:00:00+00:00)\n"));
+    }
+}
@@ -0,0 +1,125 @@
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{LogColumn, RadiusRequest};
+
+/// One level of a [`SortSpec`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SortKey {
+    pub column: LogColumn,
+    pub descending: bool,
+}
+
+/// Sort keys of the list, most significant first; ties keep the file order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SortSpec(Vec<SortKey>);
+
+/// Newest requests first.
+impl Default for SortSpec {
+    fn default() -> Self {
+        Self::by(LogColumn::Timestamp, true)
+    }
+}
+
+/// Keys in order, most significant first; no key keeps the file order.
+impl FromIterator<SortKey> for SortSpec {
+    fn from_iter<I: IntoIterator<Item = SortKey>>(keys: I) -> Self {
+        Self(keys.into_iter().collect())
+    }
+}
+
+impl SortSpec {
+    /// Single-level sort.
+    #[must_use]
+    pub fn by(column: LogColumn, descending: bool) -> Self {
+        Self(vec![SortKey { column, descending }])
+    }
+
+    #[must_use]
+    pub fn keys(&self) -> &[SortKey] {
+        &self.0
+    }
+
+    /// Level (0 = primary) and key of `column`, if the list is sorted by it.
+    #[must_use]
+    pub fn level(&self, column: LogColumn) -> Option<(usize, SortKey)> {
+        self.0.iter().copied().enumerate().find(|(_, key)| key.column == column)
+    }
+
+    /// Column header click.
+    ///
+    /// A plain click sorts by `column` alone, reversing it if it already was the
+    /// primary key. With `add` (Shift+click), `column` becomes the next key, or is
+    /// reversed if it is already one of them.
+    pub fn click(&mut self, column: LogColumn, add: bool) {
+        let existing = self.0.iter().position(|key| key.column == column);
+        match (add, existing) {
+            (true, Some(i)) => self.0[i].descending = !self.0[i].descending,
+            (true, None) => self.0.push(SortKey { column, descending: false }),
+            (false, Some(0)) => {
+                let descending = !self.0[0].descending;
+                *self = Self::by(column, descending);
+            }
+            (false, _) => *self = Self::by(column, false),
+        }
+    }
+
+    /// Order of the rows `a` and `b` of `items`.
+    pub(crate) fn compare(&self, items: &[RadiusRequest], a: usize, b: usize) -> Ordering {
+        self.0
+            .iter()
+            .map(|key| {
+                let ord = compare_column(&items[a], &items[b], key.column);
+                if key.descending { ord.reverse() } else { ord }
+            })
+            .find(|ord| ord.is_ne())
+            .unwrap_or_else(|| a.cmp(&b))
+    }
+}
+
+fn compare_column(a: &RadiusRequest, b: &RadiusRequest, col: LogColumn) -> Ordering {
+    match col {
+        // Chronological, unparsed timestamps first (in text order)
+        LogColumn::Timestamp => a.time.cmp(&b.time).then_with(|| a.timestamp.cmp(&b.timestamp)),
+        // Numeric, unparsed values first (in text order)
+        LogColumn::ApIp => a.ap_ip_addr.cmp(&b.ap_ip_addr).then_with(|| a.ap_ip.cmp(&b.ap_ip)),
+        LogColumn::Mac => a.mac_addr.cmp(&b.mac_addr).then_with(|| a.mac.cmp(&b.mac)),
+        LogColumn::Reason => {
+            let r_a = if a.reason.is_empty() { &a.resp_type } else { &a.reason };
+            let r_b = if b.reason.is_empty() { &b.resp_type } else { &b.reason };
+            r_a.cmp(r_b)
+        }
+        col => a.column_text(col).cmp(b.column_text(col)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(column: LogColumn, descending: bool) -> SortKey {
+        SortKey { column, descending }
+    }
+
+    #[test]
+    fn clicks_build_levels() {
+        let mut spec = SortSpec::default();
+        spec.click(LogColumn::User, false);
+        assert_eq!(spec.keys(), [key(LogColumn::User, false)]);
+        spec.click(LogColumn::Timestamp, true);
+        spec.click(LogColumn::Timestamp, true);
+        assert_eq!(spec.keys(), [key(LogColumn::User, false), key(LogColumn::Timestamp, true)]);
+        assert_eq!(spec.level(LogColumn::Timestamp), Some((1, key(LogColumn::Timestamp, true))));
+
+        // A plain click on the primary key reverses it and drops the others
+        spec.click(LogColumn::User, false);
+        assert_eq!(spec, SortSpec::by(LogColumn::User, true));
+        spec.click(LogColumn::Server, false);
+        assert_eq!(spec, SortSpec::by(LogColumn::Server, false));
+
+        let json = serde_json::to_string(&spec).expect("serialize");
+        assert_eq!(json, r#"[{"column":"Server","descending":false}]"#);
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::model::RadiusRequest;
+
+/// Rows of each "top" list by default.
+pub const DEFAULT_TOP: usize = 10;
+
+/// A value and the number of requests that have it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Count {
+    pub value: String,
+    pub count: usize,
+}
+
+/// A Reason-Code, its description and the number of requests that got it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReasonCount {
+    pub code: u32,
+    /// Reason column text of the first request with this code.
+    pub reason: String,
+    pub count: usize,
+}
+
+/// Number of different non-empty values per column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DistinctCounts {
+    pub users: usize,
+    pub macs: usize,
+    pub access_points: usize,
+    pub servers: usize,
+    pub reason_codes: usize,
+}
+
+/// Period covered by the requests.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TimeSpan {
+    pub first: DateTime<Utc>,
+    pub last: DateTime<Utc>,
+    /// Timestamp column of the first request, as displayed.
+    pub first_shown: String,
+    /// Timestamp column of the last request, as displayed.
+    pub last_shown: String,
+}
+
+/// Aggregate view of a set of requests: outcomes, busiest users, MACs, access
+/// points, servers and reason codes, and the period they cover.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Statistics {
+    pub requests: usize,
+    /// Source events of these requests.
+    pub events: usize,
+    pub accepted: usize,
+    pub rejected: usize,
+    /// Requests that went through at least one Access-Challenge (EAP), whatever their outcome.
+    pub challenged: usize,
+    /// Access-Requests with neither Accept nor Reject.
+    pub unanswered: usize,
+    pub accounting: usize,
+    /// Accepted / (accepted + rejected); `None` without any of them.
+    pub success_rate: Option<f64>,
+    /// `None` when no request has a valid timestamp.
+    pub span: Option<TimeSpan>,
+    pub distinct: DistinctCounts,
+    pub top_users: Vec<Count>,
+    pub top_macs: Vec<Count>,
+    /// By client friendly name, or by address for the unnamed ones.
+    pub top_access_points: Vec<Count>,
+    pub top_servers: Vec<Count>,
+    pub top_reasons: Vec<ReasonCount>,
+}
+
+impl Statistics {
+    /// Statistics of the rows `ids` of `items` (the whole dataset or the current view),
+    /// with `top` rows per "top" list.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // success rate: exact below 2^52 requests
+    pub fn compute(items: &[RadiusRequest], ids: &[usize], top: usize) -> Self {
+        let mut stats = Self::default();
+        let mut users: HashMap<&str, usize> = HashMap::new();
+        let mut macs: HashMap<&str, usize> = HashMap::new();
+        let mut access_points: HashMap<&str, usize> = HashMap::new();
+        let mut servers: HashMap<&str, usize> = HashMap::new();
+        let mut reasons: HashMap<u32, (usize, &str)> = HashMap::new();
+        let mut first: Option<&RadiusRequest> = None;
+        let mut last: Option<&RadiusRequest> = None;
+
+        for req in ids.iter().filter_map(|&i| items.get(i)) {
+            stats.requests += 1;
+            stats.events += req.events.len();
+            match req.resp_type.as_str() {
+                "Access-Accept" => stats.accepted += 1,
+                "Access-Reject" => stats.rejected += 1,
+                _ if req.req_type == "Access-Request" => stats.unanswered += 1,
+                _ => {}
+            }
+            if req.req_type == "Accounting-Request" {
+                stats.accounting += 1;
+            }
+            if req.challenged {
+                stats.challenged += 1;
+            }
+
+            let ap = if req.ap_name.is_empty() { &req.ap_ip } else { &req.ap_name };
+            for (counts, value) in [(&mut users, &req.user), (&mut macs, &req.mac), (&mut access_points, ap), (&mut servers, &req.server)] {
+                if !value.is_empty() {
+                    *counts.entry(value.as_str()).or_default() += 1;
+                }
+            }
+            if let Some(code) = req.reason_code {
+                reasons.entry(code).or_insert((0, req.reason.as_str())).0 += 1;
+            }
+
+            if let Some(time) = req.time {
+                if first.and_then(|f| f.time).is_none_or(|f| time < f) {
+                    first = Some(req);
+                }
+                if last.and_then(|l| l.time).is_none_or(|l| time > l) {
+                    last = Some(req);
+                }
+            }
+        }
+
+        let decided = stats.accepted + stats.rejected;
+        stats.success_rate = (decided > 0).then(|| stats.accepted as f64 / decided as f64);
+        stats.span = first.zip(last).and_then(|(first, last)| {
+            Some(TimeSpan {
+                first: first.time?,
+                last: last.time?,
+                first_shown: first.timestamp.clone(),
+                last_shown: last.timestamp.clone(),
+            })
+        });
+        stats.distinct = DistinctCounts {
+            users: users.len(),
+            macs: macs.len(),
+            access_points: access_points.len(),
+            servers: servers.len(),
+            reason_codes: reasons.len(),
+        };
+        stats.top_users = top_counts(users, top);
+        stats.top_macs = top_counts(macs, top);
+        stats.top_access_points = top_counts(access_points, top);
+        stats.top_servers = top_counts(servers, top);
+
+        let mut reasons: Vec<ReasonCount> = reasons
+            .into_iter()
+            .map(|(code, (count, reason))| ReasonCount { code, reason: reason.to_string(), count })
+            .collect();
+        reasons.sort_unstable_by(|a, b| b.count.cmp(&a.count).then(a.code.cmp(&b.code)));
+        reasons.truncate(top);
+        stats.top_reasons = reasons;
+        stats
+    }
+
+    /// Human-readable summary: totals, then one block per "top" list.
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{} requests ({} events)\n", self.requests, self.events);
+        if let Some(span) = &self.span {
+            let _ = writeln!(out, "From {} to {} ({})", span.first_shown, span.last_shown, format_duration(span.last - span.first));
+        }
+        let _ = write!(out, "Accepted: {}, rejected: {}", self.accepted, self.rejected);
+        if let Some(rate) = self.success_rate {
+            let _ = write!(out, " (success rate {:.1}%)", rate * 100.0);
+        }
+        let _ = writeln!(out, ", challenged: {}, unanswered: {}, accounting: {}", self.challenged, self.unanswered, self.accounting);
+        let d = &self.distinct;
+        let _ = writeln!(
+            out,
+            "Distinct: {} users, {} MACs, {} access points, {} servers, {} reason codes",
+            d.users, d.macs, d.access_points, d.servers, d.reason_codes
+        );
+
+        for (title, counts) in [
+            ("Top users", &self.top_users),
+            ("Top MACs", &self.top_macs),
+            ("Top access points", &self.top_access_points),
+            ("Top servers", &self.top_servers),
+        ] {
+            let _ = writeln!(out, "\n{title}");
+            for c in counts {
+                let _ = writeln!(out, "{:>9}  {}", c.count, c.value);
+            }
+        }
+        out.push_str("\nTop reason codes\n");
+        for r in &self.top_reasons {
+            let _ = writeln!(out, "{:>9}  {}", r.count, r.reason);
+        }
+        out
+    }
+}
+
+// Most frequent first, ties by value so the lists are stable
+fn top_counts(counts: HashMap<&str, usize>, top: usize) -> Vec<Count> {
+    let mut counts: Vec<(&str, usize)> = counts.into_iter().collect();
+    counts.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    counts.into_iter().take(top).map(|(value, count)| Count { value: value.to_string(), count }).collect()
+}
+
+fn format_duration(duration: chrono::TimeDelta) -> String {
+    let secs = duration.num_seconds();
+    let (days, hours, minutes) = (secs / 86_400, secs / 3600 % 24, secs / 60 % 60);
+    if days > 0 {
+        format!("{days}d {hours}h {minutes:02}m")
+    } else {
+        format!("{hours}h {minutes:02}m {:02}s", secs % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_reader;
+    use crate::test_support::SAMPLE;
+    use std::io::Cursor;
+
+    #[test]
+    fn counts_outcomes_and_tops() {
+        // jdoe rejected twice, alice accepted, bob still waiting for an answer
+        let lines: Vec<&str> = SAMPLE.lines().collect();
+        let jdoe_again = |line: &str| line.replace("08:00:00", "09:00:00").replace("01/15/2024 1<", "01/15/2024 3<");
+        let bob = lines[2].replace("alice", "bob").replace("01/15/2024 2<", "01/15/2024 4<");
+        let log = format!("{SAMPLE}{}\n{}\n{bob}\n", jdoe_again(lines[0]), jdoe_again(lines[1]));
+        let (items, _) = parse_reader(Cursor::new(log.clone()), log.len() as u64, None).expect("parse");
+        let ids: Vec<usize> = (0..items.len()).collect();
+
+        let stats = Statistics::compute(&items, &ids, 2);
+        assert_eq!((stats.requests, stats.events), (4, 7));
+        assert_eq!((stats.accepted, stats.rejected, stats.unanswered, stats.accounting), (1, 2, 1, 0));
+        assert_eq!(stats.success_rate, Some(1.0 / 3.0));
+        assert_eq!(stats.top_users, [
+            Count { value: "CORP\\jdoe".to_string(), count: 2 },
+            Count { value: "alice".to_string(), count: 1 },
+        ]);
+        assert_eq!(stats.distinct, DistinctCounts { users: 3, macs: 1, access_points: 1, servers: 1, reason_codes: 2 });
+        assert_eq!((stats.top_reasons[0].code, stats.top_reasons[0].count), (16, 2));
+        let span = stats.span.as_ref().expect("span");
+        assert_eq!((span.first_shown.as_str(), span.last_shown.as_str()), ("01/15/2024 08:00:00.123", "01/15/2024 09:00:00.123"));
+        assert!(stats.to_text().contains("Accepted: 1, rejected: 2 (success rate 33.3%)"));
+
+        // The current view only
+        let view = Statistics::compute(&items, &[1], DEFAULT_TOP);
+        assert_eq!((view.requests, view.accepted, view.distinct.users), (1, 1, 1));
+        assert!(Statistics::compute(&items, &[], DEFAULT_TOP).span.is_none());
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(chrono::TimeDelta::seconds(3725)), "1h 02m 05s");
+        assert_eq!(format_duration(chrono::TimeDelta::seconds(2 * 86_400 + 3600)), "2d 1h 00m");
+    }
+}
@@ -0,0 +1,261 @@
+use std::io::{self, BufRead};
+
+use memchr::memmem;
+
+const START_TAG: &[u8] = b"<Event";
+const END_TAG: &[u8] = b"</Event>";
+
+/// One complete `<Event>...</Event>` element and its byte offset in the source.
+#[derive(Debug, Clone)]
+pub struct RawEvent {
+    pub offset: u64,
+    pub bytes: Vec<u8>,
+}
+
+/// A source of raw log records (XML elements, CSV lines...) read incrementally.
+pub trait RecordSplitter {
+    /// Returns the next complete record, or `None` at end of stream.
+    ///
+    /// # Errors
+    /// Propagates I/O errors of the underlying reader.
+    fn next_record(&mut self) -> io::Result<Option<RawEvent>>;
+
+    /// Absolute offset up to which the source has been fully consumed
+    /// (start of the pending partial record, if any).
+    fn offset(&self) -> u64;
+
+    /// Bytes read after the last complete record.
+    fn pending(&self) -> &[u8];
+}
+
+/// Splits a byte stream into raw `<Event>` elements without loading it whole.
+///
+/// Only the element currently being assembled is buffered, so memory stays
+/// bounded whatever the file size. A trailing element that is not closed yet
+/// stays pending (see [`EventSplitter::pending`]).
+pub struct EventSplitter<R> {
+    reader: R,
+    buf: Vec<u8>,
+    // Read cursor inside `buf`: everything before it has been emitted or skipped
+    pos: usize,
+    // Absolute offset of `buf[0]` in the source
+    buf_offset: u64,
+    eof: bool,
+}
+
+impl<R: BufRead> EventSplitter<R> {
+    pub const fn new(reader: R) -> Self {
+        Self::with_offset(reader, 0)
+    }
+
+    /// Starts splitting a reader positioned at absolute byte `offset` of the source.
+    pub const fn with_offset(reader: R, offset: u64) -> Self {
+        Self { reader, buf: Vec::new(), pos: 0, buf_offset: offset, eof: false }
+    }
+
+    /// Absolute offset up to which the source has been fully consumed
+    /// (start of the pending partial element, if any).
+    pub const fn offset(&self) -> u64 {
+        self.buf_offset + self.pos as u64
+    }
+
+    /// Bytes read after the last complete element (a partial `<Event>`, whitespace...).
+    pub fn pending(&self) -> &[u8] {
+        &self.buf[self.pos..]
+    }
+
+    /// Returns the next complete element, or `None` at end of stream.
+    ///
+    /// # Errors
+    /// Propagates I/O errors of the underlying reader.
+    pub fn next_event(&mut self) -> io::Result<Option<RawEvent>> {
+        // Where to resume searching for the closing tag after a refill
+        let mut end_search_from = 0;
+        loop {
+            let window = &self.buf[self.pos..];
+            if let Some(start) = find_start(window) {
+                let from = end_search_from.max(start + START_TAG.len());
+                if let Some(end) = memmem::find(&window[from..], END_TAG) {
+                    let mut end = from + end + END_TAG.len();
+                    // Resync: an element whose closing tag was lost ends where the next one starts
+                    if let Some(next) = find_start(&window[start + START_TAG.len()..end]) {
+                        end = start + START_TAG.len() + next;
+                    }
+                    let event = RawEvent {
+                        offset: self.buf_offset + (self.pos + start) as u64,
+                        bytes: window[start..end].to_vec(),
+                    };
+                    self.pos += end;
+                    return Ok(Some(event));
+                }
+                // Skip what precedes the element, keep the partial element
+                self.pos += start;
+                end_search_from = window.len().saturating_sub(start + END_TAG.len() - 1);
+            } else {
+                // Nothing but inter-element noise: keep a tail that may be a cut start tag
+                self.pos += window.len().saturating_sub(START_TAG.len());
+                end_search_from = 0;
+            }
+            if self.eof || !self.fill()? {
+                return Ok(None);
+            }
+        }
+    }
+
+    // Compacts the buffer and appends the next block of the reader. Returns false at EOF.
+    fn fill(&mut self) -> io::Result<bool> {
+        if self.pos > 0 {
+            self.buf.drain(..self.pos);
+            self.buf_offset += self.pos as u64;
+            self.pos = 0;
+        }
+        let block = self.reader.fill_buf()?;
+        if block.is_empty() {
+            self.eof = true;
+            return Ok(false);
+        }
+        let n = block.len();
+        self.buf.extend_from_slice(block);
+        self.reader.consume(n);
+        Ok(true)
+    }
+}
+
+impl<R: BufRead> RecordSplitter for EventSplitter<R> {
+    fn next_record(&mut self) -> io::Result<Option<RawEvent>> {
+        self.next_event()
+    }
+
+    fn offset(&self) -> u64 {
+        Self::offset(self)
+    }
+
+    fn pending(&self) -> &[u8] {
+        Self::pending(self)
+    }
+}
+
+impl<R: BufRead> Iterator for EventSplitter<R> {
+    type Item = io::Result<RawEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event().transpose()
+    }
+}
+
+/// Splits a byte stream into lines (one record per line, as in the ODBC format).
+///
+/// A last line without its terminating `\n` is still being written: it stays
+/// pending instead of being returned.
+pub struct LineSplitter<R> {
+    reader: R,
+    offset: u64,
+    partial: Vec<u8>,
+}
+
+impl<R: BufRead> LineSplitter<R> {
+    /// Starts splitting a reader positioned at absolute byte `offset` of the source.
+    pub const fn with_offset(reader: R, offset: u64) -> Self {
+        Self { reader, offset, partial: Vec::new() }
+    }
+}
+
+impl<R: BufRead> RecordSplitter for LineSplitter<R> {
+    fn next_record(&mut self) -> io::Result<Option<RawEvent>> {
+        loop {
+            let start = self.partial.len();
+            self.reader.read_until(b'\n', &mut self.partial)?;
+            if self.partial.len() == start || self.partial.last() != Some(&b'\n') {
+                // EOF: keep the unterminated line pending
+                return Ok(None);
+            }
+            let line = std::mem::take(&mut self.partial);
+            let offset = self.offset;
+            self.offset += line.len() as u64;
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            return Ok(Some(RawEvent { offset, bytes: line }));
+        }
+    }
+
+    fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    fn pending(&self) -> &[u8] {
+        &self.partial
+    }
+}
+
+// Finds `<Event>` or `<Event attr...>`, ignoring `<Event-Source>` and friends
+fn find_start(haystack: &[u8]) -> Option<usize> {
+    let mut from = 0;
+    while let Some(i) = memmem::find(&haystack[from..], START_TAG) {
+        let at = from + i;
+        // A cut right after "<Event" is undecidable until more data arrives: keep it
+        match haystack.get(at + START_TAG.len()) {
+            None | Some(b'>' | b' ' | b'\t' | b'\r' | b'\n') => return Some(at),
+            Some(_) => from = at + 1,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::BufReader;
+
+    fn split(data: &[u8], capacity: usize) -> Vec<(u64, String)> {
+        EventSplitter::new(BufReader::with_capacity(capacity, data))
+            .map(|r| {
+                let ev = r.expect("read");
+                (ev.offset, String::from_utf8(ev.bytes).expect("utf8"))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn splits_across_tiny_reads() {
+        let data = b"\xEF\xBB\xBF<Event><Event-Source>IAS</Event-Source></Event>\r\n<Event a=\"1\"><X>y</X></Event>";
+        for capacity in [1, 2, 3, 7, 64, 8192] {
+            let events = split(data, capacity);
+            assert_eq!(events.len(), 2, "capacity {capacity}");
+            assert_eq!(events[0], (3, "<Event><Event-Source>IAS</Event-Source></Event>".to_string()));
+            assert_eq!(events[1].1, "<Event a=\"1\"><X>y</X></Event>");
+            assert_eq!(&data[events[1].0 as usize..events[1].0 as usize + 6], b"<Event");
+        }
+    }
+
+    #[test]
+    fn resyncs_on_unclosed_element() {
+        let data = b"<Event><A>1</A>\r\n<Event><A>2</A></Event>";
+        for capacity in [1, 5, 64] {
+            let events = split(data, capacity);
+            assert_eq!(events, vec![(0, "<Event><A>1</A>\r\n".to_string()), (17, "<Event><A>2</A></Event>".to_string())], "capacity {capacity}");
+        }
+    }
+
+    #[test]
+    fn lines_keep_unterminated_tail_pending() {
+        let data = b"a,1\r\n\r\nb,2\nc,";
+        let mut splitter = LineSplitter::with_offset(BufReader::with_capacity(2, &data[..]), 100);
+        let first = splitter.next_record().expect("read").expect("line");
+        assert_eq!((first.offset, first.bytes.as_slice()), (100, &b"a,1\r\n"[..]));
+        let second = splitter.next_record().expect("read").expect("line");
+        assert_eq!((second.offset, second.bytes.as_slice()), (107, &b"b,2\n"[..]));
+        assert!(splitter.next_record().expect("read").is_none());
+        assert_eq!((RecordSplitter::offset(&splitter), RecordSplitter::pending(&splitter)), (111, &b"c,"[..]));
+    }
+
+    #[test]
+    fn partial_trailing_event_stays_pending() {
+        let data = b"<Event><A>1</A></Event>\n<Event><A>2</A></Ev";
+        let mut splitter = EventSplitter::new(BufReader::with_capacity(4, &data[..]));
+        assert!(splitter.next_event().expect("read").is_some());
+        assert!(splitter.next_event().expect("read").is_none());
+        assert_eq!(splitter.pending(), b"<Event><A>2</A></Ev");
+        assert_eq!(splitter.offset(), 24);
+    }
+}
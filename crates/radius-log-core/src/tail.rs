@@ -0,0 +1,225 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::format::LogFormat;
+use crate::model::{Event, RadiusRequest};
+use crate::parser::{read_records, SessionGrouper};
+use crate::progress::ProgressSink;
+use crate::report::ParseReport;
+
+/// Events appended to a followed file since the previous read.
+#[derive(Debug, Default)]
+pub struct TailChunk {
+    /// Events with the bytes of their record in the file.
+    pub events: Vec<(Range<u64>, Event)>,
+    /// Raw records read and the ones that failed to deserialize.
+    pub report: ParseReport,
+}
+
+/// Outcome of [`LogTail::read_appended`].
+#[derive(Debug)]
+pub enum TailRead {
+    /// New complete events (possibly none).
+    Appended(TailChunk),
+    /// The file shrank or was replaced: the caller must reload it entirely.
+    Truncated,
+}
+
+/// Follows a growing NPS log file, parsing only the bytes appended since the last read.
+///
+/// A trailing `<Event>` that is still being written is kept in memory and
+/// completed on the next read. Appended events are merged into the session
+/// groups (Class / Acct-Session-Id) created by the initial load.
+pub struct LogTail {
+    path: PathBuf,
+    // Detected on the first non-blank bytes (an empty file can still become either)
+    format: Option<LogFormat>,
+    // Offset of the first byte not yet turned into events (start of `partial`)
+    offset: u64,
+    partial: Vec<u8>,
+    grouper: SessionGrouper,
+}
+
+impl LogTail {
+    /// Fully parses `path` and returns the tail state along with the requests
+    /// and parse report, exactly like [`crate::parse_full_logic`].
+    ///
+    /// Unlike a one-shot parse, a trailing incomplete record is not reported:
+    /// it is most likely still being written.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened or read.
+    pub fn open(path: impl AsRef<Path>, progress: Option<&dyn ProgressSink>) -> anyhow::Result<(Self, Vec<RadiusRequest>, ParseReport)> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::open(&path)?;
+        let total_len = file.metadata()?.len();
+        let mut reader = BufReader::with_capacity(1 << 20, file);
+
+        let grouper = SessionGrouper::for_file(&path);
+        let mut tail = Self { path, format: LogFormat::detect(reader.fill_buf()?), offset: 0, partial: Vec::new(), grouper };
+        let mut requests = Vec::new();
+        let Some(format) = tail.format else {
+            if let Some(p) = progress {
+                p.report(100);
+            }
+            return Ok((tail, requests, ParseReport::default()));
+        };
+
+        let outcome = read_records(format, reader, 0, total_len, progress, |events| {
+            for (span, ev) in events {
+                tail.grouper.push(&mut requests, span, &ev);
+            }
+        })?;
+        tail.offset = outcome.offset;
+        tail.partial = outcome.pending;
+
+        let file = tail.path.to_string_lossy().into_owned();
+        Ok((tail, requests, outcome.report.with_file(&file)))
+    }
+
+    /// Path of the followed file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Offset up to which the file has been turned into events.
+    #[must_use]
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Reads and deserializes the complete events appended since the last call.
+    ///
+    /// Does not touch any request: merge the result with [`LogTail::merge`],
+    /// which lets callers do the I/O without holding a lock on their data.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened or read.
+    pub fn read_appended(&mut self) -> anyhow::Result<TailRead> {
+        let mut file = File::open(&self.path)?;
+        let len = file.metadata()?.len();
+        let read_from = self.offset + self.partial.len() as u64;
+
+        if len < read_from {
+            return Ok(TailRead::Truncated);
+        }
+        if len == read_from {
+            return Ok(TailRead::Appended(TailChunk::default()));
+        }
+
+        file.seek(SeekFrom::Start(read_from))?;
+        // Resume with the buffered partial element, then the new bytes
+        let partial = std::mem::take(&mut self.partial);
+        let mut reader = Cursor::new(partial).chain(BufReader::new(file.take(len - read_from)));
+
+        let format = match self.format {
+            Some(format) => format,
+            None => match LogFormat::detect(reader.fill_buf()?) {
+                Some(format) => *self.format.insert(format),
+                None => return Ok(TailRead::Appended(TailChunk::default())),
+            },
+        };
+
+        let mut chunk = TailChunk::default();
+        let outcome = read_records(format, reader, self.offset, len, None, |events| chunk.events.extend(events))?;
+        chunk.report = outcome.report.with_file(&self.path.to_string_lossy());
+        self.offset = outcome.offset;
+        self.partial = outcome.pending;
+
+        Ok(TailRead::Appended(chunk))
+    }
+
+    /// Folds `events` into `requests` (the vector returned by [`LogTail::open`]).
+    ///
+    /// Returns the sorted, deduplicated indices of the requests that were
+    /// modified or appended.
+    pub fn merge(&mut self, events: Vec<(Range<u64>, Event)>, requests: &mut Vec<RadiusRequest>) -> Vec<usize> {
+        let mut touched: Vec<usize> = events.into_iter().map(|(span, ev)| self.grouper.push(requests, span, &ev)).collect();
+        touched.sort_unstable();
+        touched.dedup();
+        touched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{temp_log, SAMPLE};
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    fn append(path: &str, data: &str) {
+        let mut f = OpenOptions::new().append(true).open(path).expect("open");
+        f.write_all(data.as_bytes()).expect("append");
+    }
+
+    fn appended(tail: &mut LogTail) -> TailChunk {
+        match tail.read_appended().expect("read") {
+            TailRead::Appended(chunk) => chunk,
+            TailRead::Truncated => panic!("unexpected truncation"),
+        }
+    }
+
+    #[test]
+    fn merges_appended_events_into_sessions() {
+        // Start with a request whose response has not been written yet
+        let (head, response) = SAMPLE.split_at(SAMPLE.find("<Event><Timestamp data_type=\"4\">01/15/2024 08:00:00.456").expect("second event"));
+        let path = temp_log("tail", head);
+        let (mut tail, mut reqs, report) = LogTail::open(&path, None).expect("open");
+        assert_eq!((reqs.len(), report.raw_count), (1, 1));
+        assert!(reqs[0].resp_type.is_empty());
+
+        // Nothing new
+        assert!(appended(&mut tail).events.is_empty());
+
+        // Half an element: buffered, not parsed
+        let (first_half, second_half) = response.split_at(40);
+        append(&path, first_half);
+        let chunk = appended(&mut tail);
+        assert_eq!(chunk.report.raw_count, 0);
+
+        append(&path, second_half);
+        let chunk = appended(&mut tail);
+        assert_eq!(chunk.report.raw_count, 3);
+        let touched = tail.merge(chunk.events, &mut reqs);
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(touched, vec![0, 1]);
+        assert_eq!(reqs.len(), 2);
+        assert_eq!(reqs[0].resp_type, "Access-Reject");
+        assert_eq!(reqs[1].resp_type, "Access-Accept");
+        assert_eq!(tail.offset(), SAMPLE.trim_end().len() as u64);
+    }
+
+    #[test]
+    fn follows_odbc_lines() {
+        let path = temp_log("tail-odbc", "");
+        let (mut tail, mut reqs, _) = LogTail::open(&path, None).expect("open");
+
+        append(&path, "\"NPS01\",\"IAS\",01/15/2024,08:00:00,1,\"jdoe\",,,,,,,,,,,,,,,,,,,,,\"c1\"\r\n\"NPS01\",\"IAS\",01/15/2024,08:00:01,2,");
+        let chunk = appended(&mut tail);
+        tail.merge(chunk.events, &mut reqs);
+        append(&path, ",,,,,,,,,,,,,,,,,,,,0,\"c1\"\r\n");
+        let chunk = appended(&mut tail);
+        let touched = tail.merge(chunk.events, &mut reqs);
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(touched, vec![0]);
+        assert_eq!(reqs.len(), 1);
+        assert_eq!(reqs[0].user, "jdoe");
+        assert_eq!(reqs[0].resp_type, "Access-Accept");
+    }
+
+    #[test]
+    fn detects_truncation() {
+        let path = temp_log("tail-trunc", SAMPLE);
+        let (mut tail, _, _) = LogTail::open(&path, None).expect("open");
+        fs::write(&path, "<Event></Event>").expect("truncate");
+        let res = tail.read_appended().expect("read");
+        let _ = fs::remove_file(&path);
+        assert!(matches!(res, TailRead::Truncated));
+    }
+}
@@ -0,0 +1,16 @@
+//! Fixtures shared by the unit tests.
+
+use std::fs;
+
+pub const SAMPLE: &str = r#"<Event><Timestamp data_type="4">01/15/2024 08:00:00.123</Timestamp><Computer-Name data_type="1">NPS01</Computer-Name><Packet-Type data_type="0">1</Packet-Type><User-Name data_type="1">CORP\jdoe</User-Name><Client-IP-Address data_type="3">10.0.0.9</Client-IP-Address><Client-Friendly-Name data_type="1">AP-LOBBY</Client-Friendly-Name><Calling-Station-Id data_type="1">AA-BB-CC-DD-EE-FF</Calling-Station-Id><Class data_type="1">311 1 10.0.0.1 01/15/2024 1</Class><SAM-Account-Name data_type="1">CORP\jdoe</SAM-Account-Name></Event>
+<Event><Timestamp data_type="4">01/15/2024 08:00:00.456</Timestamp><Computer-Name data_type="1">NPS01</Computer-Name><Packet-Type data_type="0">3</Packet-Type><Class data_type="1">311 1 10.0.0.1 01/15/2024 1</Class><Reason-Code data_type="0">16</Reason-Code></Event>
+<Event><Timestamp data_type="4">01/15/2024 08:01:00.000</Timestamp><Computer-Name data_type="1">NPS01</Computer-Name><Packet-Type data_type="0">1</Packet-Type><User-Name data_type="1">alice</User-Name><Class data_type="1">311 1 10.0.0.1 01/15/2024 2</Class></Event>
+<Event><Timestamp data_type="4">01/15/2024 08:01:00.100</Timestamp><Computer-Name data_type="1">NPS01</Computer-Name><Packet-Type data_type="0">2</Packet-Type><Class data_type="1">311 1 10.0.0.1 01/15/2024 2</Class><Reason-Code data_type="0">0</Reason-Code></Event>
+"#;
+
+/// Writes `content` to a unique file in the temp directory and returns its path.
+pub fn temp_log(name: &str, content: &str) -> String {
+    let path = std::env::temp_dir().join(format!("radius-log-core-{}-{name}.log", std::process::id()));
+    fs::write(&path, content).expect("write temp log");
+    path.to_string_lossy().into_owned()
+}
@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Event, RadiusRequest};
+
+/// Format of the NPS `Timestamp` attribute (`01/15/2024 08:00:00.123`).
+pub const NPS_TIMESTAMP_FORMAT: &str = "%m/%d/%Y %H:%M:%S%.3f";
+
+// Accepted on input: NPS XML/ODBC (with or without milliseconds), then ISO 8601
+const INPUT_FORMATS: &[&str] = &["%m/%d/%Y %H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"];
+
+/// Parses a timestamp as written by NPS, in the wall-clock time of the server that logged it.
+#[must_use]
+pub fn parse_nps_timestamp(s: &str) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    INPUT_FORMATS.iter().find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+}
+
+/// Time zone of a log source or of the display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Zone {
+    /// Time zone of this machine.
+    #[default]
+    Local,
+    Utc,
+    /// IANA zone, e.g. `"Europe/Paris"`.
+    Named(Tz),
+}
+
+impl Zone {
+    /// Zone from a name: `local`, `utc` or an IANA name (`Europe/Paris`), as typed on the command line.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("local") {
+            return Some(Self::Local);
+        }
+        if name.eq_ignore_ascii_case("utc") {
+            return Some(Self::Utc);
+        }
+        name.parse::<Tz>().ok().map(Self::Named)
+    }
+
+    /// Instant of the wall-clock time `logged` in this zone.
+    ///
+    /// Ambiguous times (DST fall back) take the earliest instant; times
+    /// skipped by a DST jump are read one hour later.
+    #[must_use]
+    pub fn resolve(self, logged: NaiveDateTime) -> Option<DateTime<Utc>> {
+        let resolve_in = |tz: &dyn Fn(&NaiveDateTime) -> Option<DateTime<Utc>>| {
+            tz(&logged).or_else(|| tz(&(logged + TimeDelta::hours(1))))
+        };
+        match self {
+            Self::Local => resolve_in(&|t| Local.from_local_datetime(t).earliest().map(|d| d.to_utc())),
+            Self::Utc => Some(logged.and_utc()),
+            Self::Named(tz) => resolve_in(&|t| tz.from_local_datetime(t).earliest().map(|d| d.to_utc())),
+        }
+    }
+
+    /// Wall-clock time of `instant` in this zone.
+    #[must_use]
+    pub fn to_wall_clock(self, instant: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            Self::Local => instant.with_timezone(&Local).naive_local(),
+            Self::Utc => instant.naive_utc(),
+            Self::Named(tz) => instant.with_timezone(&tz).naive_local(),
+        }
+    }
+}
+
+/// Span of instants, open-ended on a missing side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl TimeRange {
+    /// `seconds` before and after `center` (the "Time Window" context of a request).
+    #[must_use]
+    pub fn around(center: DateTime<Utc>, seconds: u32) -> Self {
+        let delta = TimeDelta::seconds(i64::from(seconds));
+        Self { from: Some(center - delta), to: Some(center + delta) }
+    }
+
+    /// Whether `instant` falls in the range (bounds included); unknown instants never do.
+    #[must_use]
+    pub fn contains(&self, instant: Option<DateTime<Utc>>) -> bool {
+        instant.is_some_and(|t| self.from.is_none_or(|from| t >= from) && self.to.is_none_or(|to| t <= to))
+    }
+
+    /// Whether the span `first..=last` (e.g. of a log file) shares at least one instant with the range.
+    #[must_use]
+    pub fn overlaps(&self, first: DateTime<Utc>, last: DateTime<Utc>) -> bool {
+        self.from.is_none_or(|from| last >= from) && self.to.is_none_or(|to| first <= to)
+    }
+}
+
+/// Time filter of the view: a quick preset, resolved each time the filter
+/// runs (so "last 15 minutes" follows a live tail), or fixed bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeFilter {
+    Last15Minutes,
+    LastHour,
+    /// Since midnight.
+    Today,
+    Yesterday,
+    Between(TimeRange),
+}
+
+impl TimeFilter {
+    pub const PRESETS: [Self; 4] = [Self::Last15Minutes, Self::LastHour, Self::Today, Self::Yesterday];
+
+    /// Instants covered at `now`, days starting at midnight in `zone`.
+    #[must_use]
+    pub fn resolve(self, now: DateTime<Utc>, zone: Zone) -> TimeRange {
+        let midnight = |days_back: i64| {
+            let day = zone.to_wall_clock(now).date() - TimeDelta::days(days_back);
+            zone.resolve(day.and_time(NaiveTime::MIN))
+        };
+        match self {
+            Self::Last15Minutes => TimeRange { from: Some(now - TimeDelta::minutes(15)), to: None },
+            Self::LastHour => TimeRange { from: Some(now - TimeDelta::hours(1)), to: None },
+            Self::Today => TimeRange { from: midnight(0), to: None },
+            Self::Yesterday => TimeRange { from: midnight(1), to: midnight(0).map(|t| t - TimeDelta::nanoseconds(1)) },
+            Self::Between(range) => range,
+        }
+    }
+}
+
+/// How timestamps are interpreted (per server) and displayed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeSettings {
+    /// chrono `strftime` pattern of the Timestamp column.
+    pub display_format: String,
+    /// Zone timestamps are displayed in; `None` shows them as logged by each server.
+    pub display_zone: Option<Zone>,
+    /// Zone of the servers not listed in `server_zones`.
+    pub default_server_zone: Zone,
+    /// Zone per server (Computer-Name), for NPS farms spread over several regions.
+    pub server_zones: HashMap<String, Zone>,
+}
+
+impl Default for TimeSettings {
+    fn default() -> Self {
+        Self {
+            display_format: NPS_TIMESTAMP_FORMAT.to_string(),
+            display_zone: None,
+            default_server_zone: Zone::Local,
+            server_zones: HashMap::new(),
+        }
+    }
+}
+
+impl TimeSettings {
+    /// Zone `server` logs in.
+    #[must_use]
+    pub fn server_zone(&self, server: &str) -> Zone {
+        self.server_zones.get(server).copied().unwrap_or(self.default_server_zone)
+    }
+
+    /// Instant of a single event, with its server's zone.
+    #[must_use]
+    pub fn event_instant(&self, event: &Event) -> Option<DateTime<Utc>> {
+        let logged = parse_nps_timestamp(event.timestamp.as_deref()?)?;
+        self.server_zone(event.server.as_deref().unwrap_or_default()).resolve(logged)
+    }
+
+    /// Resolves the instant of `req` with its server's zone and renders its Timestamp column.
+    ///
+    /// Requests whose timestamp could not be parsed keep the logged text.
+    pub fn apply(&self, req: &mut RadiusRequest) {
+        let Some(logged) = req.logged_at else { return };
+        req.time = self.server_zone(&req.server).resolve(logged);
+
+        let shown = self.shown_time(req).unwrap_or(logged);
+        let mut text = String::new();
+        // An invalid pattern makes chrono fail the write: fall back to the NPS format
+        if write!(text, "{}", shown.format(&self.display_format)).is_err() {
+            text = shown.format(NPS_TIMESTAMP_FORMAT).to_string();
+        }
+        req.timestamp = text;
+    }
+
+    /// Wall-clock time shown in the Timestamp column of `req` (export, charts...).
+    #[must_use]
+    pub fn shown_time(&self, req: &RadiusRequest) -> Option<NaiveDateTime> {
+        match (self.display_zone, req.time) {
+            (Some(zone), Some(instant)) => Some(zone.to_wall_clock(instant)),
+            _ => req.logged_at,
+        }
+    }
+
+    /// [`TimeSettings::apply`] on every request, in parallel.
+    pub fn apply_all(&self, requests: &mut [RadiusRequest]) {
+        requests.par_iter_mut().for_each(|req| self.apply(req));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn req(server: &str, logged: &str) -> RadiusRequest {
+        RadiusRequest {
+            server: server.to_string(),
+            timestamp: logged.to_string(),
+            logged_at: parse_nps_timestamp(logged),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_nps_formats() {
+        let t = parse_nps_timestamp("01/15/2024 08:00:00.123").expect("with millis");
+        assert_eq!(t.format("%Y-%m-%d %H:%M:%S%.3f").to_string(), "2024-01-15 08:00:00.123");
+        assert!(parse_nps_timestamp("01/15/2024 08:00:00").is_some());
+        assert!(parse_nps_timestamp("2024-01-15T08:00:00").is_some());
+        assert!(parse_nps_timestamp("yesterday").is_none());
+    }
+
+    #[test]
+    fn zones_by_name() {
+        assert_eq!(Zone::from_name("UTC"), Some(Zone::Utc));
+        assert_eq!(Zone::from_name("local"), Some(Zone::Local));
+        assert_eq!(Zone::from_name("Europe/Paris"), Some(Zone::Named(Tz::Europe__Paris)));
+        assert_eq!(Zone::from_name("Mars/Olympus"), None);
+    }
+
+    #[test]
+    fn servers_in_different_zones() {
+        let mut settings = TimeSettings {
+            display_format: "%Y-%m-%d %H:%M".to_string(),
+            display_zone: Some(Zone::Utc),
+            default_server_zone: Zone::Utc,
+            ..Default::default()
+        };
+        settings.server_zones.insert("NPS-PARIS".to_string(), Zone::Named(Tz::Europe__Paris));
+
+        let mut paris = req("NPS-PARIS", "07/01/2024 10:00:00.000");
+        let mut london = req("NPS-UTC", "07/01/2024 08:30:00.000");
+        settings.apply(&mut paris);
+        settings.apply(&mut london);
+
+        // 10:00 in Paris (UTC+2 in summer) happened before 08:30 UTC
+        assert_eq!(paris.timestamp, "2024-07-01 08:00");
+        assert!(paris.time < london.time);
+
+        // As logged
+        settings.display_zone = None;
+        settings.apply(&mut paris);
+        assert_eq!(paris.timestamp, "2024-07-01 10:00");
+    }
+
+    #[test]
+    fn invalid_pattern_falls_back_and_unparsed_text_is_kept() {
+        let settings = TimeSettings { display_format: "%Q".to_string(), default_server_zone: Zone::Utc, ..Default::default() };
+        let mut r = req("NPS01", "01/15/2024 08:00:00");
+        settings.apply(&mut r);
+        assert_eq!(r.timestamp, "01/15/2024 08:00:00.000");
+
+        let mut raw = req("NPS01", "garbage");
+        settings.apply(&mut raw);
+        assert_eq!((raw.timestamp.as_str(), raw.time), ("garbage", None));
+    }
+
+    #[test]
+    fn range_around_an_instant() {
+        let at = |s: &str| parse_nps_timestamp(s).map(|t| t.and_utc());
+        let window = TimeRange::around(at("01/15/2024 08:00:00").expect("valid"), 60);
+        assert!(window.contains(at("01/15/2024 07:59:00")));
+        assert!(window.contains(at("01/15/2024 08:01:00")));
+        assert!(!window.contains(at("01/15/2024 08:01:00.001")));
+        assert!(!window.contains(None));
+        assert!(TimeRange { from: at("01/15/2024 08:00:00"), to: None }.contains(at("12/31/2030 00:00:00")));
+    }
+
+    #[test]
+    fn presets_and_overlap() {
+        let at = |s: &str| parse_nps_timestamp(s).expect("valid").and_utc();
+        let now = at("01/15/2024 10:20:00");
+
+        let today = TimeFilter::Today.resolve(now, Zone::Utc);
+        assert_eq!(today.from, Some(at("01/15/2024 00:00:00")));
+        let yesterday = TimeFilter::Yesterday.resolve(now, Zone::Utc);
+        assert!(yesterday.contains(Some(at("01/14/2024 23:59:59.999"))));
+        assert!(!yesterday.contains(Some(at("01/15/2024 00:00:00"))));
+        // Midnight in Paris is 23:00 UTC the day before
+        let paris = TimeFilter::Today.resolve(now, Zone::Named(Tz::Europe__Paris));
+        assert_eq!(paris.from, Some(at("01/14/2024 23:00:00")));
+
+        let last_hour = TimeFilter::LastHour.resolve(now, Zone::Utc);
+        assert!(last_hour.overlaps(at("01/15/2024 00:00:00"), at("01/15/2024 09:30:00")));
+        assert!(!last_hour.overlaps(at("01/14/2024 00:00:00"), at("01/14/2024 23:59:59")));
+        assert!(TimeRange::default().overlaps(at("01/14/2024 00:00:00"), at("01/14/2024 23:59:59")));
+    }
+
+    #[test]
+    fn dst_gap_is_shifted() {
+        // 02:30 does not exist in Paris on 2024-03-31
+        let logged = parse_nps_timestamp("03/31/2024 02:30:00").expect("valid");
+        let instant = Zone::Named(Tz::Europe__Paris).resolve(logged).expect("shifted");
+        assert_eq!(instant.naive_utc().format("%H:%M").to_string(), "01:30");
+    }
+}
@@ -0,0 +1,261 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread::JoinHandle;
+
+use crate::category::ErrorFilter;
+use crate::filter::{filter_and_sort_cancellable, refilter_touched};
+use crate::index::SearchIndex;
+use crate::model::RadiusRequest;
+use crate::query::Query;
+use crate::sort::SortSpec;
+
+/// Criteria of a filter pass.
+#[derive(Debug, Clone)]
+pub struct FilterRequest {
+    pub query: Query,
+    /// Errors-only mode, `None` when off.
+    pub errors: Option<ErrorFilter>,
+    pub sort: SortSpec,
+}
+
+/// Work for the [`FilterWorker`].
+#[derive(Debug, Clone)]
+pub enum FilterJob {
+    /// New criteria: every row is filtered and sorted again.
+    Full(FilterRequest),
+    /// Rows appended or completed by tail mode, checked against the current criteria.
+    Touched(Vec<usize>),
+}
+
+impl FilterJob {
+    // A pending job absorbs the next one: only the latest criteria matter,
+    // and a full pass already covers the touched rows
+    fn absorb(self, next: Self) -> Self {
+        match (self, next) {
+            (Self::Touched(mut rows), Self::Touched(more)) => {
+                rows.extend(more);
+                Self::Touched(rows)
+            }
+            (Self::Full(request), Self::Touched(_)) | (_, Self::Full(request)) => Self::Full(request),
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    pending: Option<(u64, FilterJob)>,
+    submitted: u64,
+    completed: u64,
+    shutdown: bool,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    wake: Condvar,
+    done: Condvar,
+    // Sequence number of the newest full pass; older passes are abandoned
+    latest_full: AtomicU64,
+}
+
+/// Single background thread that runs the filter passes of the list, one at a time.
+///
+/// Jobs are numbered in submission order. A job submitted while another is
+/// waiting replaces it, and a new [`FilterJob::Full`] cancels the pass in progress,
+/// so `filtered_ids` only ever receives the result of the latest criteria.
+///
+/// Locks are taken in one order, `all_items`, then `index`, then `filtered_ids`,
+/// and a full pass releases the first two before writing its result: callers
+/// must not hold `filtered_ids` while waiting for `all_items`. Rows appended
+/// meanwhile are submitted as [`FilterJob::Touched`] and applied after the pass.
+pub struct FilterWorker {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FilterWorker {
+    /// Starts the worker; `on_done` is called from its thread with the number of each finished job.
+    ///
+    /// `generation` is the version of `all_items` (see [`SearchIndex`]), bumped by
+    /// their writers under the write lock: the index is only used when it matches.
+    ///
+    /// # Panics
+    /// The worker thread panics if one of the locks is poisoned.
+    #[must_use]
+    pub fn spawn(
+        all_items: Arc<RwLock<Vec<RadiusRequest>>>,
+        generation: Arc<AtomicU64>,
+        index: Arc<RwLock<SearchIndex>>,
+        filtered_ids: Arc<RwLock<Vec<usize>>>,
+        on_done: impl Fn(u64) + Send + 'static,
+    ) -> Self {
+        let shared = Arc::new(Shared::default());
+        let worker_shared = shared.clone();
+        let thread = std::thread::spawn(move || {
+            // Criteria of the current `filtered_ids`, for touched rows
+            let mut current: Option<FilterRequest> = None;
+            while let Some((seq, job)) = worker_shared.next_job() {
+                let finished = match job {
+                    FilterJob::Full(request) => {
+                        let cancelled = || worker_shared.latest_full.load(Ordering::Relaxed) > seq;
+                        let items = all_items.read().expect("Lock failed");
+                        let index = index.read().expect("Lock failed");
+                        let current_index = index.current(generation.load(Ordering::SeqCst));
+                        let ids = filter_and_sort_cancellable(
+                            &items, current_index, &request.query, request.errors.as_ref(), &request.sort, &cancelled,
+                        );
+                        drop(index);
+                        drop(items);
+                        // Criteria replaced while the result waited for the lock: the newer pass writes it
+                        ids.and_then(|ids| {
+                            let mut filtered = filtered_ids.write().expect("Lock failed");
+                            if cancelled() {
+                                return None;
+                            }
+                            *filtered = ids;
+                            drop(filtered);
+                            current = Some(request);
+                            Some(())
+                        })
+                    }
+                    FilterJob::Touched(rows) => {
+                        if let Some(request) = &current {
+                            let items = all_items.read().expect("Lock failed");
+                            let mut ids = filtered_ids.write().expect("Lock failed");
+                            refilter_touched(&items, &mut ids, &rows, &request.query, request.errors.as_ref(), &request.sort);
+                            drop(ids);
+                            drop(items);
+                        }
+                        Some(())
+                    }
+                };
+                if finished.is_some() {
+                    worker_shared.complete(seq);
+                    on_done(seq);
+                }
+            }
+        });
+        Self { shared, thread: Some(thread) }
+    }
+
+    /// Queues `job` and returns its number (see [`FilterWorker::wait`]).
+    ///
+    /// # Panics
+    /// Panics if the worker state lock is poisoned.
+    #[must_use]
+    pub fn submit(&self, job: FilterJob) -> u64 {
+        let mut state = self.shared.state.lock().expect("Lock poisoned");
+        state.submitted += 1;
+        let seq = state.submitted;
+        let job = match state.pending.take() {
+            Some((_, pending)) => pending.absorb(job),
+            None => job,
+        };
+        if matches!(job, FilterJob::Full(_)) {
+            self.shared.latest_full.store(seq, Ordering::Relaxed);
+        }
+        state.pending = Some((seq, job));
+        drop(state);
+        self.shared.wake.notify_one();
+        seq
+    }
+
+    /// Blocks until job `seq`, or a later one that superseded it, has finished.
+    ///
+    /// # Panics
+    /// Panics if the worker state lock is poisoned.
+    pub fn wait(&self, seq: u64) {
+        let state = self.shared.state.lock().expect("Lock poisoned");
+        drop(self.shared.done.wait_while(state, |state| state.completed < seq && !state.shutdown).expect("Lock poisoned"));
+    }
+}
+
+impl Shared {
+    // Next job to run, `None` once the worker is dropped
+    fn next_job(&self) -> Option<(u64, FilterJob)> {
+        let mut state = self.state.lock().expect("Lock poisoned");
+        loop {
+            if state.shutdown {
+                return None;
+            }
+            if let Some(job) = state.pending.take() {
+                return Some(job);
+            }
+            state = self.wake.wait(state).expect("Lock poisoned");
+        }
+    }
+
+    fn complete(&self, seq: u64) {
+        let mut state = self.state.lock().expect("Lock poisoned");
+        state.completed = state.completed.max(seq);
+        drop(state);
+        self.done.notify_all();
+    }
+}
+
+impl Drop for FilterWorker {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.shared.state.lock() {
+            state.shutdown = true;
+        }
+        self.shared.latest_full.store(u64::MAX, Ordering::Relaxed);
+        self.shared.wake.notify_all();
+        self.shared.done.notify_all();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::LogColumn;
+    use std::sync::mpsc;
+
+    fn req(user: &str) -> RadiusRequest {
+        RadiusRequest { user: user.to_string(), ..Default::default() }
+    }
+
+    fn full(text: &str) -> FilterJob {
+        FilterJob::Full(FilterRequest {
+            query: Query::parse(text).expect("valid"),
+            errors: None,
+            sort: SortSpec::by(LogColumn::User, false),
+        })
+    }
+
+    #[test]
+    fn latest_criteria_win() {
+        let items = Arc::new(RwLock::new(vec![req("bob"), req("alice"), req("bobby")]));
+        let index = Arc::new(RwLock::new(SearchIndex::default()));
+        let ids = Arc::new(RwLock::new(Vec::new()));
+        let (tx, rx) = mpsc::channel();
+        let worker = FilterWorker::spawn(items.clone(), Arc::new(AtomicU64::new(0)), index, ids.clone(), move |seq| tx.send(seq).expect("send"));
+
+        // Typed quickly: only the last search is guaranteed to be applied
+        let _ = worker.submit(full("a"));
+        let _ = worker.submit(full("bo"));
+        let last = worker.submit(full("bob"));
+        worker.wait(last);
+        assert_eq!(*ids.read().expect("Lock failed"), vec![0, 2]);
+
+        // Tail rows are checked against the latest criteria
+        items.write().expect("Lock failed").push(req("bobo"));
+        let touched = worker.submit(FilterJob::Touched(vec![3]));
+        worker.wait(touched);
+        assert_eq!(*ids.read().expect("Lock failed"), vec![0, 2, 3]);
+
+        drop(worker);
+        let done: Vec<u64> = rx.iter().collect();
+        assert!(done.windows(2).all(|w| w[0] < w[1]) && done.contains(&last) && done.last() == Some(&touched), "{done:?}");
+    }
+
+    #[test]
+    fn pending_jobs_merge() {
+        let job = FilterJob::Touched(vec![1]).absorb(FilterJob::Touched(vec![2]));
+        assert!(matches!(&job, FilterJob::Touched(rows) if rows == &[1, 2]));
+        assert!(matches!(job.absorb(full("x")), FilterJob::Full(_)));
+        assert!(matches!(full("x").absorb(FilterJob::Touched(vec![3])), FilterJob::Full(_)));
+    }
+}
@@ -0,0 +1,67 @@
+//! NPS "DTS compliant" XML log records.
+//!
+//! Each record is an `<Event>` element whose children are the attributes:
+//! `<Event><Timestamp data_type="4">...</Timestamp><Packet-Type data_type="0">1</Packet-Type>...</Event>`
+
+use quick_xml::errors::IllFormedError;
+use quick_xml::escape::unescape;
+use quick_xml::events::Event as XmlEvent;
+use quick_xml::Reader;
+
+use crate::attributes::AttributeMap;
+use crate::model::Event;
+
+/// Parses one `<Event>` element, keeping every child element as an attribute.
+///
+/// # Errors
+/// Returns the XML error if the element is malformed.
+pub fn parse_xml_event(xml: &str) -> quick_xml::Result<Event> {
+    let mut reader = Reader::from_str(xml);
+    let mut attributes = AttributeMap::new();
+    let mut in_event = false;
+
+    loop {
+        match reader.read_event()? {
+            XmlEvent::Start(e) if !in_event => in_event = e.name().as_ref() == b"Event",
+            XmlEvent::Start(e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                let raw = reader.read_text(e.name())?;
+                let value = unescape(raw.trim())?;
+                if !value.is_empty() {
+                    attributes.push(name, value);
+                }
+            }
+            XmlEvent::End(e) if e.name().as_ref() == b"Event" => break,
+            XmlEvent::Eof => return Err(IllFormedError::MissingEndTag("Event".to_string()).into()),
+            _ => {}
+        }
+    }
+
+    Ok(Event::from_attributes(attributes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_every_child_in_order() {
+        let ev = parse_xml_event(
+            "<Event><Timestamp data_type=\"4\">01/15/2024 08:00:00.123</Timestamp>\
+             <Called-Station-Id data_type=\"1\">00-11-22-33-44-55:Corp</Called-Station-Id>\
+             <NP-Policy-Name data_type=\"1\">Wifi &amp; VPN</NP-Policy-Name><Empty/>\
+             <Packet-Type data_type=\"0\">1</Packet-Type></Event>",
+        ).expect("valid event");
+        assert_eq!(ev.timestamp.as_deref(), Some("01/15/2024 08:00:00.123"));
+        assert_eq!(ev.packet_type.as_deref(), Some("1"));
+        let names: Vec<&str> = ev.attributes.iter().map(|(n, _)| n).collect();
+        assert_eq!(names, vec!["Timestamp", "Called-Station-Id", "NP-Policy-Name", "Packet-Type"]);
+        assert_eq!(ev.attributes.get("np-policy-name"), Some("Wifi & VPN"));
+    }
+
+    #[test]
+    fn reports_malformed_xml() {
+        assert!(parse_xml_event("<Event><User-Name>x</Class></Event>").is_err());
+        assert!(parse_xml_event("<Event><User-Name>x</User-Name>").is_err());
+    }
+}
@@ -3,16 +3,113 @@ ui-open-log = 📂 Open Log
 ui-folder = 📂 Folder
 ui-errors = ⚠️ Errors
 ui-append = Append
+ui-regex = Regex
 ui-copy = 📋 Copy
 ui-status-ready = Ready. Open a log file.
 ui-search-hint = Search...
 ui-status-display = Displaying: { $count } sessions ({ $raw } raw events).
+ui-status-display-lost = Displaying: { $count } sessions ({ $raw } raw events, { $lost } skipped - right-click > Parse report).
+ui-status-display-failed-files = Displaying: { $count } sessions ({ $raw } raw events, { $lost } skipped, { $files } file(s) not parsed - right-click > Parse report).
+ui-status-out-of-range = { $files } file(s) outside the time range not loaded.
 ui-status-error = Error during loading.
 ui-status-loading = Loading...
 ui-status-loading-folder = Loading folder...
+ui-export = 📊 Export
+ui-file-xlsx = Excel Workbook
+ui-file-json = JSON file
+ui-presets = ⭐ Presets
+ui-preset-save = 💾 Save current view...
+ui-preset-delete = 🗑 Delete current preset
+ui-preset-import = 📥 Import presets...
+ui-preset-export = 📤 Export presets...
+ui-errors-all-categories = All failures
+cat-success = Success
+cat-credentials = Credentials
+cat-account-state = Account state
+cat-certificate = Certificate / PKI
+cat-policy = Policy
+cat-infrastructure = Infrastructure / proxy
+cat-other = Other failures
+ui-preset-save-title = Save preset
+ui-preset-name = Name
+ui-preset-delete-confirm = Delete the preset "{ $name }"?
+ui-status-preset-applied = Preset: { $name }
+ui-status-preset-saved = Preset "{ $name }" saved.
+ui-status-no-preset = Apply a preset first.
+ui-status-presets-imported = { $count } preset(s) imported.
+ui-status-presets-exported = { $count } preset(s) exported.
+ui-status-presets-error = Presets: { $error }
+ui-status-exporting = Exporting...
+ui-status-exported = Exported { $count } sessions to Excel.
+ui-status-export-error = Error during export.
+ui-status-query-error = Invalid search: { $error }
 ui-menu-copy-cell = 📋 Copy cell
 ui-menu-copy-row = 📋 Copy entire row
+ui-menu-session-details = 🔎 Session details
+ui-session-title = Session details - { $user } - { $timestamp }
+ui-session-unreadable = The events of this request could not be read again from the log file (moved, deleted or rewritten since it was loaded).
+ui-menu-copy-attributes = 📋 Copy all attributes
+ui-menu-parse-report = 🩺 Parse report
+ui-report-title = Parse report
+ui-menu-statistics = 📊 Statistics
+ui-stats-title = Statistics - { $shown } of { $total } sessions
+ui-stats-empty = No request in the view: load a log, or widen the search, the time range or the column filters.
+ui-menu-detect-attacks = 🚨 Detect attacks...
+ui-findings-title = Attack detection
+ui-findings-count = Attack detection - { $count } findings
+ui-findings-none = No password spray (one source failing against { $users } users) or brute force ({ $failures } failures of one user) within { $secs } s. The thresholds are in config.json (detection).
+ui-findings-start = Start
+ui-findings-attack = Attack
+ui-findings-source = Source
+ui-findings-value = Value
+ui-findings-users = Users
+ui-findings-failures = Failures
+ui-findings-show = Show in the list
+ui-findings-close = Close
+attack-password-spray = Password spray
+attack-brute-force = Brute force
+ui-report-copy-prompt = Copy the full report to the clipboard?
 ui-menu-filter-cell = 🔍 Filter by this value
+ui-menu-time-window = ⏱ Time window
+ui-time-window-secs = ±{ $secs } s
+ui-menu-leave-time-window = ✖ Leave time window
+ui-status-time-window = Time window: ±{ $secs } s around { $timestamp } (right-click > Leave time window to exit).
+ui-status-no-time = This request has no valid timestamp.
+ui-menu-time-range = 🕒 Time range...
+ui-time-range-title = Time range
+ui-reload-folder-question = { $files } file(s) of the folder were not loaded because they were outside the previous time range. Reload the folder to include them?
+ui-time-from = From
+ui-time-to = To
+ui-time-last-15-min = Last 15 min
+ui-time-last-hour = Last hour
+ui-time-today = Today
+ui-time-yesterday = Yesterday
+ui-time-between = Custom range
+ui-time-clear = All times
+ui-cancel = Cancel
+ui-status-time-range = Time range: { $range }
+ui-status-time-range-cleared = Time range cleared.
+bucket-minute = minute
+bucket-5min = 5 minutes
+bucket-hour = hour
+ui-chart-summary = { $from } → { $to }, per { $size }, peak { $max } - drag to set the time range
+ui-chart-empty = No timestamp in the view
+ui-chart-outside = { $count } far off, left out
+ui-menu-chart-export = 💾 Export as CSV...
+ui-menu-chart-clear = ✖ Clear the time range
+ui-file-csv = CSV file
+ui-status-chart-exported = Time series exported to { $path }
+ui-menu-column-filter = 🔽 Filter this column...
+ui-menu-column-filters = Column filters
+ui-menu-clear-column-filters = ✖ Clear all column filters
+ui-column-filter-title = Filter: { $column }
+ui-column-filter-contains = Contains
+ui-column-filter-value = Value
+ui-column-filter-rows = Rows
+ui-column-filter-empty = (empty)
+ui-column-filter-clear = No filter
+ui-menu-mac-notation = MAC notation
+ui-mac-as-logged = As logged
 ui-btn-show-all = ⚠️ Show all
 ui-btn-errors-only = ⚠️ Errors
 ui-unknown-user = - UNKNOWN -
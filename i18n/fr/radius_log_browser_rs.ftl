@@ -3,16 +3,113 @@ ui-open-log = 📂 Ouvrir Log
 ui-folder = 📂 Dossier
 ui-errors = ⚠️ Erreurs
 ui-append = Append
+ui-regex = Regex
 ui-copy = 📋 Copier
 ui-status-ready = Prêt. Ouvrez un fichier log.
 ui-search-hint = Rechercher...
 ui-status-display = Affichage : { $count } sessions ({ $raw } événements bruts).
+ui-status-display-lost = Affichage : { $count } sessions ({ $raw } événements bruts, { $lost } ignorés - clic droit > Rapport d'analyse).
+ui-status-display-failed-files = Affichage : { $count } sessions ({ $raw } événements bruts, { $lost } ignorés, { $files } fichier(s) non analysé(s) - clic droit > Rapport d'analyse).
+ui-status-out-of-range = { $files } fichier(s) hors de la plage horaire non chargé(s).
 ui-status-error = Erreur lors du chargement.
 ui-status-loading = Chargement...
 ui-status-loading-folder = Chargement du répertoire...
+ui-export = 📊 Exporter
+ui-file-xlsx = Classeur Excel
+ui-file-json = Fichier JSON
+ui-presets = ⭐ Favoris
+ui-preset-save = 💾 Enregistrer la vue...
+ui-preset-delete = 🗑 Supprimer le favori actif
+ui-preset-import = 📥 Importer des favoris...
+ui-preset-export = 📤 Exporter les favoris...
+ui-errors-all-categories = Tous les échecs
+cat-success = Succès
+cat-credentials = Identifiants
+cat-account-state = État du compte
+cat-certificate = Certificat / PKI
+cat-policy = Stratégie
+cat-infrastructure = Infrastructure / proxy
+cat-other = Autres échecs
+ui-preset-save-title = Enregistrer le favori
+ui-preset-name = Nom
+ui-preset-delete-confirm = Supprimer le favori « { $name } » ?
+ui-status-preset-applied = Favori : { $name }
+ui-status-preset-saved = Favori « { $name } » enregistré.
+ui-status-no-preset = Appliquez d'abord un favori.
+ui-status-presets-imported = { $count } favori(s) importé(s).
+ui-status-presets-exported = { $count } favori(s) exporté(s).
+ui-status-presets-error = Favoris : { $error }
+ui-status-exporting = Export en cours...
+ui-status-exported = { $count } sessions exportées vers Excel.
+ui-status-export-error = Erreur lors de l'export.
+ui-status-query-error = Recherche invalide : { $error }
 ui-menu-copy-cell = 📋 Copier la cellule
 ui-menu-copy-row = 📋 Copier la ligne entière
+ui-menu-session-details = 🔎 Détails de la session
+ui-session-title = Détails de la session - { $user } - { $timestamp }
+ui-session-unreadable = Les événements de cette requête n'ont pas pu être relus dans le fichier journal (déplacé, supprimé ou réécrit depuis son chargement).
+ui-menu-copy-attributes = 📋 Copier tous les attributs
+ui-menu-parse-report = 🩺 Rapport d'analyse
+ui-report-title = Rapport d'analyse
+ui-menu-statistics = 📊 Statistiques
+ui-stats-title = Statistiques - { $shown } sessions sur { $total }
+ui-stats-empty = Aucune requête affichée : chargez un journal, ou élargissez la recherche, la plage horaire ou les filtres de colonnes.
+ui-menu-detect-attacks = 🚨 Détecter les attaques...
+ui-findings-title = Détection d'attaques
+ui-findings-count = Détection d'attaques - { $count } alertes
+ui-findings-none = Aucune pulvérisation de mots de passe (une source en échec sur { $users } utilisateurs) ni force brute ({ $failures } échecs d'un utilisateur) en { $secs } s. Les seuils sont dans config.json (detection).
+ui-findings-start = Début
+ui-findings-attack = Attaque
+ui-findings-source = Source
+ui-findings-value = Valeur
+ui-findings-users = Utilisateurs
+ui-findings-failures = Échecs
+ui-findings-show = Afficher dans la liste
+ui-findings-close = Fermer
+attack-password-spray = Pulvérisation de mots de passe
+attack-brute-force = Force brute
+ui-report-copy-prompt = Copier le rapport complet dans le presse-papiers ?
 ui-menu-filter-cell = 🔍 Filtrer par cette valeur
+ui-menu-time-window = ⏱ Fenêtre temporelle
+ui-time-window-secs = ±{ $secs } s
+ui-menu-leave-time-window = ✖ Quitter la fenêtre temporelle
+ui-status-time-window = Fenêtre temporelle : ±{ $secs } s autour de { $timestamp } (clic droit > Quitter la fenêtre temporelle pour sortir).
+ui-status-no-time = Cette requête n'a pas d'horodatage valide.
+ui-menu-time-range = 🕒 Période...
+ui-time-range-title = Période
+ui-reload-folder-question = { $files } fichier(s) du dossier n'ont pas été chargés car hors de la plage horaire précédente. Recharger le dossier pour les inclure ?
+ui-time-from = Du
+ui-time-to = Au
+ui-time-last-15-min = 15 dern. min
+ui-time-last-hour = Dernière heure
+ui-time-today = Aujourd'hui
+ui-time-yesterday = Hier
+ui-time-between = Période personnalisée
+ui-time-clear = Toute période
+ui-cancel = Annuler
+ui-status-time-range = Période : { $range }
+ui-status-time-range-cleared = Période effacée.
+bucket-minute = minute
+bucket-5min = 5 minutes
+bucket-hour = heure
+ui-chart-summary = { $from } → { $to }, par { $size }, pic { $max } - glisser pour choisir la période
+ui-chart-empty = Aucun horodatage dans la vue
+ui-chart-outside = { $count } trop éloignées, ignorées
+ui-menu-chart-export = 💾 Exporter en CSV...
+ui-menu-chart-clear = ✖ Effacer la période
+ui-file-csv = Fichier CSV
+ui-status-chart-exported = Série temporelle exportée vers { $path }
+ui-menu-column-filter = 🔽 Filtrer cette colonne...
+ui-menu-column-filters = Filtres de colonnes
+ui-menu-clear-column-filters = ✖ Effacer tous les filtres de colonnes
+ui-column-filter-title = Filtre : { $column }
+ui-column-filter-contains = Contient
+ui-column-filter-value = Valeur
+ui-column-filter-rows = Lignes
+ui-column-filter-empty = (vide)
+ui-column-filter-clear = Aucun filtre
+ui-menu-mac-notation = Notation des adresses MAC
+ui-mac-as-logged = Telle que journalisée
 ui-btn-show-all = ⚠️ Tout afficher
 ui-btn-errors-only = ⚠️ Erreurs
 ui-unknown-user = - INCONNU -
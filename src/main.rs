@@ -3,16 +3,17 @@
 
 use winsafe::prelude::*;
 use winsafe::{gui, co, msg};
-use quick_xml::de::from_str;
-use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock, Mutex, OnceLock};
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::fs;
-use std::collections::{HashMap, HashSet};
+use std::io::BufWriter;
+use std::collections::HashMap;
 use std::thread;
 use std::time::Duration;
 use notify::{Watcher, RecursiveMode};
+use radius_log_core::{attribute_names, detect_attacks, distinct_values, events_text, export_xlsx, file_time_span, list_log_files, parse_full_logic, presets_from_json, presets_to_json, read_events, upsert_preset, ColumnFilter, ColumnFilters, DetectionSettings, ErrorFilter, ExportColumn, FilterJob, FilterPreset, FilterRequest, FilterWorker, Finding, LogColumn, LogTail, MacNotation, ParseReport, ProgressSink, Query, RadiusRequest, ReasonCategories, ReasonCategory, SearchIndex, SortSpec, Statistics, TailRead, TimeFilter, TimeRange, TimeSeries, TimeSettings, Zone, DEFAULT_TOP};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike, Utc};
 
 // Manual FFI declaration for SetCursor (not exported by winsafe)
 // This allows us to use winsafe's HCURSOR with the native SetCursor function
@@ -80,6 +81,7 @@ pub fn clone_hicon(hicon: &winsafe::guard::DestroyIconGuard) -> winsafe::HICON {
 const WM_LOAD_DONE: co::WM = make_wm_safe(co::WM::USER.raw() + 1);
 const WM_LOAD_ERROR: co::WM = make_wm_safe(co::WM::USER.raw() + 2);
 const WM_FILTER_DONE: co::WM = make_wm_safe(co::WM::USER.raw() + 3); // New message
+const WM_EXPORT_DONE: co::WM = make_wm_safe(co::WM::USER.raw() + 4); // wparam = rows written, lparam = 1 on failure
 const WM_PROGRESS: co::WM = make_wm_safe(co::WM::USER.raw() + 10); // For progress bar
 const WM_FILE_CHANGED: co::WM = make_wm_safe(co::WM::USER.raw() + 11); // For Tail mode
 const WM_FORCE_WAIT: co::WM = make_wm_safe(co::WM::USER.raw() + 20);
@@ -123,56 +125,21 @@ impl SafeHWND {
         });
     }
 }
-const IDT_SEARCH_TIMER: usize = 100; // ID for search timer
 
-// IDs for the Font menu
+// Loading progress is forwarded to the UI thread as WM_PROGRESS (wparam = percent)
+impl ProgressSink for SafeHWND {
+    fn report(&self, percent: u8) {
+        self.post(WM_PROGRESS, percent as usize, 0);
+    }
+}
 
+const IDT_SEARCH_TIMER: usize = 100; // ID for search timer
+const CHART_HEIGHT: i32 = 90; // Time series chart above the list
+const TIME_WINDOW_PRESETS: [u32; 4] = [10, 30, 60, 300]; // Seconds offered in the "Time window" menu
+const PRESET_ACTIONS: [&str; 4] = ["ui-preset-save", "ui-preset-delete", "ui-preset-import", "ui-preset-export"]; // After the names in the presets dropdown
 
-// --- XML Structures ---
-#[derive(Debug, Deserialize, Clone)]
-#[serde(rename = "Event")]
-struct Event {
-    #[serde(rename = "Timestamp")]
-    timestamp: Option<String>,
-    #[serde(rename = "Packet-Type")]
-    packet_type: Option<String>,
-    #[serde(rename = "Class")]
-    class: Option<String>,
-    #[serde(rename = "Acct-Session-Id")]
-    acct_session_id: Option<String>,
-    #[serde(rename = "Computer-Name")]
-    server: Option<String>,
-    #[serde(rename = "Client-IP-Address")]
-    ap_ip: Option<String>,
-    #[serde(rename = "NAS-Identifier")]
-    ap_name: Option<String>,
-    #[serde(rename = "Client-Friendly-Name")]
-    client_friendly_name: Option<String>,
-    #[serde(rename = "Calling-Station-Id")]
-    mac: Option<String>,
-    #[serde(rename = "User-Name")]
-    user_name: Option<String>,
-    #[serde(rename = "SAM-Account-Name")]
-    sam_account: Option<String>,
-    #[serde(rename = "Reason-Code")]
-    reason_code: Option<String>,
-}
+// IDs for the Font menu
 
-#[derive(Clone, Debug, Default)]
-struct RadiusRequest {
-    timestamp: String,
-    req_type: String,
-    server: String,
-    ap_ip: String,
-    ap_name: String,
-    mac: String,
-    user: String,
-    resp_type: String,
-    reason: String,
-    class_id: String,
-    session_id: String,
-    bg_color: Option<(u8, u8, u8)>,
-}
 
 #[derive(Serialize, Deserialize, Clone)]
 struct AppConfig {
@@ -182,17 +149,54 @@ struct AppConfig {
     window_height: i32,
     column_widths: Vec<i32>,
     visible_columns: Vec<LogColumn>,
+    // Named views picked from the presets dropdown
+    #[serde(default)]
+    filter_presets: Vec<FilterPreset>,
+    // Timestamp display format / zones (absent from older config files)
+    #[serde(default)]
+    time: TimeSettings,
+    #[serde(default)]
+    mac_notation: MacNotation,
+    // Half-width of the "Time window" context, in seconds
+    #[serde(default = "default_time_window_secs")]
+    time_window_secs: u32,
+    // Header filters of the list columns
+    #[serde(default)]
+    column_filters: ColumnFilters,
+    // Sort keys of the list, most significant first
+    #[serde(default)]
+    sort: SortSpec,
+    // Failure category targeted by "Errors" and the error navigation (None = every failure)
+    #[serde(default)]
+    error_category: Option<ReasonCategory>,
+    // Per-code overrides of the built-in reason code categories
+    #[serde(default)]
+    reason_categories: ReasonCategories,
+    // Thresholds of the password-spray / brute-force detection
+    #[serde(default)]
+    detection: DetectionSettings,
 }
 
+const fn default_time_window_secs() -> u32 { 60 }
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             window_x: 0,
             window_y: 0,
-            window_width: 1000, // Slightly larger default value
+            window_width: 1250, // Room for the presets and error category dropdowns
             window_height: 700,
             column_widths: vec![150, 120, 120, 110, 150, 130, 150, 150, 350, 150],
             visible_columns: LogColumn::all(),
+            filter_presets: Vec::new(),
+            time: TimeSettings::default(),
+            mac_notation: MacNotation::default(),
+            time_window_secs: default_time_window_secs(),
+            column_filters: ColumnFilters::default(),
+            sort: SortSpec::default(),
+            error_category: None,
+            reason_categories: ReasonCategories::default(),
+            detection: DetectionSettings::default(),
         }
     }
 }
@@ -228,76 +232,6 @@ impl AppConfig {
 
 }
 
-// Optimization: Zero-allocation case-insensitive substring search
-fn contains_ignore_case(haystack: &str, needle_lower: &str) -> bool {
-    if needle_lower.is_empty() { return true; }
-    let needle_len = needle_lower.len();
-    let haystack_len = haystack.len();
-
-    if needle_len > haystack_len { return false; }
-
-    let needle_bytes = needle_lower.as_bytes();
-    let haystack_bytes = haystack.as_bytes();
-    
-    for i in 0..=(haystack_len - needle_len) {
-        let mut split_match = true;
-        for j in 0..needle_len {
-            if !haystack_bytes[i + j].to_ascii_lowercase().eq(&needle_bytes[j]) {
-                split_match = false;
-                break;
-            }
-        }
-        if split_match { return true; }
-    }
-    false
-}
-
-impl RadiusRequest {
-    // OPTIMIZATION: Case-insensitive search without massive intermediate allocation
-    // Change signature to accept &str (already lowercase)
-    fn matches(&self, query_lower: &str) -> bool {
-        if query_lower.is_empty() { return true; }
-        
-        // Use the zero-allocation helper
-        contains_ignore_case(&self.timestamp, query_lower)
-        || contains_ignore_case(&self.user, query_lower)
-        || contains_ignore_case(&self.mac, query_lower)
-        || contains_ignore_case(&self.ap_ip, query_lower)
-        || contains_ignore_case(&self.ap_name, query_lower)
-        || contains_ignore_case(&self.server, query_lower)
-        || contains_ignore_case(&self.reason, query_lower)
-        || contains_ignore_case(&self.req_type, query_lower)
-        || contains_ignore_case(&self.resp_type, query_lower)
-    }
-
-    fn to_tsv(&self) -> String {
-        format!("{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}", 
-            self.timestamp, self.req_type, self.server, self.ap_ip, 
-            self.ap_name, self.mac, self.user, self.reason)
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
-enum LogColumn {
-    Timestamp, Type, Server, ApIp, ApName, Mac, User, ResponseType, Reason, Session,
-}
-
-impl LogColumn {
-    fn all() -> Vec<Self> {
-        vec![Self::Timestamp, Self::Type, Self::Server, Self::ApIp,
-             Self::ApName, Self::Mac, Self::User, Self::ResponseType, Self::Reason, Self::Session]
-    }
-
-    const fn ftl_key(self) -> &'static str {
-        match self {
-            Self::Timestamp => "col-timestamp", Self::Type => "col-type", Self::Server => "col-server",
-            Self::ApIp => "col-ap-ip", Self::ApName => "col-ap-name", Self::Mac => "col-mac",
-            Self::User => "col-user", Self::ResponseType => "col-responsetype", Self::Reason => "col-reason",
-            Self::Session => "col-session",
-        }
-    }
-}
-
 // --- About Window ---
 
 #[derive(Clone)]
@@ -354,31 +288,667 @@ impl AboutWindow {
     fn on_wm_events(&self) {
         let me = self.clone();
         self.wnd.on().wm_create(move |_| {
-            let hinst = winsafe::HINSTANCE::GetModuleHandle(None).expect("GetModuleHandle failed");
-            
-            // Load icon from resource ID 1 using winsafe high-level API
-            let icon_size = winsafe::SIZE::with(128, 128);
-            if let Ok(hicon_guard) = hinst.LoadImageIcon(
-                winsafe::IdOicStr::Id(1),
-                icon_size,
-                co::LR::DEFAULTCOLOR | co::LR::SHARED,
-            ) {
-                // Clone the HICON to use with SetIcon (guard will not destroy due to LR_SHARED)
-                let hicon = clone_hicon(&hicon_guard);
-                let _ = send_message_safe(me.stc_icon.hwnd(), msg::stm::SetIcon { icon: &hicon });
+            let hinst = winsafe::HINSTANCE::GetModuleHandle(None).expect("GetModuleHandle failed");
+            
+            // Load icon from resource ID 1 using winsafe high-level API
+            let icon_size = winsafe::SIZE::with(128, 128);
+            if let Ok(hicon_guard) = hinst.LoadImageIcon(
+                winsafe::IdOicStr::Id(1),
+                icon_size,
+                co::LR::DEFAULTCOLOR | co::LR::SHARED,
+            ) {
+                // Clone the HICON to use with SetIcon (guard will not destroy due to LR_SHARED)
+                let hicon = clone_hicon(&hicon_guard);
+                let _ = send_message_safe(me.stc_icon.hwnd(), msg::stm::SetIcon { icon: &hicon });
+            }
+            Ok(0)
+        });
+
+        let wnd = self.wnd.clone();
+        self.btn_ok.on().bn_clicked(move || {
+            send_message_safe(wnd.hwnd(), winsafe::msg::wm::Close {});
+            Ok(())
+        });
+    }
+
+    pub fn show(&self, parent: &impl winsafe::prelude::GuiParent) -> winsafe::AnyResult<()> {
+        self.wnd.show_modal(parent)
+    }
+}
+
+// --- Session Detail Window ---
+
+// Every event of one request (request, challenges, response, accounting) with all its attributes
+#[derive(Clone)]
+struct SessionWindow {
+    wnd:      gui::WindowModal,
+    #[allow(dead_code)]
+    txt_detail: gui::Edit,
+    btn_copy: gui::Button,
+    btn_ok:   gui::Button,
+    detail:   String,
+}
+
+impl SessionWindow {
+    pub fn new(title: &str, detail: String) -> Self {
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+
+        let wnd = gui::WindowModal::new(gui::WindowModalOpts {
+            title,
+            size: (760, 520),
+            style: co::WS::CAPTION | co::WS::SYSMENU | co::WS::VISIBLE | co::WS::SIZEBOX,
+            ..Default::default()
+        });
+
+        // Multi-line edit controls need CRLF line breaks
+        let txt_detail = gui::Edit::new(&wnd, gui::EditOpts {
+            text: &detail.replace('\n', "\r\n"),
+            position: (10, 10),
+            width: 740,
+            height: 455,
+            control_style: co::ES::MULTILINE | co::ES::READONLY | co::ES::AUTOVSCROLL,
+            window_style: co::WS::CHILD | co::WS::VISIBLE | co::WS::VSCROLL | co::WS::TABSTOP,
+            resize_behavior: (gui::Horz::Resize, gui::Vert::Resize),
+            ..Default::default()
+        });
+
+        let btn_copy = gui::Button::new(&wnd, gui::ButtonOpts {
+            text: &loader.get("ui-copy"),
+            position: (580, 475),
+            width: 80,
+            height: 30,
+            resize_behavior: (gui::Horz::Repos, gui::Vert::Repos),
+            ..Default::default()
+        });
+
+        let btn_ok = gui::Button::new(&wnd, gui::ButtonOpts {
+            text: "OK",
+            position: (670, 475),
+            width: 80,
+            height: 30,
+            resize_behavior: (gui::Horz::Repos, gui::Vert::Repos),
+            ..Default::default()
+        });
+
+        let new_self = Self { wnd, txt_detail, btn_copy, btn_ok, detail };
+        new_self.on_events();
+        new_self
+    }
+
+    fn on_events(&self) {
+        let detail = self.detail.clone();
+        self.btn_copy.on().bn_clicked(move || {
+            let _ = clipboard_win::set_clipboard_string(&detail);
+            Ok(())
+        });
+
+        let wnd = self.wnd.clone();
+        self.btn_ok.on().bn_clicked(move || {
+            send_message_safe(wnd.hwnd(), winsafe::msg::wm::Close {});
+            Ok(())
+        });
+    }
+
+    pub fn show(&self, parent: &impl winsafe::prelude::GuiParent) -> winsafe::AnyResult<()> {
+        self.wnd.show_modal(parent)
+    }
+}
+
+// --- Statistics Window ---
+
+// Summary of the view: outcomes, success rate, period, distinct values and top lists
+#[derive(Clone)]
+struct StatsWindow {
+    wnd:       gui::WindowModal,
+    #[allow(dead_code)]
+    txt_stats: gui::Edit,
+    btn_copy:  gui::Button,
+    btn_ok:    gui::Button,
+    text:      String,
+}
+
+impl StatsWindow {
+    pub fn new(title: &str, text: String) -> Self {
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+
+        let wnd = gui::WindowModal::new(gui::WindowModalOpts {
+            title,
+            size: (560, 600),
+            style: co::WS::CAPTION | co::WS::SYSMENU | co::WS::VISIBLE | co::WS::SIZEBOX,
+            ..Default::default()
+        });
+
+        // Multi-line edit controls need CRLF line breaks
+        let txt_stats = gui::Edit::new(&wnd, gui::EditOpts {
+            text: &text.replace('\n', "\r\n"),
+            position: (10, 10),
+            width: 540,
+            height: 535,
+            control_style: co::ES::MULTILINE | co::ES::READONLY | co::ES::AUTOVSCROLL,
+            window_style: co::WS::CHILD | co::WS::VISIBLE | co::WS::VSCROLL | co::WS::TABSTOP,
+            resize_behavior: (gui::Horz::Resize, gui::Vert::Resize),
+            ..Default::default()
+        });
+
+        let btn_copy = gui::Button::new(&wnd, gui::ButtonOpts {
+            text: &loader.get("ui-copy"),
+            position: (380, 555),
+            width: 80,
+            height: 30,
+            resize_behavior: (gui::Horz::Repos, gui::Vert::Repos),
+            ..Default::default()
+        });
+
+        let btn_ok = gui::Button::new(&wnd, gui::ButtonOpts {
+            text: "OK",
+            position: (470, 555),
+            width: 80,
+            height: 30,
+            resize_behavior: (gui::Horz::Repos, gui::Vert::Repos),
+            ..Default::default()
+        });
+
+        let new_self = Self { wnd, txt_stats, btn_copy, btn_ok, text };
+        new_self.on_events();
+        new_self
+    }
+
+    fn on_events(&self) {
+        let text = self.text.clone();
+        self.btn_copy.on().bn_clicked(move || {
+            let _ = clipboard_win::set_clipboard_string(&text);
+            Ok(())
+        });
+
+        let wnd = self.wnd.clone();
+        self.btn_ok.on().bn_clicked(move || {
+            send_message_safe(wnd.hwnd(), winsafe::msg::wm::Close {});
+            Ok(())
+        });
+    }
+
+    pub fn show(&self, parent: &impl winsafe::prelude::GuiParent) -> winsafe::AnyResult<()> {
+        self.wnd.show_modal(parent)
+    }
+}
+
+// --- Time Range Window ---
+
+// From/to pickers (display zone) and quick presets
+#[derive(Clone)]
+struct TimeRangeWindow {
+    wnd:           gui::WindowModal,
+    #[allow(dead_code)]
+    lbl_from:      gui::Label,
+    #[allow(dead_code)]
+    lbl_to:        gui::Label,
+    dtp_from_date: gui::DateTimePicker,
+    dtp_from_time: gui::DateTimePicker,
+    dtp_to_date:   gui::DateTimePicker,
+    dtp_to_time:   gui::DateTimePicker,
+    btn_presets:   Vec<(gui::Button, TimeFilter)>,
+    btn_clear:     gui::Button,
+    btn_ok:        gui::Button,
+    btn_cancel:    gui::Button,
+    initial:       Option<TimeFilter>,
+    zone:          Zone, // Of the pickers (display zone)
+    result:        Arc<Mutex<Option<Option<TimeFilter>>>>, // None = cancelled, Some(None) = cleared
+}
+
+impl TimeRangeWindow {
+    pub fn new(initial: Option<TimeFilter>, zone: Zone) -> Self {
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+
+        let wnd = gui::WindowModal::new(gui::WindowModalOpts {
+            title: &clean_tr(&loader.get("ui-time-range-title")),
+            size: (360, 200),
+            style: co::WS::CAPTION | co::WS::SYSMENU | co::WS::VISIBLE,
+            ..Default::default()
+        });
+
+        let label = |key: &str, y: i32| gui::Label::new(&wnd, gui::LabelOpts {
+            text: &clean_tr(&loader.get(key)), position: (20, y + 4), size: (55, 20), ..Default::default()
+        });
+        // Dates can be unchecked (open-ended range), times follow their date
+        let date_picker = |y: i32| gui::DateTimePicker::new(&wnd, gui::DateTimePickerOpts {
+            position: (80, y), width: 130, control_style: co::DTS::SHOWNONE | co::DTS::SHORTDATECENTURYFORMAT, ..Default::default()
+        });
+        let time_picker = |y: i32| gui::DateTimePicker::new(&wnd, gui::DateTimePickerOpts {
+            position: (220, y), width: 110, control_style: co::DTS::TIMEFORMAT, ..Default::default()
+        });
+
+        let btn_presets = TimeFilter::PRESETS.iter().enumerate().map(|(i, &preset)| {
+            let btn = gui::Button::new(&wnd, gui::ButtonOpts {
+                text: &clean_tr(&loader.get(time_preset_key(preset))), position: (20 + 80 * i as i32, 90), width: 75, height: 26, ..Default::default()
+            });
+            (btn, preset)
+        }).collect();
+
+        let button = |text: &str, x: i32| gui::Button::new(&wnd, gui::ButtonOpts {
+            text, position: (x, 150), width: 80, height: 30, ..Default::default()
+        });
+
+        let new_self = Self {
+            lbl_from: label("ui-time-from", 16),
+            lbl_to: label("ui-time-to", 51),
+            dtp_from_date: date_picker(16),
+            dtp_from_time: time_picker(16),
+            dtp_to_date: date_picker(51),
+            dtp_to_time: time_picker(51),
+            btn_presets,
+            btn_clear: button(&clean_tr(&loader.get("ui-time-clear")), 20),
+            btn_ok: button("OK", 165),
+            btn_cancel: button(&clean_tr(&loader.get("ui-cancel")), 255),
+            wnd,
+            initial,
+            zone,
+            result: Arc::new(Mutex::new(None)),
+        };
+        new_self.on_events();
+        new_self
+    }
+
+    fn on_events(&self) {
+        let me = self.clone();
+        self.wnd.on().wm_create(move |_| {
+            // Fixed bounds are shown as they are; presets and "no filter" leave the dates unchecked
+            let range = match me.initial {
+                Some(TimeFilter::Between(range)) => range,
+                _ => TimeRange::default(),
+            };
+            let midnight = NaiveDate::default().and_hms_opt(0, 0, 0);
+            let end_of_day = NaiveDate::default().and_hms_opt(23, 59, 59);
+            set_picker(&me.dtp_from_date, range.from.map(|t| me.zone.to_wall_clock(t)));
+            set_picker(&me.dtp_from_time, range.from.map(|t| me.zone.to_wall_clock(t)).or(midnight));
+            set_picker(&me.dtp_to_date, range.to.map(|t| me.zone.to_wall_clock(t)));
+            set_picker(&me.dtp_to_time, range.to.map(|t| me.zone.to_wall_clock(t)).or(end_of_day));
+            Ok(0)
+        });
+
+        for (btn, preset) in &self.btn_presets {
+            let me = self.clone();
+            let preset = *preset;
+            btn.on().bn_clicked(move || {
+                me.close_with(Some(Some(preset)));
+                Ok(())
+            });
+        }
+
+        let me = self.clone();
+        self.btn_clear.on().bn_clicked(move || {
+            me.close_with(Some(None));
+            Ok(())
+        });
+
+        let me = self.clone();
+        self.btn_ok.on().bn_clicked(move || {
+            let from = picker_value(&me.dtp_from_date, &me.dtp_from_time).and_then(|t| me.zone.resolve(t));
+            let to = picker_value(&me.dtp_to_date, &me.dtp_to_time).and_then(|t| me.zone.resolve(t));
+            let range = match (from, to) {
+                (Some(f), Some(t)) if f > t => TimeRange { from: Some(t), to: Some(f) },
+                _ => TimeRange { from, to },
+            };
+            let filter = (range != TimeRange::default()).then_some(TimeFilter::Between(range));
+            me.close_with(Some(filter));
+            Ok(())
+        });
+
+        let me = self.clone();
+        self.btn_cancel.on().bn_clicked(move || {
+            me.close_with(None);
+            Ok(())
+        });
+    }
+
+    fn close_with(&self, result: Option<Option<TimeFilter>>) {
+        *self.result.lock().expect("Lock poisoned") = result;
+        send_message_safe(self.wnd.hwnd(), winsafe::msg::wm::Close {});
+    }
+
+    /// Shows the window; `None` if cancelled, `Some(None)` if the filter was cleared.
+    pub fn show(&self, parent: &impl winsafe::prelude::GuiParent) -> winsafe::AnyResult<Option<Option<TimeFilter>>> {
+        self.wnd.show_modal(parent)?;
+        Ok(self.result.lock().expect("Lock poisoned").take())
+    }
+}
+
+const fn time_preset_key(preset: TimeFilter) -> &'static str {
+    match preset {
+        TimeFilter::Last15Minutes => "ui-time-last-15-min",
+        TimeFilter::LastHour => "ui-time-last-hour",
+        TimeFilter::Today => "ui-time-today",
+        TimeFilter::Yesterday => "ui-time-yesterday",
+        TimeFilter::Between(_) => "ui-time-between",
+    }
+}
+
+// `None` unchecks the picker (DTS_SHOWNONE)
+fn set_picker(dtp: &gui::DateTimePicker, value: Option<NaiveDateTime>) {
+    match value {
+        Some(t) => {
+            let st = winsafe::SYSTEMTIME {
+                wYear: t.year() as u16,
+                wMonth: t.month() as u16,
+                wDayOfWeek: t.weekday().num_days_from_sunday() as u16,
+                wDay: t.day() as u16,
+                wHour: t.hour() as u16,
+                wMinute: t.minute() as u16,
+                wSecond: t.second() as u16,
+                wMilliseconds: 0,
+            };
+            let _ = dtp.set_date_time(&st);
+        }
+        None => {
+            let _ = send_message_safe(dtp.hwnd(), msg::dtm::SetSystemTime { system_time: None });
+        }
+    }
+}
+
+// Date of the first picker at the time of the second; `None` when the date is unchecked
+fn picker_value(date: &gui::DateTimePicker, time: &gui::DateTimePicker) -> Option<NaiveDateTime> {
+    let d = date.date_time().ok()?;
+    let t = time.date_time().ok()?;
+    NaiveDate::from_ymd_opt(d.wYear.into(), d.wMonth.into(), d.wDay.into())?
+        .and_hms_opt(t.wHour.into(), t.wMinute.into(), t.wSecond.into())
+}
+
+// --- Preset Name Window ---
+
+#[derive(Clone)]
+struct PresetNameWindow {
+    wnd:        gui::WindowModal,
+    #[allow(dead_code)]
+    lbl_name:   gui::Label,
+    txt_name:   gui::Edit,
+    btn_ok:     gui::Button,
+    btn_cancel: gui::Button,
+    result:     Arc<Mutex<Option<String>>>,
+}
+
+impl PresetNameWindow {
+    pub fn new(suggested: &str) -> Self {
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+
+        let wnd = gui::WindowModal::new(gui::WindowModalOpts {
+            title: &clean_tr(&loader.get("ui-preset-save-title")),
+            size: (360, 120),
+            style: co::WS::CAPTION | co::WS::SYSMENU | co::WS::VISIBLE,
+            ..Default::default()
+        });
+
+        let button = |text: &str, x: i32| gui::Button::new(&wnd, gui::ButtonOpts {
+            text, position: (x, 70), width: 80, height: 30, ..Default::default()
+        });
+
+        let new_self = Self {
+            lbl_name: gui::Label::new(&wnd, gui::LabelOpts {
+                text: &clean_tr(&loader.get("ui-preset-name")), position: (20, 24), size: (55, 20), ..Default::default()
+            }),
+            txt_name: gui::Edit::new(&wnd, gui::EditOpts {
+                text: suggested, position: (80, 20), width: 260, height: 22, ..Default::default()
+            }),
+            btn_ok: button("OK", 170),
+            btn_cancel: button(&clean_tr(&loader.get("ui-cancel")), 260),
+            wnd,
+            result: Arc::new(Mutex::new(None)),
+        };
+        new_self.on_events();
+        new_self
+    }
+
+    fn on_events(&self) {
+        let me = self.clone();
+        self.btn_ok.on().bn_clicked(move || {
+            let name = me.txt_name.text().unwrap_or_default().trim().to_string();
+            if !name.is_empty() {
+                *me.result.lock().expect("Lock poisoned") = Some(name);
+                send_message_safe(me.wnd.hwnd(), winsafe::msg::wm::Close {});
+            }
+            Ok(())
+        });
+
+        let me = self.clone();
+        self.btn_cancel.on().bn_clicked(move || {
+            send_message_safe(me.wnd.hwnd(), winsafe::msg::wm::Close {});
+            Ok(())
+        });
+    }
+
+    /// Shows the window; `None` if cancelled.
+    pub fn show(&self, parent: &impl winsafe::prelude::GuiParent) -> winsafe::AnyResult<Option<String>> {
+        self.wnd.show_modal(parent)?;
+        Ok(self.result.lock().expect("Lock poisoned").take())
+    }
+}
+
+// --- Column Filter Window ---
+
+// Most frequent values offered in the pick-list
+const MAX_PICK_VALUES: usize = 1000;
+
+// Text filter + pick-list of the distinct values (with their row counts) of one column
+#[derive(Clone)]
+struct ColumnFilterWindow {
+    wnd:          gui::WindowModal,
+    #[allow(dead_code)]
+    lbl_contains: gui::Label,
+    txt_contains: gui::Edit,
+    lst_values:   gui::ListView,
+    btn_clear:    gui::Button,
+    btn_ok:       gui::Button,
+    btn_cancel:   gui::Button,
+    values:       Arc<Vec<(String, usize)>>,
+    initial:      Option<ColumnFilter>,
+    result:       Arc<Mutex<Option<Option<ColumnFilter>>>>, // None = cancelled, Some(None) = cleared
+}
+
+impl ColumnFilterWindow {
+    pub fn new(title: &str, values: Vec<(String, usize)>, initial: Option<ColumnFilter>) -> Self {
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+
+        let wnd = gui::WindowModal::new(gui::WindowModalOpts {
+            title,
+            size: (420, 440),
+            style: co::WS::CAPTION | co::WS::SYSMENU | co::WS::VISIBLE,
+            ..Default::default()
+        });
+
+        let contains = match &initial {
+            Some(ColumnFilter::Contains(text)) => text.clone(),
+            _ => String::new(),
+        };
+
+        let button = |text: &str, x: i32| gui::Button::new(&wnd, gui::ButtonOpts {
+            text, position: (x, 395), width: 80, height: 30, ..Default::default()
+        });
+
+        let new_self = Self {
+            lbl_contains: gui::Label::new(&wnd, gui::LabelOpts {
+                text: &clean_tr(&loader.get("ui-column-filter-contains")), position: (20, 19), size: (75, 20), ..Default::default()
+            }),
+            txt_contains: gui::Edit::new(&wnd, gui::EditOpts {
+                text: &contains, position: (100, 15), width: 300, height: 22, ..Default::default()
+            }),
+            lst_values: gui::ListView::new(&wnd, gui::ListViewOpts {
+                position: (20, 50),
+                size: (380, 330),
+                control_style: co::LVS::REPORT | co::LVS::NOSORTHEADER | co::LVS::SHOWSELALWAYS,
+                control_ex_style: co::LVS_EX::CHECKBOXES | co::LVS_EX::FULLROWSELECT,
+                ..Default::default()
+            }),
+            btn_clear: button(&clean_tr(&loader.get("ui-column-filter-clear")), 20),
+            btn_ok: button("OK", 230),
+            btn_cancel: button(&clean_tr(&loader.get("ui-cancel")), 320),
+            wnd,
+            values: Arc::new(values),
+            initial,
+            result: Arc::new(Mutex::new(None)),
+        };
+        new_self.on_events();
+        new_self
+    }
+
+    fn on_events(&self) {
+        let me = self.clone();
+        self.wnd.on().wm_create(move |_| {
+            let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+            me.lst_values.cols().add(&clean_tr(&loader.get("ui-column-filter-value")), 270)?;
+            me.lst_values.cols().add(&clean_tr(&loader.get("ui-column-filter-rows")), 80)?;
+
+            let checked: &[String] = match &me.initial {
+                Some(ColumnFilter::OneOf(values)) => values,
+                _ => &[],
+            };
+            let empty = clean_tr(&loader.get("ui-column-filter-empty"));
+            for (value, count) in me.values.iter() {
+                let shown = if value.is_empty() { empty.as_str() } else { value.as_str() };
+                let item = me.lst_values.items().add(&[shown, &count.to_string()], None)?;
+                if checked.contains(value) {
+                    item.check(true);
+                }
+            }
+            Ok(0)
+        });
+
+        let me = self.clone();
+        self.btn_clear.on().bn_clicked(move || {
+            me.close_with(Some(None));
+            Ok(())
+        });
+
+        let me = self.clone();
+        self.btn_ok.on().bn_clicked(move || {
+            // Ticked values win over the text
+            let picked: Vec<String> = me.values.iter().enumerate()
+                .filter(|(i, _)| me.lst_values.items().get(*i as u32).is_checked())
+                .map(|(_, (value, _))| value.clone())
+                .collect();
+            let text = me.txt_contains.text().unwrap_or_default().trim().to_string();
+            let filter = if !picked.is_empty() {
+                Some(ColumnFilter::OneOf(picked))
+            } else if !text.is_empty() {
+                Some(ColumnFilter::Contains(text))
+            } else {
+                None
+            };
+            me.close_with(Some(filter));
+            Ok(())
+        });
+
+        let me = self.clone();
+        self.btn_cancel.on().bn_clicked(move || {
+            me.close_with(None);
+            Ok(())
+        });
+    }
+
+    fn close_with(&self, result: Option<Option<ColumnFilter>>) {
+        *self.result.lock().expect("Lock poisoned") = result;
+        send_message_safe(self.wnd.hwnd(), winsafe::msg::wm::Close {});
+    }
+
+    /// Shows the window; `None` if cancelled, `Some(None)` if the filter was cleared.
+    pub fn show(&self, parent: &impl winsafe::prelude::GuiParent) -> winsafe::AnyResult<Option<Option<ColumnFilter>>> {
+        self.wnd.show_modal(parent)?;
+        Ok(self.result.lock().expect("Lock poisoned").take())
+    }
+}
+
+// --- Findings Window ---
+
+// Password sprays and brute forces found in the loaded logs; the chosen one is shown in the list
+#[derive(Clone)]
+struct FindingsWindow {
+    wnd:          gui::WindowModal,
+    lst_findings: gui::ListView,
+    btn_show:     gui::Button,
+    btn_close:    gui::Button,
+    rows:         Arc<Vec<[String; 6]>>,
+    result:       Arc<Mutex<Option<usize>>>,
+}
+
+impl FindingsWindow {
+    pub fn new(title: &str, rows: Vec<[String; 6]>) -> Self {
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+
+        let wnd = gui::WindowModal::new(gui::WindowModalOpts {
+            title,
+            size: (760, 420),
+            style: co::WS::CAPTION | co::WS::SYSMENU | co::WS::VISIBLE | co::WS::SIZEBOX,
+            ..Default::default()
+        });
+
+        let button = |text: &str, x: i32| gui::Button::new(&wnd, gui::ButtonOpts {
+            text, position: (x, 375), width: 110, height: 30,
+            resize_behavior: (gui::Horz::Repos, gui::Vert::Repos), ..Default::default()
+        });
+
+        let new_self = Self {
+            lst_findings: gui::ListView::new(&wnd, gui::ListViewOpts {
+                position: (10, 10),
+                size: (740, 355),
+                control_style: co::LVS::REPORT | co::LVS::NOSORTHEADER | co::LVS::SHOWSELALWAYS | co::LVS::SINGLESEL,
+                control_ex_style: co::LVS_EX::FULLROWSELECT,
+                resize_behavior: (gui::Horz::Resize, gui::Vert::Resize),
+                ..Default::default()
+            }),
+            btn_show: button(&clean_tr(&loader.get("ui-findings-show")), 520),
+            btn_close: button(&clean_tr(&loader.get("ui-findings-close")), 640),
+            wnd,
+            rows: Arc::new(rows),
+            result: Arc::new(Mutex::new(None)),
+        };
+        new_self.on_events();
+        new_self
+    }
+
+    fn on_events(&self) {
+        let me = self.clone();
+        self.wnd.on().wm_create(move |_| {
+            let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+            let headers = [
+                ("ui-findings-start", 150), ("ui-findings-attack", 130), ("ui-findings-source", 110),
+                ("ui-findings-value", 180), ("ui-findings-users", 70), ("ui-findings-failures", 70),
+            ];
+            for (key, width) in headers {
+                me.lst_findings.cols().add(&clean_tr(&loader.get(key)), width)?;
+            }
+            for row in me.rows.iter() {
+                let cells: Vec<&str> = row.iter().map(String::as_str).collect();
+                me.lst_findings.items().add(&cells, None)?;
             }
             Ok(0)
         });
 
+        let me = self.clone();
+        self.btn_show.on().bn_clicked(move || {
+            me.close_with_focused();
+            Ok(())
+        });
+
+        let me = self.clone();
+        self.lst_findings.on().nm_dbl_clk(move |_| {
+            me.close_with_focused();
+            Ok(())
+        });
+
         let wnd = self.wnd.clone();
-        self.btn_ok.on().bn_clicked(move || {
+        self.btn_close.on().bn_clicked(move || {
             send_message_safe(wnd.hwnd(), winsafe::msg::wm::Close {});
             Ok(())
         });
     }
 
-    pub fn show(&self, parent: &impl winsafe::prelude::GuiParent) -> winsafe::AnyResult<()> {
-        self.wnd.show_modal(parent)
+    fn close_with_focused(&self) {
+        let Some(item) = self.lst_findings.items().focused() else { return };
+        *self.result.lock().expect("Lock poisoned") = Some(item.index() as usize);
+        send_message_safe(self.wnd.hwnd(), winsafe::msg::wm::Close {});
+    }
+
+    /// Shows the window; the index of the finding to show, `None` if closed.
+    pub fn show(&self, parent: &impl winsafe::prelude::GuiParent) -> winsafe::AnyResult<Option<usize>> {
+        self.wnd.show_modal(parent)?;
+        Ok(self.result.lock().expect("Lock poisoned").take())
     }
 }
 
@@ -388,6 +958,7 @@ impl AboutWindow {
 struct MyWindow {
     wnd:          gui::WindowMain,
     lst_logs:     gui::ListView,
+    chart:        gui::WindowControl, // Requests per interval of the view; dragging sets the time range
     txt_search:   gui::Edit,
     btn_open:     gui::Button,
     btn_open_folder: gui::Button,
@@ -395,23 +966,40 @@ struct MyWindow {
     btn_prev_err: gui::Button, // Error navigation
     btn_next_err: gui::Button, // Error navigation
     btn_about:    gui::Button,
+    btn_export:   gui::Button,
     cb_append:    gui::CheckBox,
+    cb_regex:     gui::CheckBox, // Search box text is one regular expression
+    cmb_presets:  gui::ComboBox,  // Saved filter presets + their actions
+    cmb_error_category: gui::ComboBox, // Failure category of "Errors" and the error navigation
     status_bar:   gui::StatusBar,
     progress_bar: gui::ProgressBar,
     
     all_items:    Arc<RwLock<Vec<RadiusRequest>>>,
-    raw_count:    Arc<RwLock<usize>>,
-    filtered_ids: Arc<RwLock<Vec<usize>>>,
+    items_generation: Arc<AtomicU64>, // Version of all_items, bumped under its write lock (see SearchIndex)
+    rows_loaded:  Arc<AtomicU64>, // Bumped with items_generation when a load replaces or extends all_items (not by in-place rewrites)
+    search_index: Arc<RwLock<SearchIndex>>, // Value index of all_items (locked after it)
+    filter_worker: Arc<OnceLock<FilterWorker>>, // Only writer of filtered_ids, started with the window
+    load_seq:     Arc<AtomicU64>, // Filter job whose completion ends a load (0 = none)
+    parse_report: Arc<RwLock<ParseReport>>, // Raw / recovered / skipped events of the loaded files
+    filtered_ids: Arc<RwLock<Vec<usize>>>, // Locked after all_items and search_index, never before
+    last_query:   Arc<RwLock<Query>>, // Criteria of the last submitted pass, kept while the search box is invalid
+    series:       Arc<RwLock<Option<TimeSeries>>>, // Chart data, computed by the filter worker after each pass
+    chart_drag:   Arc<Mutex<Option<(usize, usize)>>>, // Buckets (anchor, current) being selected on the chart
     show_errors:  Arc<RwLock<bool>>,
-    sort_col:     Arc<RwLock<LogColumn>>,
-    sort_desc:    Arc<RwLock<bool>>,
+    error_category: Arc<RwLock<Option<ReasonCategory>>>, // None = every failure
+    sort:         Arc<RwLock<SortSpec>>,
+    time_window:  Arc<RwLock<Option<TimeRange>>>, // "Time window" context around a request
+    time_filter:  Arc<RwLock<Option<TimeFilter>>>, // Time range of the view (also skips files when loading a folder)
     visible_cols: Arc<RwLock<Vec<LogColumn>>>,
+    column_filters: Arc<RwLock<ColumnFilters>>, // Header filters, saved in the config
+    active_preset: Arc<RwLock<Option<String>>>, // Last applied preset (target of "Delete")
     config:       Arc<RwLock<AppConfig>>,
     is_busy:      Arc<AtomicBool>,
     
     // For Tail mode
     current_file_path: Arc<Mutex<Option<String>>>,
-    last_file_size:    Arc<Mutex<u64>>,
+    current_folder:    Arc<Mutex<Option<String>>>, // Folder shown alone (reloaded when the time range grows)
+    tail:              Arc<Mutex<Option<(LogTail, u64)>>>, // Offset + session map of the followed file, and the rows_loaded it indexes into
     watcher:           Arc<Mutex<Option<notify::RecommendedWatcher>>>,
 }
 
@@ -452,14 +1040,20 @@ impl MyWindow {
         let new_self = Self {
             wnd: wnd.clone(),
             lst_logs:     gui::ListView::new(&wnd, gui::ListViewOpts {
-                position: (10, 50),
-                size: (config.window_width - 20, config.window_height - 90),
+                position: (10, 55 + CHART_HEIGHT),
+                size: (config.window_width - 20, config.window_height - 95 - CHART_HEIGHT),
                 control_style: co::LVS::REPORT | co::LVS::SHOWSELALWAYS | co::LVS::OWNERDATA,
                 resize_behavior: (gui::Horz::Resize, gui::Vert::Resize),
                 ..Default::default()
             }),
+            chart:        gui::WindowControl::new(&wnd, gui::WindowControlOpts {
+                position: (10, 50),
+                size: (config.window_width - 20, CHART_HEIGHT),
+                resize_behavior: (gui::Horz::Resize, gui::Vert::None),
+                ..Default::default()
+            }),
             txt_search:   gui::Edit::new(&wnd, gui::EditOpts {
-                position: (510, 14), width: 150, height: 22, ..Default::default()
+                position: (510, 14), width: 110, height: 22, ..Default::default()
             }),
             btn_open:     gui::Button::new(&wnd, gui::ButtonOpts {
                 text: &loader.get("ui-open-log"), position: (10, 10), width: 110, height: 30, ..Default::default()
@@ -483,12 +1077,24 @@ impl MyWindow {
                 ..Default::default()
             }),
             btn_about: gui::Button::new(&wnd, gui::ButtonOpts {
-                text: &loader.get("about_title"), position: (config.window_width - 130, 10), width: 120, height: 30,
+                text: &loader.get("about_title"), position: (config.window_width - 90, 10), width: 80, height: 30,
                 resize_behavior: (gui::Horz::Repos, gui::Vert::None), ..Default::default()
             }),
+            btn_export:   gui::Button::new(&wnd, gui::ButtonOpts {
+                text: &loader.get("ui-export"), position: (690, 10), width: 85, height: 30, ..Default::default()
+            }),
             cb_append:    gui::CheckBox::new(&wnd, gui::CheckBoxOpts {
                 text: &loader.get("ui-append"), position: (780, 14), size: (80, 20), ..Default::default()
             }),
+            cb_regex:     gui::CheckBox::new(&wnd, gui::CheckBoxOpts {
+                text: &loader.get("ui-regex"), position: (625, 14), size: (60, 20), ..Default::default()
+            }),
+            cmb_presets:  gui::ComboBox::new(&wnd, gui::ComboBoxOpts {
+                position: (865, 13), width: 140, ..Default::default()
+            }),
+            cmb_error_category: gui::ComboBox::new(&wnd, gui::ComboBoxOpts {
+                position: (1010, 13), width: 140, ..Default::default()
+            }),
             status_bar:   gui::StatusBar::new(&wnd, &[
                 gui::SbPart::Proportional(1),
             ]),
@@ -503,16 +1109,29 @@ impl MyWindow {
                 },
             ),
             all_items:    Arc::new(RwLock::new(Vec::new())),
-            raw_count:    Arc::new(RwLock::new(0)),
+            items_generation: Arc::new(AtomicU64::new(0)),
+            rows_loaded:  Arc::new(AtomicU64::new(0)),
+            search_index: Arc::new(RwLock::new(SearchIndex::default())),
+            filter_worker: Arc::new(OnceLock::new()),
+            load_seq:     Arc::new(AtomicU64::new(0)),
+            parse_report: Arc::new(RwLock::new(ParseReport::default())),
             filtered_ids: Arc::new(RwLock::new(Vec::new())),
+            last_query:   Arc::new(RwLock::new(Query::default())),
+            series:       Arc::new(RwLock::new(None)),
+            chart_drag:   Arc::new(Mutex::new(None)),
             show_errors:  Arc::new(RwLock::new(false)),
-            sort_col:     Arc::new(RwLock::new(LogColumn::Timestamp)),
-            sort_desc:    Arc::new(RwLock::new(true)),
+            error_category: Arc::new(RwLock::new(config.error_category)),
+            sort:         Arc::new(RwLock::new(config.sort.clone())),
+            time_window:  Arc::new(RwLock::new(None)),
+            time_filter:  Arc::new(RwLock::new(None)),
             visible_cols: Arc::new(RwLock::new(config.visible_columns.clone())),
+            column_filters: Arc::new(RwLock::new(config.column_filters.clone())),
+            active_preset: Arc::new(RwLock::new(None)),
             config:       Arc::new(RwLock::new(config)),
             is_busy:      Arc::new(AtomicBool::new(false)),
             current_file_path: Arc::new(Mutex::new(None)),
-            last_file_size:    Arc::new(Mutex::new(0)),
+            current_folder: Arc::new(Mutex::new(None)),
+            tail:              Arc::new(Mutex::new(None)),
             watcher:           Arc::new(Mutex::new(None)),
         };
 
@@ -559,6 +1178,23 @@ impl MyWindow {
             me.progress_bar.hwnd().ShowWindow(co::SW::HIDE);
             
             me.refresh_columns();
+            me.fill_presets();
+            me.fill_error_categories();
+
+            // Filter results are announced as WM_FILTER_DONE (wparam = job number)
+            let safe_hwnd = SafeHWND::from_hwnd(me.wnd.hwnd());
+            let (items_bg, ids_bg, series_bg) = (me.all_items.clone(), me.filtered_ids.clone(), me.series.clone());
+            let worker = FilterWorker::spawn(me.all_items.clone(), me.items_generation.clone(), me.search_index.clone(), me.filtered_ids.clone(), move |seq| {
+                // The chart of the new view is computed here, off the UI thread (items, then ids: the worker's lock order)
+                let series = {
+                    let items = items_bg.read().expect("Lock failed");
+                    let ids = ids_bg.read().expect("Lock failed");
+                    TimeSeries::compute(&items, &ids, None)
+                };
+                *series_bg.write().expect("Lock failed") = series;
+                safe_hwnd.post(WM_FILTER_DONE, seq as usize, 0);
+            });
+            let _ = me.filter_worker.set(worker);
             Ok(0)
         });
 
@@ -598,21 +1234,17 @@ impl MyWindow {
 
         // Handle loading completion
         let me = self.clone();
-        self.wnd.on().wm(WM_LOAD_DONE, move |_| {
-            let count = me.filtered_ids.read().expect("Lock failed").len();
-            let raw = *me.raw_count.read().expect("Lock failed");
-            me.lst_logs.items().set_count(count as u32, None).expect("Set count failed");
-
-            let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
-            let mut args = HashMap::new();
-            args.insert("count", count.to_string());
-            args.insert("raw", raw.to_string());
-
-            let msg = loader.get_args("ui-status-display", args);
-            let _ = me.status_bar.parts().get(0).set_text(&clean_tr(&msg));
-            me.lst_logs.hwnd().InvalidateRect(None, true).expect("Invalidate rect failed");
-
-
+        self.wnd.on().wm(WM_LOAD_DONE, move |p| {
+            // New rows: filter them with the current criteria, the status follows (WM_FILTER_DONE).
+            // An invalid search keeps the previous criteria instead of showing every row
+            if p.wparam == 0 {
+                let query = me.current_query().unwrap_or_else(|| me.last_query.read().expect("Lock failed").clone());
+                if let Some(seq) = me.submit_filter(query) {
+                    me.load_seq.store(seq, Ordering::SeqCst);
+                }
+                return Ok(0);
+            }
+            me.show_load_status();
             Ok(0)
         });
 
@@ -630,11 +1262,37 @@ impl MyWindow {
 
         // Handle filter completion (Search)
         let me = self.clone();
-        self.wnd.on().wm(WM_FILTER_DONE, move |_| {
+        self.wnd.on().wm(WM_FILTER_DONE, move |p| {
             if let Ok(ids) = me.filtered_ids.read() {
                 me.lst_logs.items().set_count(ids.len() as u32, None).expect("Set count failed");
             }
             me.lst_logs.hwnd().InvalidateRect(None, true).expect("Invalidate rect failed");
+            // Series already computed by the filter worker
+            let _ = me.chart.hwnd().InvalidateRect(None, true);
+
+            // First result after a load (or a later one that superseded it)
+            let pending = me.load_seq.load(Ordering::SeqCst);
+            if pending != 0 && p.wparam as u64 >= pending {
+                me.load_seq.store(0, Ordering::SeqCst);
+                me.show_load_status();
+                // The search box error stays visible (the view kept the previous query)
+                let _ = me.current_query();
+            }
+            Ok(0)
+        });
+
+        // Handle export completion
+        let me = self.clone();
+        self.wnd.on().wm(WM_EXPORT_DONE, move |p| {
+            let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+            let msg = if p.lparam != 0 {
+                loader.get("ui-status-export-error")
+            } else {
+                let mut args = HashMap::new();
+                args.insert("count", p.wparam.to_string());
+                loader.get_args("ui-status-exported", args)
+            };
+            let _ = me.status_bar.parts().get(0).set_text(&clean_tr(&msg));
             Ok(0)
         });
 
@@ -694,8 +1352,9 @@ impl MyWindow {
             } else if v == co::VK::F5 {
                 if let Ok(guard) = me.current_file_path.lock() {
                     if guard.is_some() {
-                        if let Ok(mut size_guard) = me.last_file_size.lock() {
-                            *size_guard = 0;
+                        // Forget the tail state: forces a full reload
+                        if let Ok(mut tail_guard) = me.tail.lock() {
+                            *tail_guard = None;
                         }
                         me.handle_file_change();
                     }
@@ -756,6 +1415,8 @@ impl MyWindow {
             let is_header = h_header_opt.is_some_and(|h| p.hwnd == *h);
             if p.hwnd == *me.lst_logs.hwnd() || is_header {
                 me.on_lst_context_menu(p.cursor_pos, p.hwnd)?;
+            } else if p.hwnd == *me.chart.hwnd() {
+                me.on_chart_context_menu(p.cursor_pos)?;
             }
             Ok(())
         }});
@@ -769,6 +1430,13 @@ impl MyWindow {
             let _ = me.wnd.hwnd().SetTimer(IDT_SEARCH_TIMER, 300, None);
             Ok(())
         }});
+        self.cb_regex.on().bn_clicked({ let me = self.clone(); move || {
+            me.trigger_async_filter();
+            Ok(())
+        }});
+
+        self.cmb_presets.on().cbn_sel_change({ let me = self.clone(); move || me.on_preset_selected() });
+        self.cmb_error_category.on().cbn_sel_change({ let me = self.clone(); move || { me.on_error_category_selected(); Ok(()) } });
 
         // --- Tooltips ---
         self.lst_logs.on().lvn_get_info_tip({
@@ -777,22 +1445,14 @@ impl MyWindow {
                 let item_idx = p.iItem;
                 let subitem_idx = p.iSubItem as usize;
 
-                if let Ok(Some(idx)) = me.filtered_ids.read().map(|ids| ids.get(item_idx as usize).copied()) {
+                // The ids guard is released before the items are locked (lock order of the filter worker)
+                let idx = me.filtered_ids.read().ok().and_then(|ids| ids.get(item_idx as usize).copied());
+                if let Some(idx) = idx {
                     if let Ok(items) = me.all_items.read() {
                         if let Some(req) = items.get(idx) {
-                            let _text = match subitem_idx {
-                                0 => req.timestamp.clone(),
-                                1 => req.req_type.clone(),
-                                2 => req.server.clone(),
-                                3 => req.ap_ip.clone(),
-                                4 => req.ap_name.clone(),
-                                5 => req.mac.clone(),
-                                6 => req.user.clone(),
-                                7 => req.resp_type.clone(),
-                                8 => req.reason.clone(),
-                                9 => req.session_id.clone(),
-                                _ => String::new(),
-                            };
+                            let _text = LogColumn::all().get(subitem_idx)
+                                .map(|&col| req.column_text(col).to_string())
+                                .unwrap_or_default();
                             // copy info tip text to p.pszText
                             // p.pszText is LPWSTR (mut pointer to buffer of cchTextMax chars)
                             // Winsafe NMLVGETINFOTIP likely exposes it.
@@ -823,10 +1483,316 @@ impl MyWindow {
 
         self.btn_rejects.on().bn_clicked({ let me = self.clone(); move || me.on_btn_rejects_clicked() });
         self.btn_about.on().bn_clicked({ let me = self.clone(); move || me.on_btn_about_clicked() });
+        self.btn_export.on().bn_clicked({ let me = self.clone(); move || me.on_btn_export_clicked() });
         self.lst_logs.on().nm_custom_draw({ let me = self.clone(); move |p| Ok(me.on_lst_nm_custom_draw(p)) });
+
+        // --- Time series chart: drag over buckets to set the time range ---
+        self.chart.on().wm_paint({ let me = self.clone(); move || me.paint_chart() });
+        self.chart.on().wm_l_button_down({ let me = self.clone(); move |p| {
+            *me.chart_drag.lock().expect("Lock failed") = me.chart_bucket_at(p.coords.x).map(|bucket| (bucket, bucket));
+            let _ = me.chart.hwnd().InvalidateRect(None, false);
+            Ok(())
+        }});
+        self.chart.on().wm_mouse_move({ let me = self.clone(); move |p| {
+            let mut drag = me.chart_drag.lock().expect("Lock failed");
+            let Some((anchor, _)) = *drag else { return Ok(()) };
+            // Released outside of the chart: its WM_LBUTTONUP went elsewhere
+            *drag = if p.vkey_code.has(co::MK::LBUTTON) {
+                Some((anchor, me.chart_bucket_at(p.coords.x).unwrap_or(anchor)))
+            } else {
+                None
+            };
+            drop(drag);
+            let _ = me.chart.hwnd().InvalidateRect(None, false);
+            Ok(())
+        }});
+        self.chart.on().wm_l_button_up({ let me = self.clone(); move |_| {
+            let drag = me.chart_drag.lock().expect("Lock failed").take();
+            let range = drag.and_then(|(anchor, current)| {
+                me.series.read().expect("Lock failed").as_ref().map(|series| series.range(anchor, current))
+            });
+            if let Some(range) = range {
+                me.set_time_filter(Some(TimeFilter::Between(range)));
+            }
+            let _ = me.chart.hwnd().InvalidateRect(None, false);
+            Ok(())
+        }});
     }
 
     // --- Async Filtering Logic ---
+    /// Writes the current view (filter, sort, visible columns) to an .xlsx file, in a background thread.
+    fn on_btn_export_clicked(&self) -> winsafe::AnyResult<()> {
+        let file_dialog = winsafe::CoCreateInstance::<winsafe::IFileSaveDialog>(
+            &co::CLSID::FileSaveDialog, None::<&winsafe::IUnknown>, co::CLSCTX::INPROC_SERVER,
+        )?;
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        file_dialog.SetFileTypes(&[(loader.get("ui-file-xlsx"), "*.xlsx".to_owned())])?;
+        file_dialog.SetDefaultExtension("xlsx")?;
+        file_dialog.SetFileName("radius-log.xlsx")?;
+
+        if !file_dialog.Show(self.wnd.hwnd())? {
+            return Ok(());
+        }
+        let path = file_dialog.GetResult()?.GetDisplayName(co::SIGDN::FILESYSPATH)?;
+
+        // Snapshot of the view: the list may be refiltered while the file is written
+        let ids = self.filtered_ids.read().expect("Lock failed").clone();
+        let visible = self.visible_cols.read().expect("Lock failed").clone();
+        let (columns, time_settings) = {
+            let config = self.config.read().expect("Lock failed");
+            let all_cols = LogColumn::all();
+            let columns: Vec<ExportColumn> = visible.iter().map(|&col| {
+                let col_idx = all_cols.iter().position(|&c| c == col).unwrap_or(0);
+                let width = config.column_widths.get(col_idx).copied().filter(|&w| w > 0).unwrap_or(150);
+                ExportColumn { column: col, header: clean_tr(&loader.get(col.ftl_key())), width_px: width as u32 }
+            }).collect();
+            (columns, config.time.clone())
+        };
+
+        let _ = self.status_bar.parts().get(0).set_text(&loader.get("ui-status-exporting"));
+
+        let all_items_bg = self.all_items.clone();
+        let safe_hwnd = SafeHWND::from_hwnd(self.wnd.hwnd());
+        std::thread::spawn(move || {
+            let result = {
+                let items = all_items_bg.read().expect("Lock failed");
+                // Every attribute of the exported rows, after the visible columns
+                let attributes = attribute_names(&items, &ids);
+                export_xlsx(&path, &items, &ids, &columns, &attributes, &time_settings)
+            };
+            match result {
+                Ok(()) => safe_hwnd.post(WM_EXPORT_DONE, ids.len(), 0),
+                Err(e) => {
+                    eprintln!("Export failed: {e:#}");
+                    safe_hwnd.post(WM_EXPORT_DONE, 0, 1);
+                }
+            }
+        });
+        Ok(())
+    }
+
+    // --- Failure Categories ---
+    // Dropdown: every failure, then one entry per failure category
+    fn fill_error_categories(&self) {
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        self.cmb_error_category.items().delete_all();
+        self.cmb_error_category.items().add(&[clean_tr(&loader.get("ui-errors-all-categories"))]);
+        self.cmb_error_category.items().add(&ReasonCategory::FAILURES.map(|cat| clean_tr(&loader.get(cat.ftl_key()))));
+        self.set_error_category(*self.error_category.read().expect("Lock failed"));
+    }
+
+    fn set_error_category(&self, category: Option<ReasonCategory>) {
+        *self.error_category.write().expect("Lock failed") = category;
+        let index = category.and_then(|cat| ReasonCategory::FAILURES.iter().position(|&c| c == cat)).map_or(0, |i| i + 1);
+        self.cmb_error_category.items().select(Some(index as u32));
+    }
+
+    fn on_error_category_selected(&self) {
+        let Some(index) = self.cmb_error_category.items().selected_index() else { return };
+        let category = (index as usize).checked_sub(1).and_then(|i| ReasonCategory::FAILURES.get(i).copied());
+        *self.error_category.write().expect("Lock failed") = category;
+        if let Ok(mut config) = self.config.write() {
+            config.error_category = category;
+            let _ = config.save();
+        }
+        if *self.show_errors.read().expect("Lock failed") {
+            self.trigger_async_filter();
+        }
+    }
+
+    // Target of "Errors" and of the error navigation
+    fn error_filter(&self) -> ErrorFilter {
+        ErrorFilter {
+            category: *self.error_category.read().expect("Lock failed"),
+            categories: self.config.read().expect("Lock failed").reason_categories.clone(),
+        }
+    }
+
+    // --- Filter Presets ---
+    // Dropdown: title, preset names, then the actions
+    fn fill_presets(&self) {
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        let names: Vec<String> = self.config.read().expect("Lock failed").filter_presets.iter().map(|p| p.name.clone()).collect();
+        self.cmb_presets.items().delete_all();
+        self.cmb_presets.items().add(&[clean_tr(&loader.get("ui-presets"))]);
+        self.cmb_presets.items().add(&names);
+        self.cmb_presets.items().add(&PRESET_ACTIONS.map(|key| clean_tr(&loader.get(key))));
+        self.cmb_presets.items().select(Some(0));
+    }
+
+    fn on_preset_selected(&self) -> winsafe::AnyResult<()> {
+        let Some(index) = self.cmb_presets.items().selected_index() else { return Ok(()) };
+        // Back to the title: the dropdown is a menu, not a state
+        self.cmb_presets.items().select(Some(0));
+        let presets = self.config.read().expect("Lock failed").filter_presets.clone();
+        let index = index as usize;
+        if index == 0 {
+            return Ok(());
+        }
+        if let Some(preset) = presets.get(index - 1) {
+            self.apply_preset(preset);
+            return Ok(());
+        }
+        match PRESET_ACTIONS.get(index - 1 - presets.len()).copied() {
+            Some("ui-preset-save") => self.save_current_preset()?,
+            Some("ui-preset-delete") => self.delete_active_preset()?,
+            Some("ui-preset-import") => self.import_presets()?,
+            Some("ui-preset-export") => self.export_presets()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn apply_preset(&self, preset: &FilterPreset) {
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        *self.show_errors.write().expect("Lock failed") = preset.errors_only;
+        self.set_error_category(preset.error_category);
+        let txt = if preset.errors_only { loader.get("ui-btn-show-all") } else { loader.get("ui-btn-errors-only") };
+        let _ = self.btn_rejects.hwnd().SetWindowText(&txt);
+        *self.sort.write().expect("Lock failed") = preset.sort.clone();
+        *self.column_filters.write().expect("Lock failed") = preset.column_filters.clone();
+        *self.active_preset.write().expect("Lock failed") = Some(preset.name.clone());
+        if let Ok(mut config) = self.config.write() {
+            config.column_filters = preset.column_filters.clone();
+            config.sort = preset.sort.clone();
+            if !preset.visible_columns.is_empty() {
+                config.visible_columns.clone_from(&preset.visible_columns);
+            }
+            let _ = config.save();
+        }
+        if !preset.visible_columns.is_empty() {
+            *self.visible_cols.write().expect("Lock failed") = preset.visible_columns.clone();
+        }
+        self.refresh_columns();
+        self.cb_regex.set_check(preset.regex);
+        // Changing the text arms the search timer: filter right away instead
+        self.txt_search.set_text(&preset.query);
+        let _ = self.wnd.hwnd().KillTimer(IDT_SEARCH_TIMER);
+        self.trigger_async_filter();
+
+        let mut args = HashMap::new();
+        args.insert("name", preset.name.clone());
+        let _ = self.status_bar.parts().get(0).set_text(&clean_tr(&loader.get_args("ui-status-preset-applied", args)));
+    }
+
+    // Current search, errors-only mode, sort and columns under a name (replaces a preset of the same name)
+    fn save_current_preset(&self) -> winsafe::AnyResult<()> {
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        let suggested = self.active_preset.read().expect("Lock failed").clone().unwrap_or_default();
+        let Some(name) = PresetNameWindow::new(&suggested).show(&self.wnd)? else { return Ok(()) };
+
+        let preset = FilterPreset {
+            name: name.clone(),
+            query: self.txt_search.text().unwrap_or_default(),
+            regex: self.cb_regex.is_checked(),
+            errors_only: *self.show_errors.read().expect("Lock failed"),
+            error_category: *self.error_category.read().expect("Lock failed"),
+            sort: self.sort.read().expect("Lock failed").clone(),
+            visible_columns: self.visible_cols.read().expect("Lock failed").clone(),
+            column_filters: self.column_filters.read().expect("Lock failed").clone(),
+        };
+        if let Ok(mut config) = self.config.write() {
+            upsert_preset(&mut config.filter_presets, preset);
+            let _ = config.save();
+        }
+        *self.active_preset.write().expect("Lock failed") = Some(name.clone());
+        self.fill_presets();
+
+        let mut args = HashMap::new();
+        args.insert("name", name);
+        let _ = self.status_bar.parts().get(0).set_text(&clean_tr(&loader.get_args("ui-status-preset-saved", args)));
+        Ok(())
+    }
+
+    fn delete_active_preset(&self) -> winsafe::AnyResult<()> {
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        let Some(name) = self.active_preset.read().expect("Lock failed").clone() else {
+            let _ = self.status_bar.parts().get(0).set_text(&clean_tr(&loader.get("ui-status-no-preset")));
+            return Ok(());
+        };
+        let mut args = HashMap::new();
+        args.insert("name", name.clone());
+        let question = clean_tr(&loader.get_args("ui-preset-delete-confirm", args));
+        if self.wnd.hwnd().MessageBox(&question, &clean_tr(&loader.get("ui-presets")), co::MB::YESNO | co::MB::ICONQUESTION)? != co::DLGID::YES {
+            return Ok(());
+        }
+        if let Ok(mut config) = self.config.write() {
+            config.filter_presets.retain(|p| p.name != name);
+            let _ = config.save();
+        }
+        *self.active_preset.write().expect("Lock failed") = None;
+        self.fill_presets();
+        Ok(())
+    }
+
+    // Adds the presets of a shared JSON file (same names are replaced)
+    fn import_presets(&self) -> winsafe::AnyResult<()> {
+        let file_dialog = winsafe::CoCreateInstance::<winsafe::IFileOpenDialog>(
+            &co::CLSID::FileOpenDialog, None::<&winsafe::IUnknown>, co::CLSCTX::INPROC_SERVER,
+        )?;
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        file_dialog.SetFileTypes(&[(loader.get("ui-file-json"), "*.json".to_owned()), (loader.get("ui-file-all"), "*.*".to_owned())])?;
+        if !file_dialog.Show(self.wnd.hwnd())? {
+            return Ok(());
+        }
+        let path = file_dialog.GetResult()?.GetDisplayName(co::SIGDN::FILESYSPATH)?;
+
+        let imported = fs::read_to_string(&path).map_err(anyhow::Error::from).and_then(|json| presets_from_json(&json));
+        let msg = match imported {
+            Ok(presets) => {
+                let count = presets.len();
+                if let Ok(mut config) = self.config.write() {
+                    for preset in presets {
+                        upsert_preset(&mut config.filter_presets, preset);
+                    }
+                    let _ = config.save();
+                }
+                self.fill_presets();
+                let mut args = HashMap::new();
+                args.insert("count", count.to_string());
+                loader.get_args("ui-status-presets-imported", args)
+            }
+            Err(e) => {
+                let mut args = HashMap::new();
+                args.insert("error", format!("{e:#}"));
+                loader.get_args("ui-status-presets-error", args)
+            }
+        };
+        let _ = self.status_bar.parts().get(0).set_text(&clean_tr(&msg));
+        Ok(())
+    }
+
+    fn export_presets(&self) -> winsafe::AnyResult<()> {
+        let file_dialog = winsafe::CoCreateInstance::<winsafe::IFileSaveDialog>(
+            &co::CLSID::FileSaveDialog, None::<&winsafe::IUnknown>, co::CLSCTX::INPROC_SERVER,
+        )?;
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        file_dialog.SetFileTypes(&[(loader.get("ui-file-json"), "*.json".to_owned())])?;
+        file_dialog.SetDefaultExtension("json")?;
+        file_dialog.SetFileName("radius-presets.json")?;
+        if !file_dialog.Show(self.wnd.hwnd())? {
+            return Ok(());
+        }
+        let path = file_dialog.GetResult()?.GetDisplayName(co::SIGDN::FILESYSPATH)?;
+
+        let presets = self.config.read().expect("Lock failed").filter_presets.clone();
+        let written = presets_to_json(&presets).and_then(|json| Ok(fs::write(&path, json)?));
+        let msg = match written {
+            Ok(()) => {
+                let mut args = HashMap::new();
+                args.insert("count", presets.len().to_string());
+                loader.get_args("ui-status-presets-exported", args)
+            }
+            Err(e) => {
+                let mut args = HashMap::new();
+                args.insert("error", format!("{e:#}"));
+                loader.get_args("ui-status-presets-error", args)
+            }
+        };
+        let _ = self.status_bar.parts().get(0).set_text(&clean_tr(&msg));
+        Ok(())
+    }
+
     fn on_btn_about_clicked(&self) -> winsafe::AnyResult<()> {
         let about_win = AboutWindow::new();
         about_win.show(&self.wnd)?;
@@ -857,8 +1823,10 @@ impl MyWindow {
     }
 
     fn jump_to_error(&self, start_idx: i32, direction: i32) {
-        let filtered = self.filtered_ids.read().expect("Lock failed");
+        let errors = self.error_filter();
+        // Same lock order as the filter worker: items, then ids
         let items = self.all_items.read().expect("Lock failed");
+        let filtered = self.filtered_ids.read().expect("Lock failed");
 
         let len = filtered.len() as i32;
         if len == 0 { return; }
@@ -874,7 +1842,7 @@ impl MyWindow {
 
             if let Some(&real_idx) = filtered.get(current as usize) {
                 if let Some(req) = items.get(real_idx) {
-                    if req.resp_type == "Access-Reject" { // Target only rejects (Red)
+                    if errors.targets(req) { // Target only the chosen failure category
                         found_idx = Some(current);
                         break; // Found it, exit loop
                     }
@@ -899,106 +1867,179 @@ impl MyWindow {
 
     // --- Tail Mode ---
     fn handle_file_change(&self) {
-        let path_opt = self.current_file_path.lock().expect("Lock poisoned").clone();
-        if let Some(path) = path_opt {
-            // Update size for next time
-            if let Ok(metadata) = std::fs::metadata(&path) {
-                let new_size = metadata.len();
-                let mut last_size = self.last_file_size.lock().expect("Lock poisoned");
-
-                if new_size > *last_size {
-                    // File grew: reload everything for now (safer for XML)
-                    // Ideally: read delta and parse fragment
-                    *last_size = new_size;
-
-                    // Trigger a reload as if Open was clicked,
-                    // but without dialog and in background
-                    let me = self.clone();
-                    // Reuse existing loading logic but adapted
-                    // Simplified: simulate Open click if possible without dialog,
-                    // but since on_btn_open_clicked opens a dialog, we extract the logic.
-
-                    // Call loading logic directly in a thread
-                    // Note: handle is_busy flag
-                    if !me.is_busy.load(Ordering::SeqCst) {
-                        let _ = me.status_bar.parts().get(0).set_text("File changed, reloading...");
-
-                        let is_busy_bg = me.is_busy.clone();
-                        let all_items_bg = me.all_items.clone();
-                        let raw_count_bg = me.raw_count.clone();
-                        let safe_hwnd = SafeHWND::from_hwnd(me.wnd.hwnd());
-                        let path_bg = path.clone();
-
-                        thread::spawn(move || {
-                            // 1. Start busy guard
-                            let busy = BusyGuard::new(is_busy_bg);
-
-                            // 2. FORCE CURSOR IMMEDIATELY
-                            let _ = post_message_safe(&safe_hwnd.h(), msg::WndMsg { msg_id: WM_FORCE_WAIT, wparam: 0, lparam: 0 });
-
-                            // Parse entire file
-                            match parse_full_logic(&path_bg, Some(safe_hwnd)) {
-                                Ok((reqs, raw)) => {
-                                    {
-                                        let mut items = all_items_bg.write().expect("Lock failed");
-                                        *items = reqs;
-                                        let mut r = raw_count_bg.write().expect("Lock failed");
-                                        *r = raw;
-                                    }
-
-                                    drop(busy); // Release is_busy flag
-
-                                    // 4. FORCE ARROW RETURN
-                                    let _ = post_message_safe(&safe_hwnd.h(), msg::WndMsg { msg_id: WM_FORCE_NORMAL, wparam: 0, lparam: 0 });
-
-                                    // Notify UI that it's done
-                                    let _ = post_message_safe(&safe_hwnd.h(), msg::WndMsg { msg_id: WM_LOAD_DONE, wparam: 0, lparam: 0 });
-                                }
-                                Err(e) => {
-                                    eprintln!("Reload error: {:?}", e);
-                                    drop(busy);
+        let Some(path) = self.current_file_path.lock().expect("Lock poisoned").clone() else { return; };
+        if self.is_busy.load(Ordering::SeqCst) { return; }
 
-                                    let _ = post_message_safe(&safe_hwnd.h(), msg::WndMsg { msg_id: WM_FORCE_NORMAL, wparam: 0, lparam: 0 });
+        let tail_bg = self.tail.clone();
+        let is_busy_bg = self.is_busy.clone();
+        let all_items_bg = self.all_items.clone();
+        let index_bg = self.search_index.clone();
+        let generation_bg = self.items_generation.clone();
+        let loaded_bg = self.rows_loaded.clone();
+        let report_bg = self.parse_report.clone();
+        let worker_bg = self.filter_worker.clone();
+        let (time_settings, mac_notation) = {
+            let cfg = self.config.read().expect("Lock failed");
+            (cfg.time.clone(), cfg.mac_notation)
+        };
+        let safe_hwnd = SafeHWND::from_hwnd(self.wnd.hwnd());
 
-                                    let _ = post_message_safe(&safe_hwnd.h(), msg::WndMsg { msg_id: WM_LOAD_ERROR, wparam: 0, lparam: 0 });
-                                }
-                            }
-                        });
+        thread::spawn(move || {
+            // Held for the whole update: concurrent notifications are serialized
+            let mut tail_guard = tail_bg.lock().expect("Lock poisoned");
+
+            // 1. Incremental path: only the bytes appended since the last read
+            let read = tail_guard.as_mut().map(|(tail, _)| tail.read_appended());
+            match read {
+                Some(Ok(TailRead::Appended(chunk))) => {
+                    if chunk.report.raw_count == 0 { return; }
+                    let Some((tail, loaded)) = tail_guard.as_mut() else { return; };
+
+                    let touched = {
+                        let mut items = all_items_bg.write().expect("Lock failed");
+                        // Rows replaced since the tail was opened (folder, other file):
+                        // its session map points into them, stop following
+                        if loaded_bg.load(Ordering::SeqCst) != *loaded {
+                            drop(items);
+                            *tail_guard = None;
+                            return;
+                        }
+                        let touched = tail.merge(chunk.events, &mut items);
+                        for &i in &touched {
+                            time_settings.apply(&mut items[i]);
+                            mac_notation.apply(&mut items[i]);
+                        }
+                        let generation = bump_generation(&generation_bg);
+                        index_bg.write().expect("Lock failed").update(&items, &touched, generation);
+                        touched
+                    };
+                    report_bg.write().expect("Lock failed").merge(chunk.report);
+
+                    // Appended rows are filtered like the others, after any pending search
+                    if let Some(worker) = worker_bg.get() {
+                        worker.wait(worker.submit(FilterJob::Touched(touched)));
+                    }
+                    safe_hwnd.post(WM_LOAD_DONE, 1, 0);
+                    return;
+                }
+                Some(Err(e)) => {
+                    eprintln!("Tail error: {:?}", e);
+                    return;
+                }
+                // File truncated / rotated, or no tail state yet (F5): full reload below
+                Some(Ok(TailRead::Truncated)) | None => {}
+            }
+
+            // 2. Full reload
+            let busy = BusyGuard::new(is_busy_bg);
+            safe_hwnd.send(WM_FORCE_WAIT, 0, 0);
+
+            match LogTail::open(&path, Some(&safe_hwnd)) {
+                Ok((tail, mut reqs, report)) => {
+                    time_settings.apply_all(&mut reqs);
+                    mac_notation.apply_all(&mut reqs);
+                    {
+                        let mut items = all_items_bg.write().expect("Lock failed");
+                        *items = reqs;
+                        bump_generation(&generation_bg);
+                        *tail_guard = Some((tail, bump_generation(&loaded_bg)));
+                        drop(tail_guard);
+                        let mut r = report_bg.write().expect("Lock failed");
+                        *r = report;
                     }
+                    rebuild_index(&all_items_bg, &generation_bg, &index_bg);
+
+                    drop(busy); // Release is_busy flag
+                    safe_hwnd.send(WM_FORCE_NORMAL, 0, 0);
+                    safe_hwnd.post(WM_LOAD_DONE, 0, 0);
+                }
+                Err(e) => {
+                    eprintln!("Reload error: {:?}", e);
+                    drop(busy);
+                    safe_hwnd.send(WM_FORCE_NORMAL, 0, 0);
+                    safe_hwnd.post(WM_LOAD_ERROR, 0, 0);
+                }
+            }
+        });
+    }
+
+    /// Parses the search box (query language, or one regex in regex mode); an invalid query is reported in the status bar.
+    fn current_query(&self) -> Option<Query> {
+        let text = self.txt_search.text().unwrap_or_default();
+        let parsed = if self.cb_regex.is_checked() { Query::regex(&text) } else { Query::parse(&text) };
+        match parsed {
+            Ok(mut query) => {
+                // Column filters and time restrictions are checked before the text query
+                query = query.with_column_filters(&self.column_filters.read().expect("Lock failed"));
+                if let Some(window) = *self.time_window.read().expect("Lock failed") {
+                    query = query.within(window);
                 }
+                if let Some(filter) = *self.time_filter.read().expect("Lock failed") {
+                    query = query.within(filter.resolve(Utc::now(), self.display_zone()));
+                }
+                Some(query)
+            }
+            Err(e) => {
+                let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+                let mut args = HashMap::new();
+                args.insert("error", e.to_string());
+                let msg = loader.get_args("ui-status-query-error", args);
+                let _ = self.status_bar.parts().get(0).set_text(&clean_tr(&msg));
+                None
             }
         }
     }
 
     fn trigger_async_filter(&self) {
-        // Capture current values
-        let query = self.txt_search.text().unwrap_or_default();
-        let show_err_val = *self.show_errors.read().expect("Lock failed");
-        let sort_col_val = *self.sort_col.read().expect("Lock failed");
-        let sort_desc_val = *self.sort_desc.read().expect("Lock failed");
+        // An invalid query keeps the current view
+        let Some(query) = self.current_query() else { return };
+        let _ = self.submit_filter(query);
+    }
 
-        let all_items_bg = self.all_items.clone();
-        let filt_ids_bg = self.filtered_ids.clone();
-        let safe_hwnd = SafeHWND::from_hwnd(self.wnd.hwnd());
+    // Hands the current criteria to the filter worker (replacing any pass not finished yet)
+    fn submit_filter(&self, query: Query) -> Option<u64> {
+        *self.last_query.write().expect("Lock failed") = query.clone();
+        let request = FilterRequest {
+            query,
+            errors: self.show_errors.read().expect("Lock failed").then(|| self.error_filter()),
+            sort: self.sort.read().expect("Lock failed").clone(),
+        };
+        self.filter_worker.get().map(|worker| worker.submit(FilterJob::Full(request)))
+    }
 
-        // We don't use BusyGuard here to avoid locking the mouse during simple search
-        thread::spawn(move || {
-            let h = safe_hwnd.h();
-            apply_filter_logic(
-                &all_items_bg,
-                &filt_ids_bg,
-                &query,
-                show_err_val,
-                sort_col_val,
-                sort_desc_val
-            );
-
-            let _ = post_message_safe(&h, msg::WndMsg {
-                msg_id: WM_FILTER_DONE,
-                wparam: 0,
-                lparam: 0,
-            });
-        });
+    // Zone of the times shown, in which the time ranges are entered and resolved
+    fn display_zone(&self) -> Zone {
+        self.config.read().expect("Lock failed").time.display_zone.unwrap_or(Zone::Local)
+    }
+
+    // Rows shown / raw events / skipped events and files of the loaded files
+    fn show_load_status(&self) {
+        let count = self.filtered_ids.read().expect("Lock failed").len();
+        let (raw, lost, failed, out_of_range) = {
+            let report = self.parse_report.read().expect("Lock failed");
+            (report.raw_count, report.lost(), report.failed_files.len(), report.out_of_range.len())
+        };
+
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        let mut args = HashMap::new();
+        args.insert("count", count.to_string());
+        args.insert("raw", raw.to_string());
+        args.insert("lost", lost.to_string());
+        args.insert("files", failed.to_string());
+
+        // Mention skipped events and files only when there are some
+        let key = match (lost, failed) {
+            (_, 1..) => "ui-status-display-failed-files",
+            (1.., 0) => "ui-status-display-lost",
+            (0, 0) => "ui-status-display",
+        };
+        let mut msg = clean_tr(&loader.get_args(key, args));
+        if out_of_range > 0 {
+            let mut args = HashMap::new();
+            args.insert("files", out_of_range.to_string());
+            msg = format!("{msg} {}", clean_tr(&loader.get_args("ui-status-out-of-range", args)));
+        }
+        let _ = self.status_bar.parts().get(0).set_text(&msg);
     }
 
 
@@ -1022,12 +2063,10 @@ impl MyWindow {
             // --- WATCHER SETUP ---
             // Deactivate the previous watcher
             *self.watcher.lock().expect("Lock poisoned") = None;
+            *self.current_folder.lock().expect("Lock poisoned") = None;
 
             if !self.cb_append.is_checked() {
                 *self.current_file_path.lock().expect("Lock poisoned") = Some(path.clone());
-                if let Ok(meta) = std::fs::metadata(&path) {
-                    *self.last_file_size.lock().expect("Lock poisoned") = meta.len();
-                }
 
                 let safe_hwnd_watcher = SafeHWND::from_hwnd(self.wnd.hwnd());
                 let mut new_watcher = notify::recommended_watcher(move |res: Result<notify::Event, _>| {
@@ -1056,11 +2095,13 @@ impl MyWindow {
             } else {
                  *self.current_file_path.lock().expect("Lock poisoned") = None;
             }
+            *self.tail.lock().expect("Lock poisoned") = None;
             
             if !self.cb_append.is_checked() {
                 let _ = self.lst_logs.items().set_count(0, None);
                 self.txt_search.set_text("");
                 *self.show_errors.write().expect("Lock failed") = false;
+                *self.time_window.write().expect("Lock failed") = None;
                 let _ = self.btn_rejects.hwnd().SetWindowText(&loader.get("ui-btn-errors-only"));
             }
             
@@ -1068,15 +2109,18 @@ impl MyWindow {
             
             let is_busy_bg = self.is_busy.clone();
             let all_items_bg = self.all_items.clone();
-            let raw_count_bg = self.raw_count.clone();
-            let filt_ids_bg = self.filtered_ids.clone();
+            let index_bg = self.search_index.clone();
+            let generation_bg = self.items_generation.clone();
+            let loaded_bg = self.rows_loaded.clone();
+            let report_bg = self.parse_report.clone();
             let is_append = self.cb_append.is_checked();
             let safe_hwnd = SafeHWND::from_hwnd(self.wnd.hwnd());
-            let query = self.txt_search.text().unwrap_or_default();
-            let show_err_val = *self.show_errors.read().expect("Lock failed");
-            let sort_col_val = *self.sort_col.read().expect("Lock failed");
-            let sort_desc_val = *self.sort_desc.read().expect("Lock failed");
             let path_bg = path.clone();
+            let tail_bg = self.tail.clone();
+            let (time_settings, mac_notation) = {
+                let cfg = self.config.read().expect("Lock failed");
+                (cfg.time.clone(), cfg.mac_notation)
+            };
 
             thread::spawn(move || {
                 // 1. Start busy guard
@@ -1085,17 +2129,31 @@ impl MyWindow {
                 // 2. FORCE CURSOR IMMEDIATELY
                 safe_hwnd.send(WM_FORCE_WAIT, 0, 0);
 
-                match parse_full_logic(&path_bg, Some(safe_hwnd)) {
-                    Ok((items, raw_total)) => {
+                // A single file is followed (tail): keep its offset and session map.
+                // Appending mixes several files, which disables tail mode.
+                let parsed = if is_append {
+                    parse_full_logic(&path_bg, Some(&safe_hwnd)).map(|(items, report)| (items, report, None))
+                } else {
+                    LogTail::open(&path_bg, Some(&safe_hwnd)).map(|(tail, items, report)| (items, report, Some(tail)))
+                };
+
+                match parsed {
+                    Ok((mut items, report, tail)) => {
+                        time_settings.apply_all(&mut items);
+                        mac_notation.apply_all(&mut items);
                         {
+                            let mut tail_guard = tail_bg.lock().expect("Lock poisoned");
                             let mut all_guard = all_items_bg.write().expect("Lock failed");
                             if is_append { all_guard.extend(items); } else { *all_guard = items; }
+                            bump_generation(&generation_bg);
+                            let loaded = bump_generation(&loaded_bg);
+                            *tail_guard = tail.map(|tail| (tail, loaded));
                         }
                         {
-                            let mut raw_guard = raw_count_bg.write().expect("Lock failed");
-                            if is_append { *raw_guard += raw_total; } else { *raw_guard = raw_total; }
+                            let mut report_guard = report_bg.write().expect("Lock failed");
+                            if is_append { report_guard.merge(report); } else { *report_guard = report; }
                         }
-                        apply_filter_logic(&all_items_bg, &filt_ids_bg, &query, show_err_val, sort_col_val, sort_desc_val);
+                        rebuild_index(&all_items_bg, &generation_bg, &index_bg);
                         
                         drop(busy); // Release is_busy flag
 
@@ -1134,86 +2192,102 @@ impl MyWindow {
                 let _ = self.lst_logs.items().set_count(0, None);
                 self.txt_search.set_text("");
                 *self.show_errors.write().expect("Lock failed") = false;
+                *self.time_window.write().expect("Lock failed") = None;
                 let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
                 let _ = self.btn_rejects.hwnd().SetWindowText(&loader.get("ui-btn-errors-only"));
             }
-            
-            let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
-            let _ = self.status_bar.parts().get(0).set_text(&loader.get("ui-status-loading-folder"));
-            
-            let folder_path_str = folder_path.clone();
-            let is_append = self.cb_append.is_checked();
-            let is_busy_bg = self.is_busy.clone();
-            let all_items_bg = self.all_items.clone();
-            let raw_count_bg = self.raw_count.clone();
-            let filt_ids_bg = self.filtered_ids.clone();
-            let query = self.txt_search.text().unwrap_or_default();
-            let show_err_val = *self.show_errors.read().expect("Lock failed");
-            let sort_col_val = *self.sort_col.read().expect("Lock failed");
-            let sort_desc_val = *self.sort_desc.read().expect("Lock failed");
-            let safe_hwnd = SafeHWND::from_hwnd(self.wnd.hwnd());
+            self.load_folder(folder_path, self.cb_append.is_checked());
+        }
+        Ok(())
+    }
 
-            thread::spawn(move || {
-                // 1. Start busy guard
-                let busy = BusyGuard::new(is_busy_bg);
-                
-                // 2. FORCE CURSOR IMMEDIATELY
-                safe_hwnd.send(WM_FORCE_WAIT, 0, 0);
+    // Parses the log files of `folder_path` in the background, leaving out the ones outside the time range
+    fn load_folder(&self, folder_path: String, is_append: bool) {
+        // Rows appended to another source cannot be reloaded from the folder alone
+        *self.current_folder.lock().expect("Lock poisoned") = (!is_append).then(|| folder_path.clone());
+        // A folder is not followed: forget the file of tail mode (and F5 reloads nothing)
+        *self.watcher.lock().expect("Lock poisoned") = None;
+        *self.current_file_path.lock().expect("Lock poisoned") = None;
+        *self.tail.lock().expect("Lock poisoned") = None;
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        let _ = self.status_bar.parts().get(0).set_text(&loader.get("ui-status-loading-folder"));
+        
+        let is_busy_bg = self.is_busy.clone();
+        let all_items_bg = self.all_items.clone();
+        let index_bg = self.search_index.clone();
+        let generation_bg = self.items_generation.clone();
+        let loaded_bg = self.rows_loaded.clone();
+        let report_bg = self.parse_report.clone();
+        let (time_settings, mac_notation) = {
+            let cfg = self.config.read().expect("Lock failed");
+            (cfg.time.clone(), cfg.mac_notation)
+        };
+        let range = self.time_filter.read().expect("Lock failed").map(|f| f.resolve(Utc::now(), self.display_zone()));
+        let safe_hwnd = SafeHWND::from_hwnd(self.wnd.hwnd());
 
-                let mut files: Vec<(std::path::PathBuf, std::time::SystemTime)> = Vec::new();
-                if let Ok(entries) = fs::read_dir(&folder_path_str) {
-                    for entry in entries.flatten() {
-                        let path = entry.path();
-                        if path.is_file() && path.extension().is_some_and(|ext| ext == "log") {
-                            if let Ok(meta) = fs::metadata(&path) {
-                                if let Ok(modified) = meta.modified() {
-                                    files.push((path, modified));
-                                }
-                            }
-                        }
+        thread::spawn(move || {
+            // 1. Start busy guard
+            let busy = BusyGuard::new(is_busy_bg);
+            
+            // 2. FORCE CURSOR IMMEDIATELY
+            safe_hwnd.send(WM_FORCE_WAIT, 0, 0);
+
+            let files = list_log_files(&folder_path).unwrap_or_default();
+            
+            let mut total_items = Vec::new();
+            let mut total_report = ParseReport::default();
+            
+            for file_path in files {
+                let file_str = file_path.to_str().unwrap_or("");
+                // Files entirely outside the time range are not parsed, only listed in the report
+                if let (Some(range), Ok(Some((first, last)))) = (range, file_time_span(file_str, &time_settings)) {
+                    if !range.overlaps(first, last) {
+                        total_report.skip_file(&file_path.to_string_lossy(), first, last);
+                        continue;
                     }
                 }
-                files.sort_by_key(|f| f.1); 
-                
-                let mut total_items = Vec::new();
-                let mut total_raw_count = 0;
-                
-                for (file_path, _) in files {
-                    if let Ok((items, raw_c)) = parse_full_logic(file_path.to_str().unwrap_or(""), None) {
+                match parse_full_logic(file_str, None) {
+                    Ok((items, report)) => {
                         total_items.extend(items);
-                        total_raw_count += raw_c;
+                        total_report.merge(report);
                     }
+                    // Listed in the parse report (status bar, right-click > Parse report)
+                    Err(e) => total_report.fail_file(&file_path.to_string_lossy(), format!("{e:#}")),
                 }
+            }
     
-                if !total_items.is_empty() {
-                     {
-                        let mut all_guard = all_items_bg.write().expect("Lock failed");
-                        if is_append { all_guard.extend(total_items); } else { *all_guard = total_items; }
-                    }
-                    {
-                        let mut raw_guard = raw_count_bg.write().expect("Lock failed");
-                        if is_append { *raw_guard += total_raw_count; } else { *raw_guard = total_raw_count; }
-                    }
-    
-                    apply_filter_logic(&all_items_bg, &filt_ids_bg, &query, show_err_val, sort_col_val, sort_desc_val);
-                    
-                    drop(busy); // Release is_busy flag
+            if !total_items.is_empty() {
+                time_settings.apply_all(&mut total_items);
+                mac_notation.apply_all(&mut total_items);
+                 {
+                    let mut all_guard = all_items_bg.write().expect("Lock failed");
+                    if is_append { all_guard.extend(total_items); } else { *all_guard = total_items; }
+                    bump_generation(&generation_bg);
+                    bump_generation(&loaded_bg);
+                }
+                {
+                    let mut report_guard = report_bg.write().expect("Lock failed");
+                    if is_append { report_guard.merge(total_report); } else { *report_guard = total_report; }
+                }
+                rebuild_index(&all_items_bg, &generation_bg, &index_bg);
+                
+                drop(busy); // Release is_busy flag
 
-                    // 4. FORCE ARROW RETURN
-                    safe_hwnd.send(WM_FORCE_NORMAL, 0, 0);
+                // 4. FORCE ARROW RETURN
+                safe_hwnd.send(WM_FORCE_NORMAL, 0, 0);
 
-                    // 5. Notify UI
-                    safe_hwnd.post(WM_LOAD_DONE, 0, 0);
-                } else {
-                     drop(busy); // Release is_busy flag
+                // 5. Notify UI
+                safe_hwnd.post(WM_LOAD_DONE, 0, 0);
+            } else {
+                 // Nothing loaded: keep the current rows, but say which files failed
+                 report_bg.write().expect("Lock failed").merge(total_report);
+                 drop(busy); // Release is_busy flag
 
-                     safe_hwnd.send(WM_FORCE_NORMAL, 0, 0);
+                 safe_hwnd.send(WM_FORCE_NORMAL, 0, 0);
 
-                     safe_hwnd.post(WM_LOAD_DONE, 0, 0);
-                }
-            });
-        }
-        Ok(())
+                 safe_hwnd.post(WM_LOAD_DONE, 0, 0);
+            }
+        });
     }
 
     #[allow(unsafe_code)]
@@ -1236,18 +2310,7 @@ impl MyWindow {
         if real_idx >= items.len() { return Ok(()); }
         let req = &items[real_idx];
 
-        let text = match log_col {
-            LogColumn::Timestamp => &req.timestamp,
-            LogColumn::Type => &req.req_type,
-            LogColumn::Server => &req.server,
-            LogColumn::ApIp => &req.ap_ip,
-            LogColumn::ApName => &req.ap_name,
-            LogColumn::Mac => &req.mac,
-            LogColumn::User => &req.user,
-            LogColumn::ResponseType => &req.resp_type,
-            LogColumn::Reason => &req.reason,
-            LogColumn::Session => &req.session_id,
-        };
+        let text = req.column_text(log_col);
 
         // FINAL FIX: Use UnsafeCell to avoid borrow lifetime issues
         // The thread_local buffer stays alive, and we access it via unsafe
@@ -1287,25 +2350,25 @@ impl MyWindow {
     //      The structure is identical, only .lock() calls change to .read() or .write().
     
     // Example modification for on_lst_lvn_column_click
+    // Shift+click adds the column as the next sort key (or reverses it)
     fn on_lst_lvn_column_click(&self, p: &winsafe::NMLISTVIEW) -> winsafe::AnyResult<()> {
-        let mut sort_col_g = self.sort_col.write().expect("Lock failed");
-        let mut sort_desc_g = self.sort_desc.write().expect("Lock failed");
-
         let visible = self.visible_cols.read().expect("Lock failed");
         let Some(&new_col) = visible.get(p.iSubItem as usize) else { return Ok(()); };
         drop(visible);
 
-        if *sort_col_g == new_col {
-            *sort_desc_g = !*sort_desc_g;
-        } else {
-            *sort_col_g = new_col;
-            *sort_desc_g = false;
+        let shift = (winsafe::GetAsyncKeyState(co::VK::SHIFT) as u16 & 0x8000) != 0;
+        let mut sort_g = self.sort.write().expect("Lock failed");
+        sort_g.click(new_col, shift);
+        let sort = sort_g.clone();
+
+        // Drop lock before triggering async filter
+        drop(sort_g);
+
+        if let Ok(mut config) = self.config.write() {
+            config.sort = sort;
+            let _ = config.save();
         }
         
-        // Drop locks before triggering async filter
-        drop(sort_col_g);
-        drop(sort_desc_g);
-        
         // Trigger async filter again
         self.trigger_async_filter();
         
@@ -1358,7 +2421,30 @@ impl MyWindow {
                 let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
                 h_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(1001), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-copy-cell"))))?;
                 h_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(1003), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-copy-row"))))?;
+                h_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(1007), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-session-details"))))?;
+                h_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(1004), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-copy-attributes"))))?;
+                h_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(1005), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-parse-report"))))?;
+                h_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(1010), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-statistics"))))?;
+                h_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(1011), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-detect-attacks"))))?;
                 h_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(1002), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-filter-cell"))))?;
+                h_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(1009), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-column-filter"))))?;
+
+                // Time window: every request around this one, whoever it is from
+                let current_secs = self.config.read().expect("Lock failed").time_window_secs;
+                let h_window_menu = winsafe::HMENU::CreatePopupMenu()?;
+                for (i, secs) in TIME_WINDOW_PRESETS.iter().enumerate() {
+                    let mut flags = co::MF::STRING;
+                    if *secs == current_secs { flags |= co::MF::CHECKED; }
+                    let mut args = HashMap::new();
+                    args.insert("secs", secs.to_string());
+                    let text = clean_tr(&loader.get_args("ui-time-window-secs", args));
+                    h_window_menu.AppendMenu(flags, winsafe::IdMenu::Id(1100 + i as u16), winsafe::BmpPtrStr::from_str(&text))?;
+                }
+                h_menu.AppendMenu(co::MF::POPUP, winsafe::IdMenu::Menu(&h_window_menu), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-time-window"))))?;
+                h_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(1008), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-time-range"))))?;
+                if self.time_window.read().expect("Lock failed").is_some() {
+                    h_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(1006), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-leave-time-window"))))?;
+                }
 
                 if let Some(cmd_id) = h_menu.TrackPopupMenu(co::TPM::RETURNCMD | co::TPM::LEFTALIGN, pt_screen, self.lst_logs.hwnd())? {
                     let mut cell_text = String::new();
@@ -1370,18 +2456,7 @@ impl MyWindow {
                             let ids = self.filtered_ids.read().expect("Lock failed");
                             if let Some(&idx) = ids.get(item_index as usize) {
                                 if let Some(req) = items.get(idx) {
-                                    cell_text = match log_col {
-                                        LogColumn::Timestamp => req.timestamp.clone(),
-                                        LogColumn::Type => req.req_type.clone(),
-                                        LogColumn::Server => req.server.clone(),
-                                        LogColumn::ApIp => req.ap_ip.clone(),
-                                        LogColumn::ApName => req.ap_name.clone(),
-                                        LogColumn::Mac => req.mac.clone(),
-                                        LogColumn::User => req.user.clone(),
-                                        LogColumn::ResponseType => req.resp_type.clone(),
-                                        LogColumn::Reason => req.reason.clone(),
-                                        LogColumn::Session => req.session_id.clone(),
-                                    };
+                                    cell_text = req.column_text(log_col).to_string();
                                 }
                             }
                         }
@@ -1399,9 +2474,36 @@ impl MyWindow {
                         1003 => {
                             let items = self.all_items.read().expect("Lock failed");
                             let ids = self.filtered_ids.read().expect("Lock failed");
-                            if let Some(&idx) = ids.get(item_index as usize) {
-                                let tsv = items[idx].to_tsv();
-                                let _ = clipboard_win::set_clipboard_string(&tsv);
+                            if let Some(req) = ids.get(item_index as usize).and_then(|&idx| items.get(idx)) {
+                                let _ = clipboard_win::set_clipboard_string(&req.to_tsv());
+                            }
+                        }
+                        1004 => {
+                            let items = self.all_items.read().expect("Lock failed");
+                            let ids = self.filtered_ids.read().expect("Lock failed");
+                            if let Some(req) = ids.get(item_index as usize).and_then(|&idx| items.get(idx)) {
+                                let _ = clipboard_win::set_clipboard_string(&req.attributes.to_text());
+                            }
+                        }
+                        1005 => self.show_parse_report()?,
+                        1010 => self.show_statistics()?,
+                        1011 => self.detect_attacks()?,
+                        1007 => self.show_session_details(item_index as usize)?,
+                        1008 => self.choose_time_range()?,
+                        1009 => {
+                            let col = self.visible_cols.read().expect("Lock failed").get(subitem_index as usize).copied();
+                            if let Some(col) = col {
+                                self.edit_column_filter(col)?;
+                            }
+                        }
+                        1006 => {
+                            *self.time_window.write().expect("Lock failed") = None;
+                            let _ = self.status_bar.parts().get(0).set_text("");
+                            self.trigger_async_filter();
+                        }
+                        1100..=1199 => {
+                            if let Some(&secs) = TIME_WINDOW_PRESETS.get((cmd_id - 1100) as usize) {
+                                self.enter_time_window(item_index as usize, secs);
                             }
                         }
                         _ => {}
@@ -1413,6 +2515,352 @@ impl MyWindow {
         Ok(())
     }
 
+    // Time range dialog; the new range applies to the view and to the next folder load
+    fn choose_time_range(&self) -> winsafe::AnyResult<()> {
+        let current = *self.time_filter.read().expect("Lock failed");
+        let Some(chosen) = TimeRangeWindow::new(current, self.display_zone()).show(&self.wnd)? else { return Ok(()) };
+        self.set_time_filter(chosen);
+        Ok(())
+    }
+
+    // New time range of the view (dialog, chart selection), announced in the status bar;
+    // offers to reload the folder when the range covers files it left out
+    fn set_time_filter(&self, filter: Option<TimeFilter>) {
+        *self.time_filter.write().expect("Lock failed") = filter;
+        self.trigger_async_filter();
+
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        let msg = match filter {
+            None => loader.get("ui-status-time-range-cleared"),
+            Some(filter) => {
+                let mut args = HashMap::new();
+                args.insert("range", describe_time_filter(filter, self.display_zone()));
+                loader.get_args("ui-status-time-range", args)
+            }
+        };
+        let _ = self.status_bar.parts().get(0).set_text(&clean_tr(&msg));
+
+        // Files of the folder left out at load time that the new range covers: offer to load them
+        let Some(folder) = self.current_folder.lock().expect("Lock poisoned").clone() else { return };
+        let range = filter.map_or_else(TimeRange::default, |f| f.resolve(Utc::now(), self.display_zone()));
+        let missing = self.parse_report.read().expect("Lock failed").out_of_range_in(&range);
+        if missing > 0 {
+            let mut args = HashMap::new();
+            args.insert("files", missing.to_string());
+            let question = clean_tr(&loader.get_args("ui-reload-folder-question", args));
+            let answer = self.wnd.hwnd().MessageBox(&question, &clean_tr(&loader.get("ui-time-range-title")), co::MB::YESNO | co::MB::ICONQUESTION);
+            if matches!(answer, Ok(co::DLGID::YES)) {
+                self.load_folder(folder, false);
+            }
+        }
+    }
+
+    // --- Time Series Chart ---
+
+    // Bucket under the x coordinate of the chart
+    fn chart_bucket_at(&self, x: i32) -> Option<usize> {
+        let count = self.series.read().expect("Lock failed").as_ref().map_or(0, |series| series.buckets.len());
+        let width = self.chart.hwnd().GetClientRect().ok()?.right;
+        (count > 0 && width > 0).then(|| (x.clamp(0, width - 1) as usize * count / width as usize).min(count - 1))
+    }
+
+    // Stacked bars per bucket (rejects, accepts, the rest), the range being dragged, and a summary line
+    fn paint_chart(&self) -> winsafe::AnyResult<()> {
+        const LABEL_HEIGHT: i32 = 16;
+        let hdc = self.chart.hwnd().BeginPaint()?;
+        let rc = self.chart.hwnd().GetClientRect()?;
+        let brush = |r, g, b| winsafe::HBRUSH::CreateSolidBrush(winsafe::COLORREF::from_rgb(r, g, b));
+        hdc.FillRect(rc, &*brush(255, 255, 255)?)?;
+        let _ = hdc.SetBkMode(co::BKMODE::TRANSPARENT);
+        let _ = hdc.SetTextColor(winsafe::COLORREF::from_rgb(80, 80, 80));
+
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        let series = self.series.read().expect("Lock failed");
+        let Some(series) = series.as_ref() else {
+            hdc.TextOut(4, 1, &clean_tr(&loader.get("ui-chart-empty")))?;
+            return Ok(());
+        };
+
+        let count = series.buckets.len() as i64;
+        let x_of = |i: usize| (i64::from(rc.right) * i as i64 / count) as i32;
+        if let Some((anchor, current)) = *self.chart_drag.lock().expect("Lock failed") {
+            let selected = winsafe::RECT { left: x_of(anchor.min(current)), top: 0, right: x_of(anchor.max(current) + 1), bottom: rc.bottom };
+            hdc.FillRect(selected, &*brush(204, 228, 247)?)?;
+        }
+
+        let plot_height = (rc.bottom - LABEL_HEIGHT).max(1) as usize;
+        let max = series.max_total().max(1);
+        let (red, green, gray) = (brush(220, 53, 69)?, brush(25, 135, 84)?, brush(160, 160, 160)?);
+        for (i, bucket) in series.buckets.iter().enumerate() {
+            let (left, mut right) = (x_of(i), x_of(i + 1));
+            if right - left > 2 {
+                right -= 1; // Gap between wide bars
+            }
+            let mut bottom = rc.bottom;
+            for (value, brush) in [(bucket.rejected, &*red), (bucket.accepted, &*green), (bucket.other, &*gray)] {
+                // A single request stays visible next to a peak
+                let height = if value == 0 { 0 } else { (value * plot_height / max).max(1) as i32 };
+                if height > 0 {
+                    hdc.FillRect(winsafe::RECT { left, top: bottom - height, right, bottom }, brush)?;
+                    bottom -= height;
+                }
+            }
+        }
+
+        let zone = self.display_zone();
+        let shown = |instant: Option<chrono::DateTime<Utc>>| {
+            instant.map(|t| zone.to_wall_clock(t).format("%Y-%m-%d %H:%M").to_string()).unwrap_or_default()
+        };
+        let span = series.range(0, series.buckets.len() - 1);
+        let mut args = HashMap::new();
+        args.insert("from", shown(span.from));
+        args.insert("to", shown(span.to));
+        args.insert("size", loader.get(series.size.ftl_key()));
+        args.insert("max", series.max_total().to_string());
+        let mut summary = clean_tr(&loader.get_args("ui-chart-summary", args));
+        if series.outside > 0 {
+            let mut args = HashMap::new();
+            args.insert("count", series.outside.to_string());
+            summary = format!("{summary} ({})", clean_tr(&loader.get_args("ui-chart-outside", args)));
+        }
+        hdc.TextOut(4, 1, &summary)?;
+        Ok(())
+    }
+
+    fn on_chart_context_menu(&self, pt_screen: winsafe::POINT) -> winsafe::AnyResult<()> {
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        let h_menu = winsafe::HMENU::CreatePopupMenu()?;
+        let export_flags = if self.series.read().expect("Lock failed").is_some() { co::MF::STRING } else { co::MF::STRING | co::MF::GRAYED };
+        h_menu.AppendMenu(export_flags, winsafe::IdMenu::Id(1201), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-chart-export"))))?;
+        if self.time_filter.read().expect("Lock failed").is_some() {
+            h_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(1202), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-chart-clear"))))?;
+        }
+
+        match h_menu.TrackPopupMenu(co::TPM::RETURNCMD | co::TPM::LEFTALIGN, pt_screen, self.chart.hwnd())? {
+            Some(1201) => self.export_chart()?,
+            Some(1202) => self.set_time_filter(None),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // The chart's time series as CSV (one line per bucket: written on the UI thread)
+    fn export_chart(&self) -> winsafe::AnyResult<()> {
+        let file_dialog = winsafe::CoCreateInstance::<winsafe::IFileSaveDialog>(
+            &co::CLSID::FileSaveDialog, None::<&winsafe::IUnknown>, co::CLSCTX::INPROC_SERVER,
+        )?;
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        file_dialog.SetFileTypes(&[(loader.get("ui-file-csv"), "*.csv".to_owned())])?;
+        file_dialog.SetDefaultExtension("csv")?;
+        file_dialog.SetFileName("radius-log-histogram.csv")?;
+
+        if !file_dialog.Show(self.wnd.hwnd())? {
+            return Ok(());
+        }
+        let path = file_dialog.GetResult()?.GetDisplayName(co::SIGDN::FILESYSPATH)?;
+
+        let zone = self.display_zone();
+        let result = match self.series.read().expect("Lock failed").as_ref() {
+            Some(series) => fs::File::create(&path).and_then(|file| series.write_csv(BufWriter::new(file), zone)),
+            None => return Ok(()),
+        };
+        let msg = match result {
+            Ok(()) => {
+                let mut args = HashMap::new();
+                args.insert("path", path);
+                loader.get_args("ui-status-chart-exported", args)
+            }
+            Err(e) => {
+                eprintln!("Chart export failed: {e:#}");
+                loader.get("ui-status-export-error")
+            }
+        };
+        let _ = self.status_bar.parts().get(0).set_text(&clean_tr(&msg));
+        Ok(())
+    }
+
+    // Filter dialog of one column: time range for the Timestamp, text / pick-list otherwise
+    fn edit_column_filter(&self, col: LogColumn) -> winsafe::AnyResult<()> {
+        let current = self.column_filters.read().expect("Lock failed").get(col).cloned();
+        let chosen = if col == LogColumn::Timestamp {
+            // Presets are fixed when chosen: a saved column filter must not drift
+            let initial = match current {
+                Some(ColumnFilter::Range(range)) => Some(TimeFilter::Between(range)),
+                _ => None,
+            };
+            let zone = self.display_zone();
+            TimeRangeWindow::new(initial, zone).show(&self.wnd)?
+                .map(|filter| filter.map(|f| ColumnFilter::Range(f.resolve(Utc::now(), zone))))
+        } else {
+            let mut values = distinct_values(&self.all_items.read().expect("Lock failed"), col);
+            values.truncate(MAX_PICK_VALUES);
+            let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+            let mut args = HashMap::new();
+            args.insert("column", clean_tr(&loader.get(col.ftl_key())));
+            let title = clean_tr(&loader.get_args("ui-column-filter-title", args));
+            ColumnFilterWindow::new(&title, values, current).show(&self.wnd)?
+        };
+        let Some(chosen) = chosen else { return Ok(()) };
+
+        self.column_filters.write().expect("Lock failed").set(col, chosen);
+        self.save_column_filters();
+        Ok(())
+    }
+
+    // Persists the header filters, refreshes the indicators and the view
+    fn save_column_filters(&self) {
+        let filters = self.column_filters.read().expect("Lock failed").clone();
+        if let Ok(mut config) = self.config.write() {
+            config.column_filters = filters;
+            let _ = config.save();
+        }
+        self.update_headers();
+        self.trigger_async_filter();
+    }
+
+    // Event sequence of the request at `view_index` in a modal window
+    fn show_session_details(&self, view_index: usize) -> winsafe::AnyResult<()> {
+        let selected = {
+            let items = self.all_items.read().expect("Lock failed");
+            let ids = self.filtered_ids.read().expect("Lock failed");
+            ids.get(view_index).and_then(|&idx| items.get(idx)).map(|req| (req.user.clone(), req.timestamp.clone(), req.events.clone()))
+        };
+        let Some((user, timestamp, events)) = selected else { return Ok(()) };
+
+        // The requests only reference their events: read them again, locks released
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        let detail = match read_events(&events) {
+            Ok(events) => events_text(&events),
+            Err(e) => format!("{}\n\n{e:#}", clean_tr(&loader.get("ui-session-unreadable"))),
+        };
+        let mut args = HashMap::new();
+        args.insert("user", user);
+        args.insert("timestamp", timestamp);
+        let title = clean_tr(&loader.get_args("ui-session-title", args));
+        SessionWindow::new(&title, detail).show(&self.wnd)
+    }
+
+    // Shows every request within `secs` of the row at `view_index`: clears the search and the errors-only mode
+    fn enter_time_window(&self, view_index: usize, secs: u32) {
+        let selected = {
+            let items = self.all_items.read().expect("Lock failed");
+            let ids = self.filtered_ids.read().expect("Lock failed");
+            ids.get(view_index).and_then(|&idx| items.get(idx)).map(|req| (req.time, req.timestamp.clone()))
+        };
+        let Some((time, timestamp)) = selected else { return };
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        let Some(center) = time else {
+            let _ = self.status_bar.parts().get(0).set_text(&clean_tr(&loader.get("ui-status-no-time")));
+            return;
+        };
+
+        if let Ok(mut config) = self.config.write() {
+            config.time_window_secs = secs;
+            let _ = config.save();
+        }
+        *self.time_window.write().expect("Lock failed") = Some(TimeRange::around(center, secs));
+        *self.show_errors.write().expect("Lock failed") = false;
+        let _ = self.btn_rejects.hwnd().SetWindowText(&loader.get("ui-btn-errors-only"));
+        self.txt_search.set_text("");
+        self.trigger_async_filter();
+
+        let mut args = HashMap::new();
+        args.insert("secs", secs.to_string());
+        args.insert("timestamp", timestamp);
+        let _ = self.status_bar.parts().get(0).set_text(&clean_tr(&loader.get_args("ui-status-time-window", args)));
+    }
+
+    // Summary + first skipped events in a message box; the full report goes to the clipboard if the user asks
+    fn show_parse_report(&self) -> winsafe::AnyResult<()> {
+        const MAX_LINES: usize = 20;
+        let text = self.parse_report.read().expect("Lock failed").to_text();
+
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        let mut shown: String = text.lines().take(MAX_LINES + 1).collect::<Vec<_>>().join("\n");
+        if text.lines().count() > MAX_LINES + 1 {
+            shown.push_str("\n...");
+        }
+        shown.push_str("\n\n");
+        shown.push_str(&clean_tr(&loader.get("ui-report-copy-prompt")));
+        let answer = self.wnd.hwnd().MessageBox(&shown, &clean_tr(&loader.get("ui-report-title")), co::MB::YESNO | co::MB::ICONINFORMATION)?;
+        if answer == co::DLGID::YES {
+            let _ = clipboard_win::set_clipboard_string(&text);
+        }
+        Ok(())
+    }
+
+    // Statistics of the rows shown (the whole dataset when nothing is filtered), also for an empty view
+    fn show_statistics(&self) -> winsafe::AnyResult<()> {
+        let (stats, total) = {
+            let items = self.all_items.read().expect("Lock failed");
+            let ids = self.filtered_ids.read().expect("Lock failed");
+            (Statistics::compute(&items, &ids, DEFAULT_TOP), items.len())
+        };
+
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        let mut args = HashMap::new();
+        args.insert("shown", stats.requests.to_string());
+        args.insert("total", total.to_string());
+        let title = clean_tr(&loader.get_args("ui-stats-title", args));
+        let mut text = stats.to_text();
+        if stats.requests == 0 {
+            text = format!("{}\n\n{text}", clean_tr(&loader.get("ui-stats-empty")));
+        }
+        StatsWindow::new(&title, text).show(&self.wnd)
+    }
+
+    // Password sprays / brute forces among all the loaded requests, with the thresholds of the config
+    fn detect_attacks(&self) -> winsafe::AnyResult<()> {
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        let (findings, rows) = {
+            let items = self.all_items.read().expect("Lock failed");
+            let config = self.config.read().expect("Lock failed");
+            let ids: Vec<usize> = (0..items.len()).collect();
+            let findings = detect_attacks(&items, &ids, &config.reason_categories, &config.detection);
+            let rows: Vec<[String; 6]> = findings.iter().map(|finding| [
+                finding.rows.first().and_then(|&i| items.get(i)).map(|req| req.timestamp.clone()).unwrap_or_default(),
+                clean_tr(&loader.get(finding.kind.ftl_key())),
+                clean_tr(&loader.get(finding.source.ftl_key())),
+                finding.value.clone(),
+                finding.users.to_string(),
+                finding.failures.to_string(),
+            ]).collect();
+            (findings, rows)
+        };
+
+        if findings.is_empty() {
+            let detection = self.config.read().expect("Lock failed").detection.clone();
+            let mut args = HashMap::new();
+            args.insert("users", detection.spray_users.to_string());
+            args.insert("failures", detection.brute_force_failures.to_string());
+            args.insert("secs", detection.window_secs.to_string());
+            let text = clean_tr(&loader.get_args("ui-findings-none", args));
+            self.wnd.hwnd().MessageBox(&text, &clean_tr(&loader.get("ui-findings-title")), co::MB::OK | co::MB::ICONINFORMATION)?;
+            return Ok(());
+        }
+
+        let mut args = HashMap::new();
+        args.insert("count", findings.len().to_string());
+        let title = clean_tr(&loader.get_args("ui-findings-count", args));
+        if let Some(finding) = FindingsWindow::new(&title, rows).show(&self.wnd)?.and_then(|i| findings.get(i)) {
+            self.show_finding(finding);
+        }
+        Ok(())
+    }
+
+    // The failures of a finding: its search query over its period, errors-only mode and time window left
+    fn show_finding(&self, finding: &Finding) {
+        let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+        *self.show_errors.write().expect("Lock failed") = false;
+        let _ = self.btn_rejects.hwnd().SetWindowText(&loader.get("ui-btn-errors-only"));
+        *self.time_window.write().expect("Lock failed") = None;
+        self.cb_regex.set_check(false);
+        // Changing the text arms the search timer: set_time_filter filters right away
+        self.txt_search.set_text(&finding.query());
+        let _ = self.wnd.hwnd().KillTimer(IDT_SEARCH_TIMER);
+        self.set_time_filter(Some(TimeFilter::Between(finding.range())));
+    }
+
     fn show_column_context_menu(&self) -> winsafe::AnyResult<isize> {
         let h_menu = winsafe::HMENU::CreatePopupMenu()?;
         let all_cols = LogColumn::all();
@@ -1427,17 +2875,88 @@ impl MyWindow {
             h_menu.AppendMenu(flags, winsafe::IdMenu::Id(2000 + i as u16), winsafe::BmpPtrStr::from_str(&text))?;
         }
 
+        // Time range (usable before loading a folder), statistics (usable on an empty view)
+        h_menu.AppendMenu(co::MF::SEPARATOR, winsafe::IdMenu::None, winsafe::BmpPtrStr::None)?;
+        h_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(2900), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-time-range"))))?;
+        h_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(2901), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-statistics"))))?;
+
+        // Header filters of the visible columns
+        let filters_now = self.column_filters.read().expect("Lock failed").clone();
+        let h_filter_menu = winsafe::HMENU::CreatePopupMenu()?;
+        for (i, col) in all_cols.iter().enumerate().filter(|(_, col)| visible_now.contains(col)) {
+            let mut flags = co::MF::STRING;
+            if filters_now.get(*col).is_some() { flags |= co::MF::CHECKED; }
+            let text = clean_tr(&loader.get(col.ftl_key()));
+            h_filter_menu.AppendMenu(flags, winsafe::IdMenu::Id(2800 + i as u16), winsafe::BmpPtrStr::from_str(&text))?;
+        }
+        if !filters_now.is_empty() {
+            h_filter_menu.AppendMenu(co::MF::SEPARATOR, winsafe::IdMenu::None, winsafe::BmpPtrStr::None)?;
+            h_filter_menu.AppendMenu(co::MF::STRING, winsafe::IdMenu::Id(2899), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-clear-column-filters"))))?;
+        }
+        h_menu.AppendMenu(co::MF::POPUP, winsafe::IdMenu::Menu(&h_filter_menu), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-column-filters"))))?;
+
+        // MAC notation (display only, applied to the loaded rows)
+        let current_notation = self.config.read().expect("Lock failed").mac_notation;
+        let h_mac_menu = winsafe::HMENU::CreatePopupMenu()?;
+        for (i, notation) in MacNotation::ALL.iter().enumerate() {
+            let mut flags = co::MF::STRING;
+            if *notation == current_notation { flags |= co::MF::CHECKED; }
+            let text = notation.format(0xaabb_ccdd_eeff).unwrap_or_else(|| clean_tr(&loader.get("ui-mac-as-logged")));
+            h_mac_menu.AppendMenu(flags, winsafe::IdMenu::Id(3000 + i as u16), winsafe::BmpPtrStr::from_str(&text))?;
+        }
+        h_menu.AppendMenu(co::MF::SEPARATOR, winsafe::IdMenu::None, winsafe::BmpPtrStr::None)?;
+        h_menu.AppendMenu(co::MF::POPUP, winsafe::IdMenu::Menu(&h_mac_menu), winsafe::BmpPtrStr::from_str(&clean_tr(&loader.get("ui-menu-mac-notation"))))?;
+
         let pt = winsafe::GetCursorPos().expect("GetCursorPos failed");
         if let Some(cmd_id) = h_menu.TrackPopupMenu(co::TPM::RETURNCMD | co::TPM::LEFTALIGN, pt, self.wnd.hwnd())? {
-            let col_idx = (cmd_id - 2000) as usize;
-            if col_idx < all_cols.len() {
-                let clicked_col = all_cols[col_idx];
-                self.toggle_column_visibility(clicked_col);
+            if cmd_id == 2900 {
+                self.choose_time_range()?;
+            } else if cmd_id == 2901 {
+                self.show_statistics()?;
+            } else if cmd_id == 2899 {
+                *self.column_filters.write().expect("Lock failed") = ColumnFilters::default();
+                self.save_column_filters();
+            } else if (2800..2899).contains(&cmd_id) {
+                if let Some(&col) = all_cols.get((cmd_id - 2800) as usize) {
+                    self.edit_column_filter(col)?;
+                }
+            } else if cmd_id >= 3000 {
+                if let Some(&notation) = MacNotation::ALL.get((cmd_id - 3000) as usize) {
+                    self.set_mac_notation(notation);
+                }
+            } else {
+                let col_idx = (cmd_id - 2000) as usize;
+                if col_idx < all_cols.len() {
+                    let clicked_col = all_cols[col_idx];
+                    self.toggle_column_visibility(clicked_col);
+                }
             }
         }
         Ok(0)
     }
 
+    fn set_mac_notation(&self, notation: MacNotation) {
+        if let Ok(mut config) = self.config.write() {
+            config.mac_notation = notation;
+            let _ = config.save();
+        }
+        let respelled = {
+            let mut items = self.all_items.write().expect("Lock failed");
+            notation.apply_all(&mut items);
+            bump_generation(&self.items_generation);
+            // The MAC pick-list holds display texts: they must follow the notation
+            self.column_filters.write().expect("Lock failed").respell_macs(&items, notation)
+        };
+        // MAC texts changed: the index must match them again
+        rebuild_index(&self.all_items, &self.items_generation, &self.search_index);
+        // Matches and MAC sort order change with the texts: filter again
+        if respelled {
+            self.save_column_filters();
+        } else {
+            self.trigger_async_filter();
+        }
+    }
+
     fn toggle_column_visibility(&self, col: LogColumn) {
         let mut visible = self.visible_cols.write().expect("Lock failed");
         if visible.contains(&col) {
@@ -1559,12 +3078,13 @@ impl MyWindow {
         // 1. Get handle of the Header control (title bar)
         let h_header = self.lst_logs.header().map(|h| h.hwnd()).expect("Failed to get header");
 
-        let (visible, sort_col, sort_desc) = {
+        let (visible, sort, filters) = {
             let v = self.visible_cols.read().expect("Lock failed").clone();
-            let sc = *self.sort_col.read().expect("Lock failed");
-            let sd = *self.sort_desc.read().expect("Lock failed");
-            (v, sc, sd)
+            let s = self.sort.read().expect("Lock failed").clone();
+            let f = self.column_filters.read().expect("Lock failed").clone();
+            (v, s, f)
         };
+        let multi_level = sort.keys().len() > 1;
 
         let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
 
@@ -1578,17 +3098,25 @@ impl MyWindow {
             // Define base format (Left alignment)
             hdi.fmt = co::HDF::LEFT | co::HDF::STRING; 
 
-            // 3. Add NATIVE arrow if it's the sorted column
-            if col == sort_col {
-                if sort_desc {
+            // 3. Add NATIVE arrow on every sort key
+            let level = sort.level(col);
+            if let Some((_, key)) = level {
+                if key.descending {
                     hdi.fmt |= co::HDF::SORTDOWN;
                 } else {
                     hdi.fmt |= co::HDF::SORTUP;
                 }
             }
             
-            // 4. Define text (WITHOUT the arrow this time!)
-            let text = clean_tr(&loader.get(col.ftl_key()));
+            // 4. Define text (WITHOUT the arrow this time!), marked when the column is filtered,
+            //    numbered by sort level when there are several keys
+            let mut text = clean_tr(&loader.get(col.ftl_key()));
+            if filters.get(col).is_some() {
+                text = format!("\u{1F50D} {text}");
+            }
+            if let Some((n, _)) = level.filter(|_| multi_level) {
+                text = format!("{text} ({})", n + 1);
+            }
             let mut wtext = winsafe::WString::from_str(&text);
             hdi.set_pszText(Some(&mut wtext));
 
@@ -1602,291 +3130,26 @@ impl MyWindow {
     }
 }
 
-// --- LOGIC FUNCTIONS ---
-
-fn apply_filter_logic(
-    all_items: &Arc<RwLock<Vec<RadiusRequest>>>,
-    filtered_ids: &Arc<RwLock<Vec<usize>>>,
-    query: &str,
-    show_errors_only: bool,
-    sort_col: LogColumn,
-    sort_descending: bool,
-) {
-    let q = query.trim().to_ascii_lowercase(); // Optimization: lowercase the query once
-    
-    // 1. Data reading (Read Lock)
-    let items = all_items.read().expect("Lock failed");
-        
-    let mut failed_session_ids = HashSet::new();
-    if show_errors_only {
-        for item in items.iter() {
-            if item.resp_type == "Access-Reject" && !item.session_id.is_empty() {
-                failed_session_ids.insert(item.session_id.clone());
-            }
-        }
-    }
-
-    // 2. Filtering (Local collection)
-    let mut ids: Vec<usize> = (0..items.len())
-        .filter(|&i| {
-            let item = &items[i];
-            if show_errors_only {
-                if item.session_id.is_empty() || !failed_session_ids.contains(&item.session_id) {
-                    return false;
-                }
-                if item.resp_type == "Access-Accept" || item.resp_type == "Accounting-Response" {
-                    return false;
-                }
-            }
-            if q.is_empty() { return true; }
-            
-            // Optimization: pass string already in lowercase
-            item.matches(&q)
-        })
-        .collect();
-
-    // 3. Sorting (On the local collection)
-    ids.sort_unstable_by(|&a_idx, &b_idx| {
-        let a = &items[a_idx];
-        let b = &items[b_idx];
-        let ord = match sort_col {
-            LogColumn::Timestamp => a.timestamp.cmp(&b.timestamp),
-            LogColumn::Type => a.req_type.cmp(&b.req_type),
-            LogColumn::Server => a.server.cmp(&b.server),
-            LogColumn::ApIp => a.ap_ip.cmp(&b.ap_ip),
-            LogColumn::ApName => a.ap_name.cmp(&b.ap_name),
-            LogColumn::Mac => a.mac.cmp(&b.mac),
-            LogColumn::User => a.user.cmp(&b.user),
-            LogColumn::ResponseType => a.resp_type.cmp(&b.resp_type),
-            LogColumn::Reason => {
-                let r_a = if a.reason.is_empty() { &a.resp_type } else { &a.reason };
-                let r_b = if b.reason.is_empty() { &b.resp_type } else { &b.reason };
-                r_a.cmp(r_b)
-            }
-            LogColumn::Session => a.session_id.cmp(&b.session_id),
-        };
-        if sort_descending { ord.reverse() } else { ord }
-    });
-    
-    // Release read lock before write lock
-    drop(items);
-
-    // 4. Result writing (Write Lock - brief)
-    let mut filt_guard = filtered_ids.write().expect("Lock failed");
-    *filt_guard = ids;
-}
-
-
-// ... (parse_full_logic, process_group, map_packet_type, map_reason, clean_tr, main remain the same)
-// I include them so the code is complete.
-
-fn parse_full_logic(path: &str, hwnd: Option<SafeHWND>) -> anyhow::Result<(Vec<RadiusRequest>, usize)> {
-    let content = fs::read_to_string(path)?;
-    let total_len = content.len() as u64;
-    
-    use quick_xml::reader::Reader;
-    use quick_xml::events::Event as XmlEvent;
-
-    let mut reader = Reader::from_str(&content);
-    let mut buf = Vec::new();
-    let mut event_blobs = Vec::new();
-    let mut last_progress = 0u8;
-
-    // Send initial 0% progress to show the progress bar
-    if let Some(sh) = hwnd {
-        let _ = post_message_safe(&sh.h(), msg::WndMsg {
-            msg_id: WM_PROGRESS,
-            wparam: 0,
-            lparam: 0,
-        });
-    }
-
-    // --- PHASE 1: SEQUENTIAL EXTRACTION (0% to 50%) ---
-    loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(XmlEvent::Start(ref e)) if e.name().as_ref() == b"Event" => {
-                let start_pos = reader.buffer_position() - (e.name().as_ref().len() as u64) - 2;
-                reader.read_to_end_into(e.name(), &mut Vec::new())?;
-                let end_pos = reader.buffer_position();
-                event_blobs.push(content[start_pos as usize..end_pos as usize].to_string());
-
-                // --- PROGRESS REPORTING (0-50%) ---
-                if let Some(sh) = hwnd {
-                    let h = sh.h();
-                    let pct = ((end_pos * 50) / total_len) as u8;
-                    if pct > last_progress {
-                        let _ = post_message_safe(&h, msg::WndMsg {
-                            msg_id: WM_PROGRESS,
-                            wparam: pct as usize,
-                            lparam: 0,
-                        });
-                        last_progress = pct;
-                    }
-                }
-            }
-            Ok(XmlEvent::Eof) => break,
-            _ => (),
-        }
-        buf.clear();
-    }
-
-    let raw_event_count = event_blobs.len();
-    if event_blobs.is_empty() {
-        return Ok((Vec::new(), 0));
-    }
-
-    // --- PHASE 2: PARALLELIZATION WITH RAYON (50% to 100%) ---
-    let processed_count = Arc::new(AtomicUsize::new(0));
-    let total_blobs = raw_event_count;
-    let count_clone = processed_count.clone();
-
-    // Rayon into_par_iter requires Send/Sync. SafeHWND handles this.
-    let sync_hwnd_opt = hwnd;
-
-    let events_all: Vec<Event> = event_blobs.into_par_iter()
-        .map(move |blob| {
-            let res = from_str::<Event>(&blob).ok();
-            
-            if let Some(sh) = sync_hwnd_opt {
-                let h = sh.h();
-                let current = count_clone.fetch_add(1, Ordering::Relaxed) + 1;
-                let step = (total_blobs / 100).max(1);
-                
-                if current.is_multiple_of(step) {
-                    let pct = 50 + ((current * 50) / total_blobs);
-                    let _ = post_message_safe(&h, msg::WndMsg {
-                        msg_id: WM_PROGRESS,
-                        wparam: pct,
-                        lparam: 0,
-                    });
-                }
-            }
-            res
-        })
-        .flatten()
-        .collect();
-
-    // ... (rest is unchanged) ...
-
-    let mut groups: Vec<Vec<Event>> = Vec::new();
-    let mut class_map: HashMap<String, usize> = HashMap::new();
-
-    for ev in events_all {
-        let key_opt = ev.class.as_deref()
-            .or(ev.acct_session_id.as_deref())
-            .filter(|s: &&str| !s.is_empty());
-        
-        if let Some(k) = key_opt {
-            if let Some(&idx) = class_map.get(k) {
-                groups[idx].push(ev);
-            } else {
-                class_map.insert(k.to_string(), groups.len());
-                groups.push(vec![ev]);
-            }
-        } else {
-            groups.push(vec![ev]);
-        }
-    }
-
-    let requests: Vec<RadiusRequest> = groups.into_par_iter()
-        .map(|g| process_group(&g))
-        .collect();
-
-    // Send 100% progress to hide the progress bar
-    if let Some(sh) = hwnd {
-        let _ = post_message_safe(&sh.h(), msg::WndMsg {
-            msg_id: WM_PROGRESS,
-            wparam: 100,
-            lparam: 0,
-        });
-    }
-        
-    Ok((requests, raw_event_count))
-}
-
-fn process_group(group: &[Event]) -> RadiusRequest {
-    let mut req = RadiusRequest::default();
-    for event in group {
-        let p_type = event.packet_type.as_deref().unwrap_or("");
-        if p_type == "1" || p_type == "4" {
-            if let Some(val) = &event.timestamp { req.timestamp.clone_from(val); }
-            if let Some(val) = &event.acct_session_id { req.session_id.clone_from(val); }
-            if let Some(val) = &event.server { req.server.clone_from(val); }
-            if let Some(val) = &event.ap_ip { req.ap_ip.clone_from(val); }
-            if let Some(val) = &event.client_friendly_name { req.ap_name.clone_from(val); }
-            else if let Some(val) = &event.ap_name { req.ap_name.clone_from(val); }
-            if let Some(val) = &event.mac { req.mac.clone_from(val); }
-            if let Some(val) = &event.class { req.class_id.clone_from(val); }
-            req.req_type = map_packet_type(p_type);
-            
-            // FIX: We take the "Unknown" string out of the loop or hardcode it
-            // Avoid accessing LANGUAGE_LOADER in parallel code (Rayon)
-            if let Some(user) = &event.sam_account { req.user.clone_from(user); } 
-            else if let Some(user) = &event.user_name { req.user.clone_from(user); } 
-            else { 
-                req.user = "Unknown User".to_string(); 
-            }
-        } else {
-            let this_resp_type = map_packet_type(p_type);
-            let code = event.reason_code.as_deref().unwrap_or("0");
-            if req.reason.is_empty() || code != "0" {
-                 req.resp_type = this_resp_type.clone();
-                 req.reason = map_reason(code);
-            }
-            match p_type {
-                "2" => req.bg_color = Some((25, 135, 84)),   // Modern Success (Bootstrap Green)
-                "3" => req.bg_color = Some((220, 53, 69)),   // Modern Danger (Bootstrap Red)
-                _ => {},
-            }
-        }
-    }
-    req
-}
-
-fn map_packet_type(code: &str) -> String {
-    match code {
-        "1" => "Access-Request".to_string(),
-        "2" => "Access-Accept".to_string(),
-        "3" => "Access-Reject".to_string(),
-        "4" => "Accounting-Request".to_string(),
-        "5" => "Accounting-Response".to_string(),
-        "11" => "Access-Challenge".to_string(),
-        _ => format!("Type {code}"),
-    }
+// Status bar text of a time filter: preset name or bounds in `zone`
+fn describe_time_filter(filter: TimeFilter, zone: Zone) -> String {
+    let loader = LANGUAGE_LOADER.get().expect("Loader not initialized");
+    let TimeFilter::Between(range) = filter else { return clean_tr(&loader.get(time_preset_key(filter))) };
+    let bound = |t: Option<chrono::DateTime<Utc>>| {
+        t.map_or_else(|| "…".to_string(), |t| zone.to_wall_clock(t).format("%Y-%m-%d %H:%M:%S").to_string())
+    };
+    format!("{} → {}", bound(range.from), bound(range.to))
 }
 
-
-// Static cache for reason map
-static REASON_MAP: OnceLock<HashMap<String, String>> = OnceLock::new();
-
-fn get_reason_map() -> &'static HashMap<String, String> {
-    REASON_MAP.get_or_init(|| {
-        // `include_str!` embeds the JSON file at compile time.
-        // Ensure the reason_codes.json file is next to main.rs
-        let json_content = include_str!("reason_codes.json");
-        
-        match serde_json::from_str(json_content) {
-            Ok(map) => map,
-            Err(e) => {
-                eprintln!("Critical error loading reason_codes.json: {}", e);
-                HashMap::new() // Returns an empty map on error to avoid crash
-            }
-        }
-    })
+// Indexes the loaded rows; takes the locks in the order of the filter worker (items, then index)
+fn rebuild_index(all_items: &RwLock<Vec<RadiusRequest>>, generation: &AtomicU64, index: &RwLock<SearchIndex>) {
+    let items = all_items.read().expect("Lock failed");
+    let built = SearchIndex::build(&items, generation.load(Ordering::SeqCst));
+    *index.write().expect("Lock failed") = built;
 }
 
-fn map_reason(code: &str) -> String {
-    // Direct search in the HashMap (O(1))
-    let reason = get_reason_map()
-        .get(code)
-        .cloned()
-        .unwrap_or_else(|| format!("Code {}", code));
-    
-    if code != "0" {
-        format!("{} ({})", reason, code)
-    } else {
-        reason
-    }
+// Marks all_items as changed (call with its write lock held); returns the new generation
+fn bump_generation(generation: &AtomicU64) -> u64 {
+    generation.fetch_add(1, Ordering::SeqCst) + 1
 }
 
 fn clean_tr(s: &str) -> String {